@@ -1,6 +1,6 @@
 use as_any::Downcast;
 use simple_ui::{
-    ui::*, window::TWindowDelegate, Color, IPoint, IRect, ISize, Window, WindowBuilder,
+    ui::*, window::TWindowDelegate, Color, EUnit, IPoint, IRect, ISize, Window, WindowBuilder,
 };
 use skia_safe::{Canvas, Paint, Rect};
 
@@ -73,8 +73,8 @@ impl TWindowDelegate for WndDelegate {
 }
 
 struct ButtonCallback1 {}
-impl ui_button::TButtonDelegate for ButtonCallback1 {
-    fn on_click(&self) {}
+impl TEventHandler for ButtonCallback1 {
+    fn on_event(&mut self, _ctx: &mut EventCtx, _event: &mut UIEvent) {}
 }
 
 fn create_root() -> Container {
@@ -84,6 +84,7 @@ fn create_root() -> Container {
     ui_root.ctrl.inner.padding = Some(IRect::new(6, 6, 6, 6));
     ui_root.ctrl.inner.border_width = 2;
     ui_root.ctrl.inner.round = 14;
+    ui_root.clip = EClip::Round;
     ui_root.ctrl.styles.default.border_color = Some(Color::DARK_GRAY);
     ui_root.ctrl.styles.default.bg_color = Some(Color::WHITE);
 
@@ -94,13 +95,13 @@ fn create_tool_bar() -> Container {
     let mut ui_toolbar = Container::new(LayoutHorizontal::new());
     {
         ui_toolbar.ctrl.inner.name = "UIToobar".to_string();
-        ui_toolbar.ctrl.inner.size.height = Some(25);
+        ui_toolbar.ctrl.inner.size.height = Some(EUnit::Dp(25));
     }
     {
         let mut ui_text = Text::new("TODO 这里需要放各种工具", 12);
         ui_text.ctrl.inner.name = "Toolbar_TODO".to_string();
-        ui_text.ctrl.inner.pos.left = Some(5);
-        ui_text.ctrl.inner.pos.top = Some(5);
+        ui_text.ctrl.inner.pos.left = Some(EUnit::Dp(5));
+        ui_text.ctrl.inner.pos.top = Some(EUnit::Dp(5));
         ui_text.ctrl.inner.margin = Some(IRect::new(20, 0, 0, 10));
 
         ui_toolbar.append_child(Box::new(ui_text));
@@ -113,7 +114,7 @@ fn create_sidebar() -> Container {
     let mut ui_siderbar = Container::new(LayoutVertical::new());
     {
         ui_siderbar.ctrl.inner.name = "UISider".to_string();
-        ui_siderbar.ctrl.inner.size.width = Some(100);
+        ui_siderbar.ctrl.inner.size.width = Some(EUnit::Dp(100));
     }
 
     ui_siderbar
@@ -144,12 +145,15 @@ fn create_ctx() -> Container {
 fn create_status_bar() -> Container {
     let mut ui_status = Container::new(LayoutHorizontal::new());
     ui_status.ctrl.inner.name = "UIStatus".to_string();
-    ui_status.ctrl.inner.size.height = Some(16);
+    ui_status.ctrl.inner.size.height = Some(EUnit::Dp(16));
 
     {
         let mut ui_txt_mouse_pos = Text::new("        ", 12);
         ui_txt_mouse_pos.ctrl.inner.name = "Status_CurCtrl".to_string();
-        ui_txt_mouse_pos.ctrl.inner.size.width = Some(160);
+        ui_txt_mouse_pos.ctrl.inner.size.width = Some(EUnit::Dp(160));
+        let style = &mut ui_txt_mouse_pos.ctrl.styles.default;
+        style.align_y = EAlign::Center;
+        style.overflow = EOverflow::EllipsisMiddle;
         ui_txt_mouse_pos.ctrl.inner.margin = Some(IRect::new(20, 0, 0, 0));
 
         ui_status.append_child(Box::new(ui_txt_mouse_pos));
@@ -158,7 +162,10 @@ fn create_status_bar() -> Container {
     {
         let mut ui_txt_mouse_pos = Text::new("   0,   0", 12);
         ui_txt_mouse_pos.ctrl.inner.name = "Status_MousePos".to_string();
-        ui_txt_mouse_pos.ctrl.inner.size.width = Some(80);
+        ui_txt_mouse_pos.ctrl.inner.size.width = Some(EUnit::Dp(80));
+        let style = &mut ui_txt_mouse_pos.ctrl.styles.default;
+        style.align_x = EAlign::End;
+        style.align_y = EAlign::Center;
         ui_txt_mouse_pos.ctrl.inner.margin = Some(IRect::new(20, 0, 0, 0));
 
         ui_status.append_child(Box::new(ui_txt_mouse_pos));
@@ -189,10 +196,10 @@ fn create() -> Container {
 
         // 输出框
         {
-            // TODO 可以换行的, skia默认不支持,需要自己处理
-            let mut ui_text_info = Text::new("", 12);
+            let mut ui_text_info = TextArea::new(12);
             ui_text_info.ctrl.inner.name = "TextInfo".to_string();
-            ui_text_info.ctrl.inner.size.height = Some(150);
+            ui_text_info.ctrl.inner.padding = Some(IRect::new(4, 4, 4, 4));
+            ui_text_info.ctrl.inner.size.height = Some(EUnit::Dp(150));
 
             ui_context2.append_child(Box::new(ui_text_info));
         }
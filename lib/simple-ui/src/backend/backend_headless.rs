@@ -0,0 +1,42 @@
+use skia_safe::{surfaces, ISize, Image, Surface};
+
+use crate::backend::TBackend;
+
+/// Draws into a raster surface and keeps the last frame, used without a real window.
+pub struct HeadlessBackend {
+    surface: Option<Surface>,
+    last_frame: Option<Image>,
+}
+
+impl HeadlessBackend {
+    pub fn new(size: ISize) -> Self {
+        Self {
+            surface: surfaces::raster_n32_premul(size),
+            last_frame: None,
+        }
+    }
+}
+
+impl TBackend for HeadlessBackend {
+    fn resize(&mut self, size: ISize) {
+        let cur = self
+            .surface
+            .as_ref()
+            .map(|s| ISize::new(s.width(), s.height()));
+        if cur != Some(size) {
+            self.surface = surfaces::raster_n32_premul(size);
+        }
+    }
+
+    fn begin_frame(&mut self) -> Option<Surface> {
+        self.surface.clone()
+    }
+
+    fn end_frame(&mut self, mut surface: Surface) {
+        self.last_frame = Some(surface.image_snapshot());
+    }
+
+    fn snapshot(&mut self) -> Option<Image> {
+        self.last_frame.clone()
+    }
+}
@@ -0,0 +1,114 @@
+use cocoa::{appkit::NSView, base::id as cocoa_id};
+use core_graphics_types::geometry::CGSize;
+use foreign_types_shared::{ForeignType, ForeignTypeRef};
+use metal::{Device, MTLPixelFormat, MetalDrawable, MetalLayer};
+use objc::runtime::YES;
+use skia_safe::{
+    gpu::{self, mtl, BackendRenderTarget, DirectContext, SurfaceOrigin},
+    ColorType, ISize, Surface,
+};
+use winit::platform::macos::WindowExtMacOS;
+
+use crate::{backend::TBackend, window::WinitWindow};
+
+pub struct MetalBackend {
+    context: DirectContext,
+
+    metal_layer: MetalLayer,
+    command_queue: metal::CommandQueue,
+
+    drawable: Option<MetalDrawable>,
+}
+
+impl MetalBackend {
+    pub fn new(window: &WinitWindow) -> Self {
+        let device = Device::system_default().expect("no device found");
+
+        let metal_layer = {
+            let draw_size = window.inner_size();
+            let layer = MetalLayer::new();
+            layer.set_device(&device);
+            layer.set_pixel_format(MTLPixelFormat::BGRA8Unorm);
+            layer.set_presents_with_transaction(false);
+
+            unsafe {
+                let view = window.ns_view() as cocoa_id;
+                view.setWantsLayer(YES);
+                view.setLayer(layer.as_ref() as *const _ as _);
+            }
+            layer.set_drawable_size(CGSize::new(draw_size.width as f64, draw_size.height as f64));
+            layer
+        };
+
+        let command_queue = device.new_command_queue();
+
+        let backend = unsafe {
+            mtl::BackendContext::new(
+                device.as_ptr() as mtl::Handle,
+                command_queue.as_ptr() as mtl::Handle,
+                std::ptr::null(),
+            )
+        };
+
+        let context = DirectContext::new_metal(&backend, None).unwrap();
+
+        Self {
+            context,
+            metal_layer,
+            command_queue,
+            drawable: None,
+        }
+    }
+}
+
+impl TBackend for MetalBackend {
+    fn resize(&mut self, size: ISize) {
+        self.metal_layer
+            .set_drawable_size(CGSize::new(size.width as f64, size.height as f64));
+    }
+
+    fn begin_frame(&mut self) -> Option<Surface> {
+        let drawable = self.metal_layer.next_drawable()?.to_owned();
+        let drawable_size = {
+            let size = self.metal_layer.drawable_size();
+            ISize::new(size.width as i32, size.height as i32)
+        };
+
+        let surface = unsafe {
+            let texture_info = mtl::TextureInfo::new(drawable.texture().as_ptr() as mtl::Handle);
+
+            let backend_render_target = BackendRenderTarget::new_metal(
+                (drawable_size.width, drawable_size.height),
+                1,
+                &texture_info,
+            );
+
+            gpu::surfaces::wrap_backend_render_target(
+                &mut self.context,
+                &backend_render_target,
+                SurfaceOrigin::TopLeft,
+                ColorType::BGRA8888,
+                None,
+                None,
+            )
+        };
+
+        if surface.is_some() {
+            self.drawable = Some(drawable);
+        }
+        surface
+    }
+
+    fn end_frame(&mut self, surface: Surface) {
+        self.context.flush_and_submit();
+        drop(surface);
+
+        let drawable = match self.drawable.take() {
+            Some(d) => d,
+            None => return,
+        };
+        let command_buffer = self.command_queue.new_command_buffer();
+        command_buffer.present_drawable(&drawable);
+        command_buffer.commit();
+    }
+}
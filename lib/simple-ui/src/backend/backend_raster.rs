@@ -0,0 +1,83 @@
+use skia_safe::{surfaces, AlphaType, ColorType, ISize, ImageInfo, Surface};
+use softbuffer::{Context, Surface as FrameBuffer};
+use std::num::NonZeroU32;
+
+use crate::{backend::TBackend, window::WinitWindow};
+
+pub struct RasterBackend {
+    _context: Context,
+    frame_buffer: FrameBuffer,
+
+    size: ISize,
+    surface: Option<Surface>,
+}
+
+impl RasterBackend {
+    pub fn new(window: &WinitWindow) -> Self {
+        let context = unsafe { Context::new(window) }.expect("softbuffer context");
+        let frame_buffer =
+            unsafe { FrameBuffer::new(&context, window) }.expect("softbuffer surface");
+
+        let draw_size = window.inner_size();
+        let mut backend = Self {
+            _context: context,
+            frame_buffer,
+
+            size: ISize::default(),
+            surface: None,
+        };
+        backend.resize(ISize::new(draw_size.width as i32, draw_size.height as i32));
+        backend
+    }
+}
+
+impl TBackend for RasterBackend {
+    fn resize(&mut self, size: ISize) {
+        if size == self.size {
+            return;
+        }
+        self.size = size;
+
+        let (width, height) = match (
+            NonZeroU32::new(size.width.max(0) as u32),
+            NonZeroU32::new(size.height.max(0) as u32),
+        ) {
+            (Some(w), Some(h)) => (w, h),
+            _ => {
+                // minimized
+                self.surface = None;
+                return;
+            }
+        };
+
+        self.frame_buffer.resize(width, height).unwrap();
+        self.surface = surfaces::raster_n32_premul(size);
+    }
+
+    fn begin_frame(&mut self) -> Option<Surface> {
+        self.surface.clone()
+    }
+
+    fn end_frame(&mut self, mut surface: Surface) {
+        let mut buffer = match self.frame_buffer.buffer_mut() {
+            Ok(b) => b,
+            Err(_) => return,
+        };
+
+        // softbuffer wants 0RGB in native endian, read as BGRA8888 bytes and
+        // turned into a u32 below
+        let info = ImageInfo::new(self.size, ColorType::BGRA8888, AlphaType::Premul, None);
+        let row_bytes = self.size.width as usize * 4;
+        let pixels = unsafe {
+            std::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut u8, buffer.len() * 4)
+        };
+        if !surface.read_pixels(&info, pixels, row_bytes, (0, 0)) {
+            return;
+        }
+        for px in buffer.iter_mut() {
+            *px = u32::from_le_bytes(px.to_ne_bytes()) & 0x00ff_ffff;
+        }
+
+        buffer.present().unwrap();
+    }
+}
@@ -0,0 +1,49 @@
+mod backend_headless;
+#[cfg(all(feature = "metal", target_os = "macos"))]
+mod backend_metal;
+#[cfg(all(feature = "raster", not(all(feature = "metal", target_os = "macos"))))]
+mod backend_raster;
+
+#[cfg(not(any(all(feature = "metal", target_os = "macos"), feature = "raster")))]
+compile_error!("simple-ui needs a render backend, enable the `metal` (macOS) or `raster` feature");
+
+use crate::{window::WinitWindow, ISize};
+use skia_safe::{Image, Surface};
+
+pub use backend_headless::HeadlessBackend;
+
+pub trait TBackend {
+    /// drawable size changed, `size` is in physical pixels
+    fn resize(&mut self, size: ISize);
+
+    /// returns the surface the next frame is drawn into
+    fn begin_frame(&mut self) -> Option<Surface>;
+    /// flushes and presents the surface returned by `begin_frame`
+    fn end_frame(&mut self, surface: Surface);
+
+    /// the last presented frame, only backends that keep it return something
+    fn snapshot(&mut self) -> Option<Image> {
+        None
+    }
+}
+
+/// Metal is preferred when it is available, otherwise the raster backend is used.
+#[cfg(all(feature = "metal", target_os = "macos"))]
+pub fn create(window: &WinitWindow) -> Box<dyn TBackend> {
+    Box::new(backend_metal::MetalBackend::new(window))
+}
+
+#[cfg(all(feature = "raster", not(all(feature = "metal", target_os = "macos"))))]
+pub fn create(window: &WinitWindow) -> Box<dyn TBackend> {
+    Box::new(backend_raster::RasterBackend::new(window))
+}
+
+#[cfg(all(feature = "metal", target_os = "macos"))]
+pub fn autoreleasepool<T, F: FnOnce() -> T>(f: F) -> T {
+    objc::rc::autoreleasepool(f)
+}
+
+#[cfg(not(all(feature = "metal", target_os = "macos")))]
+pub fn autoreleasepool<T, F: FnOnce() -> T>(f: F) -> T {
+    f()
+}
@@ -0,0 +1,90 @@
+use std::cell::RefCell;
+
+/// Where copy / cut / paste of the text controls go, see `set_clipboard`.
+pub trait TClipboard {
+    fn get_text(&mut self) -> Option<String>;
+    fn set_text(&mut self, text: &str);
+}
+
+/// Only visible inside the process, the default where there is no system clipboard.
+#[derive(Default)]
+pub struct MemoryClipboard {
+    text: Option<String>,
+}
+
+impl TClipboard for MemoryClipboard {
+    fn get_text(&mut self) -> Option<String> {
+        self.text.clone()
+    }
+    fn set_text(&mut self, text: &str) {
+        self.text = Some(text.to_string());
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub struct SystemClipboard {}
+
+#[cfg(target_os = "macos")]
+impl TClipboard for SystemClipboard {
+    fn get_text(&mut self) -> Option<String> {
+        use cocoa::{
+            appkit::{NSPasteboard, NSPasteboardTypeString},
+            base::nil,
+            foundation::NSString,
+        };
+
+        unsafe {
+            let pb = NSPasteboard::generalPasteboard(nil);
+            let s = pb.stringForType(NSPasteboardTypeString);
+            if s == nil {
+                return None;
+            }
+
+            let bytes = std::slice::from_raw_parts(s.UTF8String() as *const u8, s.len());
+            Some(String::from_utf8_lossy(bytes).into_owned())
+        }
+    }
+
+    fn set_text(&mut self, text: &str) {
+        use cocoa::{
+            appkit::{NSPasteboard, NSPasteboardTypeString},
+            base::nil,
+            foundation::NSString,
+        };
+        use objc::{msg_send, sel, sel_impl};
+
+        unsafe {
+            let pb = NSPasteboard::generalPasteboard(nil);
+            let s = NSString::alloc(nil).init_str(text);
+            pb.clearContents();
+            pb.setString_forType(s, NSPasteboardTypeString);
+            let _: () = msg_send![s, release];
+        }
+    }
+}
+
+fn default_clipboard() -> Box<dyn TClipboard> {
+    #[cfg(target_os = "macos")]
+    let c: Box<dyn TClipboard> = Box::new(SystemClipboard {});
+    #[cfg(not(target_os = "macos"))]
+    let c: Box<dyn TClipboard> = Box::<MemoryClipboard>::default();
+    c
+}
+
+thread_local! {
+    static CLIPBOARD: RefCell<Box<dyn TClipboard>> = RefCell::new(default_clipboard());
+}
+
+/// Replaces the clipboard of the current thread, the UI thread in practice.
+/// Tests use a `MemoryClipboard` to stay away from the system one.
+pub fn set_clipboard(c: Box<dyn TClipboard>) {
+    CLIPBOARD.with(|v| *v.borrow_mut() = c);
+}
+
+pub fn get_text() -> Option<String> {
+    CLIPBOARD.with(|v| v.borrow_mut().get_text())
+}
+
+pub fn set_text(text: &str) {
+    CLIPBOARD.with(|v| v.borrow_mut().set_text(text));
+}
@@ -0,0 +1,330 @@
+use crate::utils::ScaleDpi;
+use skia_safe::{
+    font_style::{Slant, Weight, Width},
+    textlayout::{FontCollection, TypefaceFontProvider},
+    Canvas, Font, FontMgr, FontStyle, Paint, Point, Typeface,
+};
+use std::{cell::RefCell, collections::HashMap, io, path::Path};
+
+/// Tried in order when a family is not set or not found, and for the chars
+/// a font has no glyph for. CJK fonts first, they cover latin as well.
+const DEFAULT_FAMILIES: &[&str] = &[
+    "PingFang SC",
+    "Microsoft YaHei",
+    "Noto Sans CJK SC",
+    "Noto Sans SC",
+    "Source Han Sans SC",
+    "WenQuanYi Micro Hei",
+    "Helvetica Neue",
+    "Segoe UI",
+    "Noto Sans",
+    "DejaVu Sans",
+    "Apple Color Emoji",
+    "Segoe UI Emoji",
+    "Noto Color Emoji",
+];
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ESlant {
+    Upright,
+    Italic,
+    Oblique,
+}
+
+/// The font of a text control, part of its style.
+#[derive(PartialEq, Clone, Debug)]
+pub struct FontDesc {
+    /// tried before the fallback families
+    pub family: Option<String>,
+    pub size: i32,
+    /// 100 thin, 400 normal, 700 bold, 900 black
+    pub weight: i32,
+    pub slant: ESlant,
+}
+
+impl FontDesc {
+    pub fn new(size: i32) -> Self {
+        Self {
+            size,
+            ..Default::default()
+        }
+    }
+
+    pub fn font_style(&self) -> FontStyle {
+        let slant = match self.slant {
+            ESlant::Upright => Slant::Upright,
+            ESlant::Italic => Slant::Italic,
+            ESlant::Oblique => Slant::Oblique,
+        };
+        FontStyle::new(Weight::from(self.weight), Width::NORMAL, slant)
+    }
+
+    pub(crate) fn make_font(&self, dpi: &ScaleDpi) -> Font {
+        let tf = get_typeface(self.family.as_deref(), self.font_style());
+        let mut font = Font::from_typeface(tf, dpi.scale(self.size) as f32);
+        font.set_subpixel(true);
+        font
+    }
+}
+
+impl Default for FontDesc {
+    fn default() -> Self {
+        Self {
+            family: None,
+            size: 12,
+            weight: 400,
+            slant: ESlant::Upright,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////
+/// Manager
+pub struct FontManager {
+    mgr: FontMgr,
+    families: Vec<String>,
+    // registered by the app, found before the system fonts
+    custom: Vec<Typeface>,
+    // fallback for a char by (char, weight, slant)
+    chars: HashMap<(char, i32, i32), Option<Typeface>>,
+    // for paragraphs, dropped when fonts are registered
+    collection: Option<FontCollection>,
+}
+
+impl Default for FontManager {
+    fn default() -> Self {
+        Self {
+            mgr: FontMgr::new(),
+            families: DEFAULT_FAMILIES.iter().map(|f| f.to_string()).collect(),
+            custom: Vec::new(),
+            chars: HashMap::new(),
+            collection: None,
+        }
+    }
+}
+
+impl FontManager {
+    fn find_family(&self, family: &str, style: FontStyle) -> Option<Typeface> {
+        let custom = self
+            .custom
+            .iter()
+            .filter(|tf| tf.family_name().eq_ignore_ascii_case(family))
+            .min_by_key(|tf| {
+                let s = tf.font_style();
+                (*s.weight() - *style.weight()).abs()
+                    + if s.slant() == style.slant() { 0 } else { 1000 }
+            });
+        match custom {
+            Some(tf) => Some(tf.clone()),
+            None => self.mgr.match_family_style(family, style),
+        }
+    }
+
+    /// The family if there is one, else the first fallback family found,
+    /// else whatever the system has.
+    pub fn get_typeface(&self, family: Option<&str>, style: FontStyle) -> Typeface {
+        family
+            .and_then(|f| self.find_family(f, style))
+            .or_else(|| {
+                self.families
+                    .iter()
+                    .find_map(|f| self.find_family(f, style))
+            })
+            .or_else(|| self.mgr.legacy_make_typeface(None::<&str>, style))
+            .unwrap_or_default()
+    }
+
+    /// A typeface with a glyph for `c`, registered fonts and the fallback
+    /// families first.
+    pub fn get_fallback(&mut self, c: char, style: FontStyle) -> Option<Typeface> {
+        let key = (c, *style.weight(), style.slant() as i32);
+        if let Some(tf) = self.chars.get(&key) {
+            return tf.clone();
+        }
+
+        let has = |tf: &Typeface| tf.unichar_to_glyph(c as i32) != 0;
+        let tf = self
+            .custom
+            .iter()
+            .find(|tf| has(tf))
+            .cloned()
+            .or_else(|| {
+                self.families
+                    .iter()
+                    .filter_map(|f| self.mgr.match_family_style(f, style))
+                    .find(has)
+            })
+            .or_else(|| {
+                self.mgr
+                    .match_family_style_character("", style, &[], c as i32)
+            });
+
+        self.chars.insert(key, tf.clone());
+        tf
+    }
+
+    pub fn register(&mut self, bytes: &[u8]) -> Option<String> {
+        let tf = self.mgr.new_from_data(bytes, None)?;
+        let family = tf.family_name();
+        self.custom.push(tf);
+        self.chars.clear();
+        self.collection = None;
+        Some(family)
+    }
+
+    pub fn set_families(&mut self, families: &[&str]) {
+        self.families = families.iter().map(|f| f.to_string()).collect();
+        self.chars.clear();
+    }
+
+    /// `family` if there is one, then the fallback families
+    pub fn get_families(&self, family: Option<&str>) -> Vec<String> {
+        family
+            .map(|f| f.to_string())
+            .into_iter()
+            .chain(self.families.iter().cloned())
+            .collect()
+    }
+
+    /// Registered fonts and the system ones for skia paragraphs.
+    pub fn get_collection(&mut self) -> FontCollection {
+        if self.collection.is_none() {
+            let mut provider = TypefaceFontProvider::new();
+            for tf in &self.custom {
+                provider.register_typeface(tf.clone(), None::<&str>);
+            }
+
+            let mut fc = FontCollection::new();
+            fc.set_asset_font_manager(Some(FontMgr::from(provider)));
+            fc.set_default_font_manager(self.mgr.clone(), None::<&str>);
+            self.collection = Some(fc);
+        }
+        self.collection.clone().unwrap()
+    }
+}
+
+thread_local! {
+    static FONTS: RefCell<FontManager> = RefCell::new(FontManager::default());
+}
+
+/// Adds a font to the fonts of the current thread, returns its family name.
+/// Controls pick it up the next time they update, e.g. on `Window::update_dpi`.
+pub fn register_font_bytes(bytes: &[u8]) -> Option<String> {
+    FONTS.with(|f| f.borrow_mut().register(bytes))
+}
+
+pub fn register_font_file(path: impl AsRef<Path>) -> io::Result<String> {
+    let bytes = std::fs::read(path)?;
+    register_font_bytes(&bytes)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "not a font file"))
+}
+
+/// Replaces the ordered fallback families, see `DEFAULT_FAMILIES`.
+pub fn set_fallback_families(families: &[&str]) {
+    FONTS.with(|f| f.borrow_mut().set_families(families));
+}
+
+pub fn get_typeface(family: Option<&str>, style: FontStyle) -> Typeface {
+    FONTS.with(|f| f.borrow().get_typeface(family, style))
+}
+
+pub(crate) fn get_families(family: Option<&str>) -> Vec<String> {
+    FONTS.with(|f| f.borrow().get_families(family))
+}
+
+pub(crate) fn get_collection() -> FontCollection {
+    FONTS.with(|f| f.borrow_mut().get_collection())
+}
+
+////////////////////////////////////////////////////////////
+/// Runs
+// joins the char before it, e.g. the parts of an emoji sequence
+fn is_joiner(c: char) -> bool {
+    matches!(c, '\u{200D}' | '\u{FE00}'..='\u{FE0F}' | '\u{0300}'..='\u{036F}')
+}
+
+/// Splits `text` into pieces one font draws, `None` is `font` itself and is
+/// used wherever it has the glyphs.
+pub(crate) fn split_runs<'a>(font: &Font, text: &'a str) -> Vec<(Option<Font>, &'a str)> {
+    let style = font
+        .typeface()
+        .map(|tf| tf.font_style())
+        .unwrap_or_default();
+
+    let mut runs = Vec::new();
+    let mut start = 0;
+    let mut cur: Option<Typeface> = None;
+    FONTS.with(|f| {
+        let mut fonts = f.borrow_mut();
+        for (i, c) in text.char_indices() {
+            if i > 0 && is_joiner(c) {
+                continue;
+            }
+            let tf = if c.is_whitespace() || font.unichar_to_glyph(c as i32) != 0 {
+                None
+            } else {
+                fonts.get_fallback(c, style)
+            };
+
+            let id = |tf: &Option<Typeface>| tf.as_ref().map(|tf| tf.unique_id());
+            if i > start && id(&tf) != id(&cur) {
+                runs.push((cur.take(), &text[start..i]));
+                start = i;
+            }
+            cur = tf;
+        }
+    });
+    if start < text.len() {
+        runs.push((cur, &text[start..]));
+    }
+
+    runs.into_iter()
+        .map(|(tf, s)| {
+            let f = tf.map(|tf| {
+                let mut f = font.clone();
+                f.set_typeface(tf);
+                f
+            });
+            (f, s)
+        })
+        .collect()
+}
+
+/// Advance width of `text` with the fallback fonts.
+pub(crate) fn measure_text(font: &Font, text: &str) -> f32 {
+    split_runs(font, text)
+        .iter()
+        .map(|(f, s)| f.as_ref().unwrap_or(font).measure_str(s, None).0)
+        .sum()
+}
+
+/// `canvas.draw_str` with the fallback fonts.
+pub(crate) fn draw_text(
+    canvas: &mut Canvas,
+    text: &str,
+    origin: Point,
+    font: &Font,
+    paint: &Paint,
+) {
+    let mut x = origin.x;
+    for (f, s) in split_runs(font, text) {
+        let f = f.as_ref().unwrap_or(font);
+        canvas.draw_str(s, Point::new(x, origin.y), f, paint);
+        x += f.measure_str(s, None).0;
+    }
+}
+
+#[test]
+fn test_runs_and_register() {
+    let font = get_typeface(None, FontStyle::default());
+    let font = Font::from_typeface(font, 12.);
+
+    // latin is drawn by the font itself in one run
+    let runs = split_runs(&font, "hello world");
+    assert_eq!(runs.len(), 1);
+    assert!(runs[0].0.is_none());
+    assert!(measure_text(&font, "hello") > 0.);
+
+    assert!(register_font_bytes(b"not a font").is_none());
+    assert!(register_font_file("/no/such/font.ttf").is_err());
+}
@@ -0,0 +1,118 @@
+use winit::event::{ModifiersState, MouseButton, VirtualKeyCode};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum EMouseButton {
+    Left,
+    Right,
+    Middle,
+    Other(u16),
+}
+
+impl From<MouseButton> for EMouseButton {
+    fn from(value: MouseButton) -> Self {
+        match value {
+            MouseButton::Left => Self::Left,
+            MouseButton::Right => Self::Right,
+            MouseButton::Middle => Self::Middle,
+            MouseButton::Other(v) => Self::Other(v),
+        }
+    }
+}
+
+/// Keys the UI cares about, letters and digits are `Char` (lower case).
+/// Typed text arrives separately as characters.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum EKey {
+    Tab,
+    Enter,
+    Escape,
+    Space,
+    Backspace,
+    Delete,
+    Insert,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Char(char),
+    Unknow,
+}
+
+impl From<VirtualKeyCode> for EKey {
+    fn from(value: VirtualKeyCode) -> Self {
+        use VirtualKeyCode::*;
+
+        match value {
+            Tab => Self::Tab,
+            Return | NumpadEnter => Self::Enter,
+            Escape => Self::Escape,
+            Space => Self::Space,
+            Back => Self::Backspace,
+            VirtualKeyCode::Delete => Self::Delete,
+            VirtualKeyCode::Insert => Self::Insert,
+            VirtualKeyCode::Left => Self::Left,
+            VirtualKeyCode::Right => Self::Right,
+            VirtualKeyCode::Up => Self::Up,
+            VirtualKeyCode::Down => Self::Down,
+            VirtualKeyCode::Home => Self::Home,
+            VirtualKeyCode::End => Self::End,
+            PageUp => Self::PageUp,
+            PageDown => Self::PageDown,
+            Key1 | Numpad1 => Self::Char('1'),
+            Key2 | Numpad2 => Self::Char('2'),
+            Key3 | Numpad3 => Self::Char('3'),
+            Key4 | Numpad4 => Self::Char('4'),
+            Key5 | Numpad5 => Self::Char('5'),
+            Key6 | Numpad6 => Self::Char('6'),
+            Key7 | Numpad7 => Self::Char('7'),
+            Key8 | Numpad8 => Self::Char('8'),
+            Key9 | Numpad9 => Self::Char('9'),
+            Key0 | Numpad0 => Self::Char('0'),
+            v if (v as u32) >= (A as u32) && (v as u32) <= (Z as u32) => {
+                Self::Char((b'a' + (v as u32 - A as u32) as u8) as char)
+            }
+            _ => Self::Unknow,
+        }
+    }
+}
+
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
+pub struct KeyModifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub logo: bool,
+}
+
+impl KeyModifiers {
+    /// the platform shortcut modifier, `Cmd` on macOS and `Ctrl` elsewhere
+    pub fn command(&self) -> bool {
+        if cfg!(target_os = "macos") {
+            self.logo
+        } else {
+            self.ctrl
+        }
+    }
+}
+
+impl From<ModifiersState> for KeyModifiers {
+    fn from(value: ModifiersState) -> Self {
+        Self {
+            shift: value.shift(),
+            ctrl: value.ctrl(),
+            alt: value.alt(),
+            logo: value.logo(),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct KeyInput {
+    pub key: EKey,
+    pub pressed: bool,
+    pub modifiers: KeyModifiers,
+}
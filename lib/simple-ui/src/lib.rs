@@ -1,9 +1,17 @@
+mod backend;
+pub mod clipboard;
+pub mod font;
+pub mod input;
+pub mod offscreen;
+pub mod testing;
 pub mod ui;
 pub mod utils_dbg;
 pub mod window;
 
 mod utils;
 
+pub use offscreen::Offscreen;
+pub use utils::{EUnit, ScaleDpi};
 pub use window::{Window, WindowBuilder};
 
 pub use skia_safe::{Color, IPoint, IRect, ISize, Point, Size};
@@ -0,0 +1,89 @@
+use skia_safe::{surfaces, Color, EncodedImageFormat, Image, Surface};
+
+use crate::{
+    ui::{Container, TCtrl},
+    utils::ScaleDpi,
+    ISize,
+};
+
+/// Renders a `Container` tree into a CPU raster surface, no window or GPU needed.
+pub struct Offscreen {
+    size: ISize,
+    dpi: ScaleDpi,
+
+    surface: Surface,
+    bg_color: Color,
+}
+
+impl Offscreen {
+    /// `size` is the logical size, the surface is `dpi.scale(size)` pixels
+    pub fn new(size: ISize, dpi: ScaleDpi) -> Option<Self> {
+        let surface = surfaces::raster_n32_premul(dpi.scale(size))?;
+
+        Some(Self {
+            size,
+            dpi,
+            surface,
+            bg_color: Color::WHITE,
+        })
+    }
+
+    pub fn set_bg_color(&mut self, c: Color) -> &mut Self {
+        self.bg_color = c;
+        self
+    }
+
+    pub fn size(&self) -> ISize {
+        self.size
+    }
+    pub fn dpi(&self) -> &ScaleDpi {
+        &self.dpi
+    }
+
+    /// Same steps as `Window` does on init / resize / redraw.
+    pub fn render(&mut self, root: &mut Container) -> Image {
+        let pixel_size = self.dpi.scale(self.size);
+
+        root.update_self(pixel_size, &self.dpi);
+        root.update_dpi(&self.dpi);
+        root.update(&self.dpi);
+
+        let canvas = self.surface.canvas();
+        canvas.clear(self.bg_color);
+        root.render(canvas, &self.dpi);
+
+        self.surface.image_snapshot()
+    }
+
+    pub fn render_to_png(&mut self, root: &mut Container) -> Option<Vec<u8>> {
+        let image = self.render(root);
+        let data = image.encode(None, EncodedImageFormat::PNG, None)?;
+
+        Some(data.as_bytes().to_vec())
+    }
+}
+
+pub fn render_to_png(root: &mut Container, size: ISize, dpi: &ScaleDpi) -> Option<Vec<u8>> {
+    Offscreen::new(size, dpi.clone())?.render_to_png(root)
+}
+
+#[test]
+fn test_render() {
+    use crate::{ui::LayoutHorizontal, IPoint, IRect};
+
+    let mut root = Container::new(LayoutHorizontal::new());
+    root.ctrl.inner.margin = Some(IRect::new(10, 10, 10, 10));
+    root.ctrl.styles.default.bg_color = Some(Color::RED);
+
+    let mut offscreen = Offscreen::new(ISize::new(100, 50), ScaleDpi::new(2.)).unwrap();
+    let image = offscreen.render(&mut root);
+    assert_eq!(image.width(), 200);
+    assert_eq!(image.height(), 100);
+
+    let pixmap = image.peek_pixels().unwrap();
+    assert_eq!(pixmap.get_color(IPoint::new(5, 5)), Color::WHITE);
+    assert_eq!(pixmap.get_color(IPoint::new(100, 50)), Color::RED);
+
+    let png = offscreen.render_to_png(&mut root).unwrap();
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+}
@@ -0,0 +1,253 @@
+use crate::{
+    input::{EKey, KeyModifiers},
+    ui::{Container, ECtrlStatus, ECtrlType, TCtrl},
+    utils::ScaleDpi,
+    IPoint, IRect, ISize, Window, WindowBuilder,
+};
+
+/// How a control is looked up.
+///
+/// `"a/b/c"` converts to `Path`, matching names from the root container down,
+/// any other string converts to `Name`.
+#[derive(Clone, Debug)]
+pub enum ESelector {
+    Name(String),
+    Path(Vec<String>),
+    Type(ECtrlType),
+}
+
+impl From<&str> for ESelector {
+    fn from(value: &str) -> Self {
+        if value.contains('/') {
+            Self::Path(
+                value
+                    .split('/')
+                    .filter(|s| !s.is_empty())
+                    .map(|s| s.to_string())
+                    .collect(),
+            )
+        } else {
+            Self::Name(value.to_string())
+        }
+    }
+}
+
+impl From<ECtrlType> for ESelector {
+    fn from(value: ECtrlType) -> Self {
+        Self::Type(value)
+    }
+}
+
+impl ESelector {
+    fn matches(&self, ctrl: &dyn TCtrl) -> bool {
+        match self {
+            Self::Name(name) => ctrl.get_inner().name == *name,
+            Self::Type(t) => ctrl.type_() == *t,
+            Self::Path(_) => false,
+        }
+    }
+}
+
+// pre-order search, `clip` is where the children of `c` can show
+fn find_in<'a>(c: &'a Container, sel: &ESelector, clip: IRect) -> Option<(&'a dyn TCtrl, IRect)> {
+    for child in c.get_children() {
+        let child = child.as_ref();
+        let child_clip = IRect::intersect(&clip, child.get_real_rc()).unwrap_or_default();
+
+        if sel.matches(child) {
+            return Some((child, child_clip));
+        }
+        if let Some(c) = child.as_container() {
+            let r = find_in(c, sel, inner_clip(c, clip));
+            if r.is_some() {
+                return r;
+            }
+        }
+    }
+    None
+}
+
+fn find_path<'a>(c: &'a Container, path: &[String], clip: IRect) -> Option<(&'a dyn TCtrl, IRect)> {
+    let (name, rest) = path.split_first()?;
+
+    let child = c
+        .get_children()
+        .iter()
+        .map(|c| c.as_ref())
+        .find(|c| c.get_inner().name == *name)?;
+    let child_clip = IRect::intersect(&clip, child.get_real_rc()).unwrap_or_default();
+
+    if rest.is_empty() {
+        return Some((child, child_clip));
+    }
+    let c = child.as_container()?;
+    find_path(c, rest, inner_clip(c, clip))
+}
+
+// where the children of `c` can show, inside `clip` of its parent
+fn inner_clip(c: &Container, clip: IRect) -> IRect {
+    match c.get_clip() {
+        Some(v) => IRect::intersect(&clip, &v.rect().round()).unwrap_or_default(),
+        None => clip,
+    }
+}
+
+/// Drives a headless `Window` like a user would and asserts on control state.
+///
+/// Positions and rects are logical, the same units `Window::inject_*` uses.
+pub struct UIDriver {
+    window: Window,
+}
+
+impl UIDriver {
+    pub fn new(root: Container, size: ISize, dpi: ScaleDpi) -> Self {
+        let mut builder = WindowBuilder::new_headless(size, dpi);
+        builder.set_root_container(root);
+
+        Self::from_window(builder.build_headless())
+    }
+
+    pub fn from_window(window: Window) -> Self {
+        Self { window }
+    }
+
+    pub fn window(&mut self) -> &mut Window {
+        &mut self.window
+    }
+
+    fn find_with_clip(&self, sel: &ESelector) -> Option<(&dyn TCtrl, IRect)> {
+        let root = self.window.get_root_container()?;
+        let clip = *root.get_real_rc();
+
+        match sel {
+            ESelector::Path(path) => {
+                // the root name is optional
+                let path = match path.first() {
+                    Some(n) if *n == root.get_inner().name => &path[1..],
+                    _ => &path[..],
+                };
+                if path.is_empty() {
+                    return Some((root as &dyn TCtrl, clip));
+                }
+                find_path(root, path, clip)
+            }
+            _ if sel.matches(root) => Some((root as &dyn TCtrl, clip)),
+            _ => find_in(root, sel, clip),
+        }
+    }
+
+    pub fn find(&self, sel: impl Into<ESelector>) -> Option<&dyn TCtrl> {
+        self.find_with_clip(&sel.into()).map(|(c, _)| c)
+    }
+
+    fn get(&self, sel: &ESelector) -> (&dyn TCtrl, IRect) {
+        match self.find_with_clip(sel) {
+            Some(r) => r,
+            None => panic!("control {:?} not found", sel),
+        }
+    }
+
+    /// logical rect of the control
+    pub fn rect(&self, sel: impl Into<ESelector>) -> IRect {
+        let (ctrl, _) = self.get(&sel.into());
+        self.window.get_dpi().rescale(*ctrl.get_real_rc())
+    }
+
+    /// logical center of the visible part of the control
+    pub fn center(&self, sel: impl Into<ESelector>) -> IPoint {
+        let sel = sel.into();
+        let (_, clip) = self.get(&sel);
+        if clip.is_empty() {
+            panic!("control {:?} is not visible", sel);
+        }
+
+        let pos = IPoint::new(clip.left + clip.width() / 2, clip.top + clip.height() / 2);
+        self.window.get_dpi().rescale(pos)
+    }
+
+    pub fn hover(&mut self, sel: impl Into<ESelector>) -> &mut Self {
+        let pos = self.center(sel);
+        self.window.inject_mouse_move(pos);
+        self
+    }
+
+    pub fn click(&mut self, sel: impl Into<ESelector>) -> &mut Self {
+        let pos = self.center(sel);
+        self.window.inject_click(pos);
+        self
+    }
+
+    /// clicks the control then types `text` into it
+    pub fn type_text(&mut self, sel: impl Into<ESelector>, text: &str) -> &mut Self {
+        self.click(sel);
+        self.window.inject_text(text);
+        self
+    }
+
+    pub fn press_key(&mut self, key: EKey) -> &mut Self {
+        self.window.inject_key(key);
+        self
+    }
+
+    pub fn press_key_with(&mut self, key: EKey, modifiers: KeyModifiers) -> &mut Self {
+        self.window.inject_modifiers(modifiers);
+        self.window.inject_key(key);
+        self.window.inject_modifiers(KeyModifiers::default());
+        self
+    }
+
+    /// Draws the pending frame, returns false if nothing requested a redraw.
+    pub fn wait_redraw(&mut self) -> bool {
+        self.window.draw_pending()
+    }
+
+    pub fn assert_redraw(&mut self) -> &mut Self {
+        assert!(self.wait_redraw(), "no redraw was requested");
+        self
+    }
+
+    pub fn assert_exists(&self, sel: impl Into<ESelector>) -> &Self {
+        let sel = sel.into();
+        assert!(
+            self.find_with_clip(&sel).is_some(),
+            "control {:?} not found",
+            sel
+        );
+        self
+    }
+
+    pub fn assert_text(&self, sel: impl Into<ESelector>, expected: &str) -> &Self {
+        let sel = sel.into();
+        let (ctrl, _) = self.get(&sel);
+        assert_eq!(ctrl.get_text(), Some(expected), "text of {:?}", sel);
+        self
+    }
+
+    pub fn assert_status(&self, sel: impl Into<ESelector>, expected: ECtrlStatus) -> &Self {
+        let sel = sel.into();
+        let (ctrl, _) = self.get(&sel);
+        assert_eq!(ctrl.get_status(), expected, "status of {:?}", sel);
+        self
+    }
+
+    pub fn assert_rect(&self, sel: impl Into<ESelector>, expected: IRect) -> &Self {
+        let sel = sel.into();
+        assert_eq!(self.rect(sel.clone()), expected, "rect of {:?}", sel);
+        self
+    }
+
+    pub fn assert_focused(&self, sel: impl Into<ESelector>) -> &Self {
+        let sel = sel.into();
+        let (ctrl, _) = self.get(&sel);
+        assert!(ctrl.get_inner().is_focused(), "{:?} is not focused", sel);
+        self
+    }
+
+    /// visible means some part of the control is inside all of its parents
+    pub fn assert_visible(&self, sel: impl Into<ESelector>, expected: bool) -> &Self {
+        let sel = sel.into();
+        let (_, clip) = self.get(&sel);
+        assert_eq!(!clip.is_empty(), expected, "visibility of {:?}", sel);
+        self
+    }
+}
@@ -0,0 +1,5 @@
+pub mod driver;
+pub mod snapshot;
+
+pub use driver::{ESelector, UIDriver};
+pub use snapshot::{ESnapshotError, Snapshot};
@@ -0,0 +1,221 @@
+use skia_safe::{
+    image::CachingHint, images, AlphaType, ColorType, Data, EncodedImageFormat, Image, ImageInfo,
+};
+use std::path::{Path, PathBuf};
+
+use crate::{offscreen::Offscreen, ui::Container, utils::ScaleDpi, ISize};
+
+/// set this env to rewrite the reference images with the current output
+pub const ENV_UPDATE_SNAPSHOTS: &str = "SIMPLE_UI_UPDATE_SNAPSHOTS";
+
+#[derive(Debug)]
+pub enum ESnapshotError {
+    Io(String),
+    Decode(String),
+    Missing(String),
+    SizeMismatch { expected: ISize, actual: ISize },
+    PixelMismatch { count: usize, max_diff: u8 },
+}
+
+impl From<std::io::Error> for ESnapshotError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(format!("io-error: {}", value))
+    }
+}
+
+/// Compares rendered images against reference PNGs stored in `dir`.
+///
+/// A missing reference is an error unless `ENV_UPDATE_SNAPSHOTS` is set, then
+/// it is recorded from the current output. On mismatch
+/// `<name>.actual.png` and `<name>.diff.png` are written next to the reference.
+pub struct Snapshot {
+    dir: PathBuf,
+
+    // max per channel difference that still counts as equal
+    tolerance: u8,
+    // number of differing pixels that is still accepted
+    max_diff_pixels: usize,
+}
+
+impl Snapshot {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            tolerance: 0,
+            max_diff_pixels: 0,
+        }
+    }
+
+    pub fn tolerance(mut self, v: u8) -> Self {
+        self.tolerance = v;
+        self
+    }
+
+    pub fn max_diff_pixels(mut self, v: usize) -> Self {
+        self.max_diff_pixels = v;
+        self
+    }
+
+    pub fn check(&self, name: &str, actual: &Image) -> Result<(), ESnapshotError> {
+        let ref_path = self.path(name, "png");
+        let actual_pixels = image_pixels(actual)?;
+
+        if std::env::var_os(ENV_UPDATE_SNAPSHOTS).is_some() {
+            return write_png(&ref_path, actual);
+        }
+        if !ref_path.exists() {
+            write_png(&self.path(name, "actual.png"), actual)?;
+            return Err(ESnapshotError::Missing(ref_path.display().to_string()));
+        }
+
+        let data = std::fs::read(&ref_path)?;
+        let expected = images::deferred_from_encoded_data(Data::new_copy(&data), None)
+            .ok_or_else(|| ESnapshotError::Decode(ref_path.display().to_string()))?;
+
+        if expected.dimensions() != actual.dimensions() {
+            write_png(&self.path(name, "actual.png"), actual)?;
+            return Err(ESnapshotError::SizeMismatch {
+                expected: expected.dimensions(),
+                actual: actual.dimensions(),
+            });
+        }
+
+        let expected_pixels = image_pixels(&expected)?;
+        let diff = compare_pixels(&expected_pixels, &actual_pixels, self.tolerance);
+        if diff.count <= self.max_diff_pixels {
+            let _ = std::fs::remove_file(self.path(name, "actual.png"));
+            let _ = std::fs::remove_file(self.path(name, "diff.png"));
+            return Ok(());
+        }
+
+        write_png(&self.path(name, "actual.png"), actual)?;
+        let diff_image = pixels_to_image(actual.dimensions(), diff.pixels)?;
+        write_png(&self.path(name, "diff.png"), &diff_image)?;
+
+        Err(ESnapshotError::PixelMismatch {
+            count: diff.count,
+            max_diff: diff.max_diff,
+        })
+    }
+
+    /// Renders `root` offscreen and checks it, panics on mismatch.
+    pub fn assert_container(&self, name: &str, root: &mut Container, size: ISize, dpi: ScaleDpi) {
+        let mut offscreen = Offscreen::new(size, dpi).expect("create offscreen surface");
+        let image = offscreen.render(root);
+
+        if let Err(e) = self.check(name, &image) {
+            panic!("snapshot `{}` mismatch: {:?}", name, e);
+        }
+    }
+
+    fn path(&self, name: &str, ext: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", name, ext))
+    }
+}
+
+struct PixelDiff {
+    count: usize,
+    max_diff: u8,
+    pixels: Vec<u8>,
+}
+
+// both buffers are RGBA8888, mismatched pixels are red in the diff image,
+// matched pixels are a faded copy of the expected one
+fn compare_pixels(expected: &[u8], actual: &[u8], tolerance: u8) -> PixelDiff {
+    let mut diff = PixelDiff {
+        count: 0,
+        max_diff: 0,
+        pixels: Vec::with_capacity(expected.len()),
+    };
+
+    for (e, a) in expected.chunks_exact(4).zip(actual.chunks_exact(4)) {
+        let d = e
+            .iter()
+            .zip(a)
+            .map(|(e, a)| e.abs_diff(*a))
+            .max()
+            .unwrap_or(0);
+        diff.max_diff = diff.max_diff.max(d);
+
+        if d > tolerance {
+            diff.count += 1;
+            diff.pixels.extend_from_slice(&[255, 0, 0, 255]);
+        } else {
+            let gray = ((e[0] as u32 + e[1] as u32 + e[2] as u32) / 3) as u8;
+            let gray = 192 + gray / 4;
+            diff.pixels.extend_from_slice(&[gray, gray, gray, 255]);
+        }
+    }
+
+    diff
+}
+
+fn rgba_info(size: ISize) -> ImageInfo {
+    ImageInfo::new(size, ColorType::RGBA8888, AlphaType::Unpremul, None)
+}
+
+fn image_pixels(image: &Image) -> Result<Vec<u8>, ESnapshotError> {
+    let info = rgba_info(image.dimensions());
+    let row_bytes = image.width() as usize * 4;
+    let mut pixels = vec![0u8; row_bytes * image.height() as usize];
+
+    if !image.read_pixels(&info, &mut pixels, row_bytes, (0, 0), CachingHint::Allow) {
+        return Err(ESnapshotError::Decode("read pixels".to_string()));
+    }
+    Ok(pixels)
+}
+
+fn pixels_to_image(size: ISize, pixels: Vec<u8>) -> Result<Image, ESnapshotError> {
+    let row_bytes = size.width as usize * 4;
+    images::raster_from_data(&rgba_info(size), Data::new_copy(&pixels), row_bytes)
+        .ok_or_else(|| ESnapshotError::Decode("create diff image".to_string()))
+}
+
+fn write_png(path: &Path, image: &Image) -> Result<(), ESnapshotError> {
+    let data = image
+        .encode(None, EncodedImageFormat::PNG, None)
+        .ok_or_else(|| ESnapshotError::Decode("encode png".to_string()))?;
+    // a new snapshot dir has no folder yet
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, data.as_bytes())?;
+    Ok(())
+}
+
+#[test]
+fn test_compare_pixels() {
+    let expected = [10, 20, 30, 255, 0, 0, 0, 255];
+    {
+        let diff = compare_pixels(&expected, &expected, 0);
+        assert_eq!(diff.count, 0);
+        assert_eq!(diff.max_diff, 0);
+    }
+    {
+        let actual = [12, 20, 30, 255, 0, 0, 9, 255];
+        let diff = compare_pixels(&expected, &actual, 2);
+        assert_eq!(diff.count, 1);
+        assert_eq!(diff.max_diff, 9);
+        assert_eq!(&diff.pixels[4..], &[255, 0, 0, 255]);
+
+        let diff = compare_pixels(&expected, &actual, 9);
+        assert_eq!(diff.count, 0);
+    }
+}
+
+#[test]
+fn test_missing_in_new_dir() {
+    if std::env::var_os(ENV_UPDATE_SNAPSHOTS).is_some() {
+        return;
+    }
+    let dir = std::env::temp_dir().join(format!("simple-ui-snapshot-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let mut root = Container::new(crate::ui::LayoutVertical::new());
+    let mut offscreen = Offscreen::new(ISize::new(8, 8), ScaleDpi::new(1.)).unwrap();
+    let image = offscreen.render(&mut root);
+
+    let result = Snapshot::new(dir.clone()).check("new", &image);
+    assert!(matches!(result, Err(ESnapshotError::Missing(_))));
+    assert!(dir.join("new.actual.png").exists());
+    let _ = std::fs::remove_dir_all(&dir);
+}
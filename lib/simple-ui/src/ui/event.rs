@@ -0,0 +1,223 @@
+use as_any::Downcast;
+
+use crate::{
+    input::{EMouseButton, KeyInput, KeyModifiers},
+    ui::{Container, TCtrl},
+    utils::ScaleDpi,
+    IPoint, Point,
+};
+use std::time::Duration;
+
+/// Positions are in physical pixels, the same space as `TCtrlInner::real_rc`.
+#[derive(PartialEq, Clone, Debug)]
+pub enum EUIEvent {
+    MouseDown {
+        pos: IPoint,
+        button: EMouseButton,
+    },
+    MouseUp {
+        pos: IPoint,
+        button: EMouseButton,
+    },
+    MouseMove {
+        pos: IPoint,
+    },
+    MouseEnter,
+    MouseLeave,
+    Click {
+        pos: IPoint,
+    },
+    /// a second `Click` on the same control soon after the first, sent after it
+    DoubleClick {
+        pos: IPoint,
+    },
+    Wheel {
+        pos: IPoint,
+        delta: Point,
+    },
+
+    KeyDown(KeyInput),
+    KeyUp(KeyInput),
+    Char(char),
+
+    FocusIn,
+    FocusOut,
+
+    /// the value of the control changed, posted by the control itself
+    Change,
+    /// the control's input was confirmed, e.g. Enter in an `Edit`
+    Submit,
+    /// a link in a `RichText` was clicked, with its url
+    Link(String),
+    /// the selected items of a `ListView` changed
+    SelectionChanged,
+    /// an item was double clicked or Enter was pressed on it, with its index
+    ItemActivate(usize),
+    /// a `CheckBox` or `RadioButton` was checked or unchecked by the user
+    Toggled,
+    /// a timer set by `UIEvent::set_timer` is due, only sent to that control
+    Timer(u32),
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum EEventPhase {
+    /// from the root container down to the parent of the target
+    Capture,
+    Target,
+    /// from the parent of the target back up to the root container
+    Bubble,
+}
+
+pub struct UIEvent {
+    pub kind: EUIEvent,
+    pub phase: EEventPhase,
+
+    /// child index paths from the root container
+    pub target: Vec<usize>,
+    pub current: Vec<usize>,
+
+    /// held when the event was dispatched
+    pub modifiers: KeyModifiers,
+
+    stopped: bool,
+    redraw: bool,
+    posted: Vec<(Vec<usize>, EUIEvent)>,
+    timers: Vec<(Vec<usize>, u32, Duration)>,
+}
+
+impl UIEvent {
+    pub fn new(kind: EUIEvent, target: Vec<usize>) -> Self {
+        Self {
+            kind,
+            phase: EEventPhase::Capture,
+            target,
+            current: Vec::new(),
+            modifiers: KeyModifiers::default(),
+            stopped: false,
+            redraw: false,
+            posted: Vec::new(),
+            timers: Vec::new(),
+        }
+    }
+
+    /// controls after the current one won't see this event
+    pub fn stop_propagation(&mut self) {
+        self.stopped = true;
+    }
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    pub fn request_redraw(&mut self) {
+        self.redraw = true;
+    }
+    pub fn need_redraw(&self) -> bool {
+        self.redraw
+    }
+
+    pub fn is_target(&self) -> bool {
+        self.phase == EEventPhase::Target
+    }
+
+    /// dispatches `kind` to the current control after this event is done
+    pub fn post(&mut self, kind: EUIEvent) {
+        self.posted.push((self.current.clone(), kind));
+    }
+    pub(crate) fn take_posted(&mut self) -> Vec<(Vec<usize>, EUIEvent)> {
+        std::mem::take(&mut self.posted)
+    }
+
+    /// Sends `EUIEvent::Timer(id)` to the current control after `delay`,
+    /// replacing its pending timer with the same id. Timers fire once.
+    pub fn set_timer(&mut self, id: u32, delay: Duration) {
+        self.timers.push((self.current.clone(), id, delay));
+    }
+    pub(crate) fn take_timers(&mut self) -> Vec<(Vec<usize>, u32, Duration)> {
+        std::mem::take(&mut self.timers)
+    }
+}
+
+/// What a handler can reach while an event is dispatched.
+pub struct EventCtx<'a> {
+    pub(crate) root: &'a mut Container,
+    pub(crate) dpi: &'a ScaleDpi,
+
+    pub(crate) redraw: bool,
+    pub(crate) close: bool,
+    // Some(None) clears the focus
+    pub(crate) focus: Option<Option<Vec<usize>>>,
+}
+
+impl<'a> EventCtx<'a> {
+    pub fn new(root: &'a mut Container, dpi: &'a ScaleDpi) -> Self {
+        Self {
+            root,
+            dpi,
+            redraw: false,
+            close: false,
+            focus: None,
+        }
+    }
+
+    pub fn get_root(&mut self) -> &mut Container {
+        &mut *self.root
+    }
+
+    pub fn get_ctrl_by_name(&self, name: &str) -> Option<&dyn TCtrl> {
+        self.root.get_ctrl_by_name(name)
+    }
+
+    pub fn get_mut_ctrl_by_name(&mut self, name: &str) -> Option<&mut dyn TCtrl> {
+        self.root.get_mut_ctrl_by_name(name)
+    }
+
+    pub fn get_mut_ctrl_by_path(&mut self, path: &[usize]) -> Option<&mut dyn TCtrl> {
+        self.root.get_mut_ctrl_by_path(path)
+    }
+
+    /// `get_mut_ctrl_by_name` downcast to the concrete control
+    pub fn get_mut_ctrl_as<T: TCtrl>(&mut self, name: &str) -> Option<&mut T> {
+        self.root.get_mut_ctrl_by_name(name)?.downcast_mut::<T>()
+    }
+
+    pub fn dpi(&self) -> &ScaleDpi {
+        self.dpi
+    }
+
+    pub fn redraw(&mut self) {
+        self.redraw = true;
+    }
+
+    /// moves the keyboard focus after the event, `None` clears it
+    pub fn set_focus(&mut self, path: Option<Vec<usize>>) {
+        self.focus = Some(path);
+    }
+
+    pub fn close_window(&mut self) {
+        self.close = true;
+    }
+}
+
+pub trait TEventHandler {
+    fn on_event(&mut self, ctx: &mut EventCtx, event: &mut UIEvent);
+}
+
+impl<F: FnMut(&mut EventCtx, &mut UIEvent)> TEventHandler for F {
+    fn on_event(&mut self, ctx: &mut EventCtx, event: &mut UIEvent) {
+        self(ctx, event)
+    }
+}
+
+// calls `f` for matching events delivered to the control itself
+pub(crate) struct TargetHandler<F> {
+    pub filter: fn(&EUIEvent) -> bool,
+    pub f: F,
+}
+
+impl<F: FnMut(&mut EventCtx, &mut UIEvent)> TEventHandler for TargetHandler<F> {
+    fn on_event(&mut self, ctx: &mut EventCtx, event: &mut UIEvent) {
+        if event.is_target() && (self.filter)(&event.kind) {
+            (self.f)(ctx, event);
+        }
+    }
+}
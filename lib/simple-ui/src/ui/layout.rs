@@ -1,12 +1,19 @@
 use crate::{
     ui::{Container, TCtrl},
     utils,
-    utils::{IPointOpt, ISizeOpt, ScaleDpi},
-    IRect,
+    utils::{EUnit, IPointOpt, ScaleDpi},
+    IRect, ISize,
 };
+use std::ops::Range;
 
 pub trait TLayout {
     fn update(&self, c: &mut Container, dpi: &ScaleDpi);
+
+    /// Size the children need inside `max` with the container's padding,
+    /// `None` if the layout has no content size.
+    fn measure(&self, _c: &Container, _max: ISize, _dpi: &ScaleDpi) -> Option<ISize> {
+        None
+    }
 }
 
 #[derive(Default)]
@@ -18,12 +25,16 @@ impl LayoutHorizontal {
     }
 }
 
-fn get_layout_real_rc(c: &mut Container, dpi: &ScaleDpi) -> Option<IRect> {
-    let mut rc = c.get_inner().real_rc.clone();
-    let c_padding = dpi.scale(match c.get_inner().padding {
+fn get_padding(c: &Container, dpi: &ScaleDpi) -> IRect {
+    dpi.scale(match c.get_inner().padding {
         Some(v) => v,
         None => IRect::new(0, 0, 0, 0),
-    });
+    })
+}
+
+fn get_layout_real_rc(c: &mut Container, dpi: &ScaleDpi) -> Option<IRect> {
+    let mut rc = c.get_inner().real_rc.clone();
+    let c_padding = get_padding(c, dpi);
     rc.left += c_padding.left;
     rc.right -= c_padding.right;
     rc.top += c_padding.top;
@@ -39,25 +50,216 @@ fn get_layout_real_rc(c: &mut Container, dpi: &ScaleDpi) -> Option<IRect> {
     Some(rc)
 }
 
+// physical bounds of a child along one axis
+#[derive(Clone, Copy, Default)]
+struct Limits {
+    min: Option<i32>,
+    max: Option<i32>,
+}
+
+impl Limits {
+    // `min` / `max` resolved against `parent`, plus the physical `margin`
+    fn new(
+        min: Option<EUnit>,
+        max: Option<EUnit>,
+        margin: i32,
+        parent: i32,
+        dpi: &ScaleDpi,
+    ) -> Self {
+        let resolve = |u: Option<EUnit>| u.and_then(|u| u.resolve(parent, dpi));
+        Self {
+            min: resolve(min).map(|v| v + margin),
+            max: resolve(max).map(|v| v + margin),
+        }
+    }
+
+    fn is_none(&self) -> bool {
+        self.min.is_none() && self.max.is_none()
+    }
+
+    // the min wins when they cross
+    fn clamp(&self, v: i32) -> i32 {
+        let v = self.max.map_or(v, |m| v.min(m));
+        self.min.map_or(v, |m| v.max(m))
+    }
+}
+
+/// `min_size` / `max_size` of a child with its margin like `get_child_size`,
+/// (width, height).
+fn get_child_limits(child: &dyn TCtrl, parent: ISize, dpi: &ScaleDpi) -> (Limits, Limits) {
+    let inner = child.get_inner();
+    let margin = inner.margin.unwrap_or_default();
+    (
+        Limits::new(
+            inner.min_size.width,
+            inner.max_size.width,
+            margin.left + margin.right,
+            parent.width,
+            dpi,
+        ),
+        Limits::new(
+            inner.min_size.height,
+            inner.max_size.height,
+            margin.top + margin.bottom,
+            parent.height,
+            dpi,
+        ),
+    )
+}
+
+// physical size of a child, `None` where the layout decides
+#[derive(Clone, Copy, Default)]
+struct ChildSize {
+    width: Option<i32>,
+    height: Option<i32>,
+}
+
+/// Physical size of a child with its margin, units resolved against the
+/// `parent` content size. `EUnit::Auto`, or any unset side with `auto_size`,
+/// is what `TCtrl::get_preferred_size` asks for. Kept within `min_size` /
+/// `max_size`, `None` shares the space left.
+fn get_child_size(child: &dyn TCtrl, parent: ISize, dpi: &ScaleDpi) -> ChildSize {
+    let inner = child.get_inner();
+    let margin = inner.margin.unwrap_or_default();
+    let is_auto = |u: Option<EUnit>| match u {
+        Some(EUnit::Auto) => true,
+        None => inner.auto_size,
+        _ => false,
+    };
+    let pref = if is_auto(inner.size.width) || is_auto(inner.size.height) {
+        let max = ISize::new(
+            parent.width - margin.left - margin.right,
+            parent.height - margin.top - margin.bottom,
+        );
+        child.get_preferred_size(max, dpi)
+    } else {
+        None
+    };
+
+    let (lw, lh) = get_child_limits(child, parent, dpi);
+    ChildSize {
+        width: match inner.size.width {
+            u if is_auto(u) => pref.map(|p| p.width + margin.left + margin.right),
+            u => u.and_then(|u| u.resolve(parent.width, dpi)),
+        }
+        .map(|v| lw.clamp(v)),
+        height: match inner.size.height {
+            u if is_auto(u) => pref.map(|p| p.height + margin.top + margin.bottom),
+            u => u.and_then(|u| u.resolve(parent.height, dpi)),
+        }
+        .map(|v| lh.clamp(v)),
+    }
+}
+
+// share of the space left for a child without a size, `EUnit::Star` or 1
+fn get_weight(u: Option<EUnit>) -> f32 {
+    match u {
+        Some(EUnit::Star(w)) => w.max(0.),
+        _ => 1.,
+    }
+}
+
+// children side by side, or stacked with `vertical`
+fn measure_stack(c: &Container, max: ISize, dpi: &ScaleDpi, vertical: bool) -> ISize {
+    let padding = get_padding(c, dpi);
+    let max = ISize::new(
+        max.width - padding.left - padding.right,
+        max.height - padding.top - padding.bottom,
+    );
+
+    let (mut u, mut v) = (0, 0);
+    for child in c.get_children() {
+        let size = get_child_size(child.as_ref(), max, dpi);
+        let (lw, lh) = get_child_limits(child.as_ref(), max, dpi);
+        // the ones sharing the space left need at least their min size
+        let size = ISize::new(
+            size.width.unwrap_or_else(|| lw.clamp(0)),
+            size.height.unwrap_or_else(|| lh.clamp(0)),
+        );
+        let (su, sv) = if vertical {
+            (size.height, size.width)
+        } else {
+            (size.width, size.height)
+        };
+        u += su;
+        v = v.max(sv);
+    }
+
+    let (w, h) = if vertical { (v, u) } else { (u, v) };
+    ISize::new(
+        w + padding.left + padding.right,
+        h + padding.top + padding.bottom,
+    )
+}
+
+/// Main sizes of stacked children. Set sizes are kept, the others share
+/// what is left by their weights. A child hitting its limits keeps the
+/// clamped size and the rest is shared again among the others.
+fn distribute(sizes: &[Option<i32>], weights: &[f32], limits: &[Limits], avail: i32) -> Vec<i32> {
+    let mut out = sizes.to_vec();
+    loop {
+        if !out.iter().any(|s| s.is_none()) {
+            break;
+        }
+        let total = out
+            .iter()
+            .zip(weights)
+            .filter(|(s, _)| s.is_none())
+            .map(|(_, w)| w)
+            .sum::<f32>();
+        let left = (avail - out.iter().flatten().sum::<i32>()).max(0) as f32;
+        let share = |w: f32| {
+            if total > 0. {
+                (left * w / total) as i32
+            } else {
+                0
+            }
+        };
+
+        let mut clamped = false;
+        for ((s, l), w) in out.iter_mut().zip(limits).zip(weights) {
+            if s.is_none() && l.clamp(share(*w)) != share(*w) {
+                *s = Some(l.clamp(share(*w)));
+                clamped = true;
+            }
+        }
+        if !clamped {
+            for (s, w) in out.iter_mut().zip(weights) {
+                *s = s.or(Some(share(*w)));
+            }
+        }
+    }
+    out.into_iter().flatten().collect()
+}
+
+// cross size of a stacked child, stretched ones stay within their limits
+fn stretch(size: Option<i32>, limits: Limits, avail: i32) -> Option<i32> {
+    match size {
+        None if !limits.is_none() => Some(limits.clamp(avail)),
+        _ => size,
+    }
+}
+
+/// Sizes in physical pixels, `main` from `distribute`. `child_point` is the
+/// start and end on the cross axis.
 fn layout_uv(
     real_rc: IRect,
     offset: &mut i32,
-    default_size: i32,
-    empty_count: &mut i32,
+    main: i32,
+    cross: Option<i32>,
     child_point: &IPointOpt,
-    child_size: &ISizeOpt,
     dpi: &ScaleDpi,
 ) -> IRect {
     let mut rc = IRect::default();
+    let resolve = |u: Option<EUnit>| u.and_then(|u| u.resolve(real_rc.height(), dpi));
 
-    rc.top = match child_point.x {
-        Some(v) => dpi.scale(v),
+    rc.top = match resolve(child_point.x) {
+        Some(v) => v,
         None => 0,
     } + real_rc.top;
 
-    rc.bottom = match child_point.y {
+    rc.bottom = match resolve(child_point.y) {
         Some(v) => {
-            let v = dpi.scale(v);
             real_rc.top
                 + if v > real_rc.height() {
                     real_rc.bottom
@@ -65,9 +267,8 @@ fn layout_uv(
                     v
                 }
         }
-        None => match child_size.height {
+        None => match cross {
             Some(vv) => {
-                let vv = dpi.scale(vv);
                 if vv + rc.top > real_rc.height() {
                     real_rc.bottom
                 } else {
@@ -82,20 +283,7 @@ fn layout_uv(
     rc.right = if *offset >= real_rc.right {
         rc.left
     } else {
-        let mut v = match child_size.width {
-            Some(v) => dpi.scale(v) + rc.left,
-            None => {
-                *empty_count -= 1;
-                if default_size <= 0 {
-                    rc.left
-                } else {
-                    rc.left + default_size
-                }
-            }
-        };
-        if v > real_rc.right {
-            v = real_rc.right
-        }
+        let v = (rc.left + main.max(0)).min(real_rc.right);
         *offset = v;
         v
     };
@@ -104,6 +292,10 @@ fn layout_uv(
 }
 
 impl TLayout for LayoutHorizontal {
+    fn measure(&self, c: &Container, max: ISize, dpi: &ScaleDpi) -> Option<ISize> {
+        Some(measure_stack(c, max, dpi, false))
+    }
+
     fn update(&self, c: &mut Container, dpi: &ScaleDpi) {
         let real_rc = get_layout_real_rc(c, dpi);
         if real_rc.is_none() {
@@ -116,30 +308,34 @@ impl TLayout for LayoutHorizontal {
             return;
         }
 
-        let mut empty_count = 0;
-        let mut total_val = 0;
-        for child in children.into_iter() {
-            match child.get_inner().size.width {
-                Some(v) => total_val += dpi.scale(v),
-                None => empty_count += 1,
-            };
-        }
-
-        let default_size = if empty_count == 0 {
-            0
-        } else {
-            (real_rc.width() - total_val) / empty_count
-        };
+        let sizes = children
+            .iter()
+            .map(|child| get_child_size(child.as_ref(), real_rc.size(), dpi))
+            .collect::<Vec<_>>();
+        let limits = children
+            .iter()
+            .map(|child| get_child_limits(child.as_ref(), real_rc.size(), dpi))
+            .collect::<Vec<_>>();
+        let widths = distribute(
+            &sizes.iter().map(|s| s.width).collect::<Vec<_>>(),
+            &children
+                .iter()
+                .map(|child| get_weight(child.get_inner().size.width))
+                .collect::<Vec<_>>(),
+            &limits.iter().map(|l| l.0).collect::<Vec<_>>(),
+            real_rc.width(),
+        );
 
         let mut offset = real_rc.left;
-        for child in children {
+        for (((child, size), limits), width) in
+            children.iter_mut().zip(sizes).zip(limits).zip(widths)
+        {
             let rc = layout_uv(
                 real_rc,
                 &mut offset,
-                default_size,
-                &mut empty_count,
+                width,
+                stretch(size.height, limits.1, real_rc.height()),
                 &IPointOpt::new(child.get_inner().pos.top, child.get_inner().pos.bottom),
-                &child.get_inner().size,
                 dpi,
             );
 
@@ -168,6 +364,10 @@ impl LayoutVertical {
 }
 
 impl TLayout for LayoutVertical {
+    fn measure(&self, c: &Container, max: ISize, dpi: &ScaleDpi) -> Option<ISize> {
+        Some(measure_stack(c, max, dpi, true))
+    }
+
     fn update(&self, c: &mut Container, dpi: &ScaleDpi) {
         let real_rc = get_layout_real_rc(c, dpi);
         if real_rc.is_none() {
@@ -180,30 +380,34 @@ impl TLayout for LayoutVertical {
             return;
         }
 
-        let mut empty_count = 0;
-        let mut total_val = 0;
-        for child in children.into_iter() {
-            match child.get_inner().size.height {
-                Some(v) => total_val += dpi.scale(v) as i32,
-                None => empty_count += 1,
-            };
-        }
-
-        let default_size = if empty_count == 0 {
-            0
-        } else {
-            (real_rc.height() - total_val) / empty_count
-        };
+        let sizes = children
+            .iter()
+            .map(|child| get_child_size(child.as_ref(), real_rc.size(), dpi))
+            .collect::<Vec<_>>();
+        let limits = children
+            .iter()
+            .map(|child| get_child_limits(child.as_ref(), real_rc.size(), dpi))
+            .collect::<Vec<_>>();
+        let heights = distribute(
+            &sizes.iter().map(|s| s.height).collect::<Vec<_>>(),
+            &children
+                .iter()
+                .map(|child| get_weight(child.get_inner().size.height))
+                .collect::<Vec<_>>(),
+            &limits.iter().map(|l| l.1).collect::<Vec<_>>(),
+            real_rc.height(),
+        );
 
         let mut offset = real_rc.top;
-        for child in children {
+        for (((child, size), limits), height) in
+            children.iter_mut().zip(sizes).zip(limits).zip(heights)
+        {
             let rc = layout_uv(
                 IRect::new(real_rc.top, real_rc.left, real_rc.bottom, real_rc.right),
                 &mut offset,
-                default_size,
-                &mut empty_count,
+                height,
+                stretch(size.width, limits.0, real_rc.width()),
                 &IPointOpt::new(child.get_inner().pos.left, child.get_inner().pos.right),
-                &ISizeOpt::new(child.get_inner().size.height, child.get_inner().size.width),
                 dpi,
             );
 
@@ -223,44 +427,18 @@ impl TLayout for LayoutVertical {
     }
 }
 
-pub struct LayoutTable {
-    row: usize,
-    line: usize,
-}
+/// Equal cells filled row by row, `row` children a row. The same as a
+/// `LayoutGrid` with `line` rows and `row` columns of `ETrack::Fraction(1.)`.
+pub struct LayoutTable {}
 
 impl LayoutTable {
     pub fn new(row: usize, line: usize) -> Box<dyn TLayout> {
-        Box::new(Self { row, line })
-    }
-}
-
-impl TLayout for LayoutTable {
-    fn update(&self, c: &mut Container, _dpi: &ScaleDpi) {
-        let rect_rc = c.get_inner().real_rc;
-
-        let row_size = rect_rc.width() as usize / self.row;
-        let line_size = rect_rc.height() as usize / self.line;
-
-        let mut count = 0usize;
-
-        for child in c.get_mut_children() {
-            let child = child.as_mut();
-
-            let cur_row = count % self.row;
-            let cur_line = count / self.row;
-            count += 1;
-            if cur_line > self.line {
-                child.get_mut_inner().real_rc = IRect::default();
-                continue;
-            }
-
-            let crc = &mut child.get_mut_inner().real_rc;
-
-            crc.left = rect_rc.left + (row_size * cur_row) as i32;
-            crc.top = rect_rc.top + (line_size * cur_line) as i32;
-            crc.right = crc.left + cur_row as i32;
-            crc.bottom = crc.top + cur_line as i32;
-        }
+        Box::new(LayoutGrid {
+            rows: vec![ETrack::Fraction(1.); line.max(1)],
+            cols: vec![ETrack::Fraction(1.); row.max(1)],
+            fixed_rows: true,
+            ..Default::default()
+        })
     }
 }
 
@@ -278,19 +456,772 @@ impl TLayout for Layout {
         if real_rc.width() <= 0 || real_rc.height() <= 0 {
             return;
         }
+        let parent = real_rc.size();
         for child in c.get_mut_children() {
+            let size = get_child_size(child.as_ref(), parent, dpi);
+            let (lw, lh) = get_child_limits(child.as_ref(), parent, dpi);
             let pos = &child.get_inner().pos;
-            let size = &child.get_inner().size;
+            let x = |u: Option<EUnit>| u.and_then(|u| u.resolve(parent.width, dpi));
+            let y = |u: Option<EUnit>| u.and_then(|u| u.resolve(parent.height, dpi));
+
+            let left = x(pos.left).unwrap_or(0);
+            let top = y(pos.top).unwrap_or(0);
+            let right = x(pos.right).unwrap_or(size.width.unwrap_or(0) + left);
+            let bottom = y(pos.bottom).unwrap_or(size.height.unwrap_or(0) + top);
+
+            let rc = IRect::new(
+                left,
+                top,
+                left + lw.clamp(right - left),
+                top + lh.clamp(bottom - top),
+            );
+            child.get_mut_inner().real_rc = utils::cal_real_rc(&real_rc, rc);
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////
+/// Flex
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum EFlexDirection {
+    Row,
+    RowReverse,
+    Column,
+    ColumnReverse,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum EFlexWrap {
+    NoWrap,
+    Wrap,
+    /// new lines go before the first one on the cross axis
+    WrapReverse,
+}
 
-            let mut rc = IRect::default();
-            rc.left = pos.left.unwrap_or(0);
+/// Where the space left on the main axis goes.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum EJustify {
+    Start,
+    End,
+    Center,
+    SpaceBetween,
+    SpaceAround,
+    SpaceEvenly,
+}
+
+/// Placement on the cross axis inside a line.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum EAlignItems {
+    Start,
+    End,
+    Center,
+    /// fills the line where the cross size is not set
+    Stretch,
+}
+
+/// How a child of a `LayoutFlex` sizes itself, sizes in logical pixels.
+#[derive(PartialEq, Clone, Debug)]
+pub struct FlexItem {
+    /// share of the space left in the line
+    pub grow: f32,
+    /// share of the missing space when the line overflows, weighted by the basis
+    pub shrink: f32,
+    /// main size before growing or shrinking, `size` or the preferred size if `None`
+    pub basis: Option<i32>,
+    /// overrides `LayoutFlex::align_items`
+    pub align_self: Option<EAlignItems>,
+}
+
+impl Default for FlexItem {
+    fn default() -> Self {
+        Self {
+            grow: 0.,
+            shrink: 1.,
+            basis: None,
+            align_self: None,
+        }
+    }
+}
+
+/// Flexbox like layout, children set their part with `TCtrlInner::flex`.
+pub struct LayoutFlex {
+    pub direction: EFlexDirection,
+    pub wrap: EFlexWrap,
+    pub justify: EJustify,
+    pub align_items: EAlignItems,
+    /// between children in a line, logical pixels
+    pub gap: i32,
+    /// between lines, logical pixels
+    pub line_gap: i32,
+}
+
+impl Default for LayoutFlex {
+    fn default() -> Self {
+        Self {
+            direction: EFlexDirection::Row,
+            wrap: EFlexWrap::NoWrap,
+            justify: EJustify::Start,
+            align_items: EAlignItems::Stretch,
+            gap: 0,
+            line_gap: 0,
+        }
+    }
+}
+
+impl LayoutFlex {
+    pub fn new(direction: EFlexDirection) -> Box<dyn TLayout> {
+        Box::new(Self {
+            direction,
+            ..Default::default()
+        })
+    }
+
+    fn is_row(&self) -> bool {
+        matches!(
+            self.direction,
+            EFlexDirection::Row | EFlexDirection::RowReverse
+        )
+    }
+
+    // (main, cross) of a physical size
+    fn uv(&self, size: ISize) -> (i32, i32) {
+        if self.is_row() {
+            (size.width, size.height)
+        } else {
+            (size.height, size.width)
+        }
+    }
+
+    fn get_item(&self, child: &dyn TCtrl, max: ISize, dpi: &ScaleDpi) -> FlexEntry {
+        let inner = child.get_inner();
+        let margin = inner.margin.unwrap_or_default();
+        let (margin_u, margin_v) = self.uv(ISize::new(
+            margin.left + margin.right,
+            margin.top + margin.bottom,
+        ));
 
-            rc.top = pos.top.unwrap_or(0);
+        let size = get_child_size(child, max, dpi);
+        let (u, v) = if self.is_row() {
+            (size.width, size.height)
+        } else {
+            (size.height, size.width)
+        };
+        let pref = || child.get_preferred_size(max, dpi).map(|p| self.uv(p));
+        let (lw, lh) = get_child_limits(child, max, dpi);
+        let (limits, cross_limits) = if self.is_row() { (lw, lh) } else { (lh, lw) };
+
+        // a `Star` main size grows from nothing by its weight
+        let star = match if self.is_row() {
+            inner.size.width
+        } else {
+            inner.size.height
+        } {
+            Some(EUnit::Star(w)) => Some(w.max(0.)),
+            _ => None,
+        };
 
-            rc.right = pos.right.unwrap_or(size.width.unwrap_or(0) + rc.left);
-            rc.bottom = pos.bottom.unwrap_or(size.height.unwrap_or(0) + rc.top);
+        let flex = &inner.flex;
+        let basis = match (flex.basis, star) {
+            (Some(b), _) => dpi.scale(b) + margin_u,
+            (None, Some(_)) => margin_u,
+            (None, None) => u
+                .or_else(|| pref().map(|p| p.0 + margin_u))
+                .unwrap_or(margin_u),
+        };
 
-            child.get_mut_inner().real_rc = utils::cal_real_rc(&real_rc, dpi.scale(rc));
+        FlexEntry {
+            basis: limits.clamp(basis),
+            cross: v.or_else(|| pref().map(|p| cross_limits.clamp(p.1 + margin_v))),
+            fixed_cross: v.is_some(),
+            grow: star.unwrap_or(flex.grow.max(0.)),
+            shrink: flex.shrink.max(0.),
+            align: flex.align_self.unwrap_or(self.align_items),
+            limits,
+            cross_limits,
+        }
+    }
+
+    // ranges of `items` in each line
+    fn split_lines(&self, items: &[FlexEntry], main: i32, gap: i32) -> Vec<Range<usize>> {
+        if self.wrap == EFlexWrap::NoWrap {
+            return vec![0..items.len()];
+        }
+
+        let mut lines = Vec::new();
+        let (mut start, mut used) = (0, 0);
+        for (i, item) in items.iter().enumerate() {
+            if i > start && used + gap + item.basis > main {
+                lines.push(start..i);
+                start = i;
+                used = 0;
+            }
+            used += if i > start { gap } else { 0 } + item.basis;
+        }
+        lines.push(start..items.len());
+        lines
+    }
+
+    // final main sizes of a line after growing or shrinking, items hitting
+    // their limits are frozen there and the line is resolved again
+    fn resolve_line(items: &[FlexEntry], main: i32, gap: i32) -> Vec<i32> {
+        let mut frozen = vec![None; items.len()];
+        loop {
+            let sizes = Self::flex_line(items, &frozen, main, gap);
+            let mut clamped = false;
+            for ((f, item), size) in frozen.iter_mut().zip(items).zip(&sizes) {
+                if f.is_none() && item.limits.clamp(*size) != *size {
+                    *f = Some(item.limits.clamp(*size));
+                    clamped = true;
+                }
+            }
+            if !clamped {
+                return sizes;
+            }
+        }
+    }
+
+    fn flex_line(items: &[FlexEntry], frozen: &[Option<i32>], main: i32, gap: i32) -> Vec<i32> {
+        let bases = items
+            .iter()
+            .zip(frozen)
+            .map(|(i, f)| f.unwrap_or(i.basis))
+            .collect::<Vec<_>>();
+        let used = bases.iter().sum::<i32>() + gap * (items.len() as i32 - 1);
+        if used == main {
+            return bases;
+        }
+        let free = (main - used) as f32;
+
+        let weights = items
+            .iter()
+            .zip(frozen)
+            .map(|(i, f)| {
+                if f.is_some() {
+                    0.
+                } else if free > 0. {
+                    i.grow
+                } else {
+                    i.shrink * i.basis as f32
+                }
+            })
+            .collect::<Vec<_>>();
+        let total = weights.iter().sum::<f32>();
+        if total <= 0. {
+            return bases;
+        }
+
+        // hands out rounding leftovers so the line ends up exact
+        let mut acc = 0.;
+        let mut given = 0;
+        bases
+            .into_iter()
+            .zip(weights)
+            .map(|(basis, w)| {
+                acc += free * w / total;
+                let d = acc.round() as i32 - given;
+                given += d;
+                (basis + d).max(0)
+            })
+            .collect()
+    }
+
+    // (space before the first child, extra space between children)
+    fn justify(&self, free: i32, n: i32) -> (f32, f32) {
+        let free = free.max(0) as f32;
+        let n = n.max(1) as f32;
+        match self.justify {
+            EJustify::Start => (0., 0.),
+            EJustify::End => (free, 0.),
+            EJustify::Center => (free / 2., 0.),
+            EJustify::SpaceBetween if n > 1. => (0., free / (n - 1.)),
+            EJustify::SpaceBetween => (0., 0.),
+            EJustify::SpaceAround => (free / n / 2., free / n),
+            EJustify::SpaceEvenly => (free / (n + 1.), free / (n + 1.)),
+        }
+    }
+}
+
+struct FlexEntry {
+    // outer sizes, with the margin
+    basis: i32,
+    // the preferred size when not set, for the line size and `align`
+    cross: Option<i32>,
+    // set or sized by `auto_size`, not stretched
+    fixed_cross: bool,
+    grow: f32,
+    shrink: f32,
+    align: EAlignItems,
+    limits: Limits,
+    cross_limits: Limits,
+}
+
+impl TLayout for LayoutFlex {
+    fn measure(&self, c: &Container, max: ISize, dpi: &ScaleDpi) -> Option<ISize> {
+        let padding = get_padding(c, dpi);
+        let max = ISize::new(
+            max.width - padding.left - padding.right,
+            max.height - padding.top - padding.bottom,
+        );
+        let gap = dpi.scale(self.gap);
+
+        let items = c
+            .get_children()
+            .iter()
+            .map(|child| self.get_item(child.as_ref(), max, dpi))
+            .collect::<Vec<_>>();
+        let u = items.iter().map(|i| i.basis).sum::<i32>() + gap * (items.len() as i32 - 1).max(0);
+        let v = items.iter().filter_map(|i| i.cross).max().unwrap_or(0);
+
+        let (w, h) = if self.is_row() { (u, v) } else { (v, u) };
+        Some(ISize::new(
+            w + padding.left + padding.right,
+            h + padding.top + padding.bottom,
+        ))
+    }
+
+    fn update(&self, c: &mut Container, dpi: &ScaleDpi) {
+        let real_rc = match get_layout_real_rc(c, dpi) {
+            Some(v) => v,
+            None => return,
+        };
+        let (main, cross) = self.uv(real_rc.size());
+        let gap = dpi.scale(self.gap);
+        let line_gap = dpi.scale(self.line_gap);
+
+        let children = c.get_mut_children();
+        let items = children
+            .iter()
+            .map(|child| self.get_item(child.as_ref(), real_rc.size(), dpi))
+            .collect::<Vec<_>>();
+        let lines = self.split_lines(&items, main, gap);
+
+        // one line takes all the cross space, several share what is left equally
+        let mut line_sizes = lines
+            .iter()
+            .map(|r| {
+                items[r.clone()]
+                    .iter()
+                    .filter_map(|i| i.cross)
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<_>>();
+        if self.wrap == EFlexWrap::NoWrap {
+            line_sizes[0] = cross;
+        } else {
+            let used = line_sizes.iter().sum::<i32>() + line_gap * (lines.len() as i32 - 1);
+            let extra = (cross - used).max(0) / lines.len() as i32;
+            line_sizes.iter_mut().for_each(|s| *s += extra);
+        }
+
+        let reverse_u = matches!(
+            self.direction,
+            EFlexDirection::RowReverse | EFlexDirection::ColumnReverse
+        );
+        let reverse_v = self.wrap == EFlexWrap::WrapReverse;
+
+        let mut line_v = 0;
+        for (range, line_size) in lines.into_iter().zip(line_sizes) {
+            let line = &items[range.clone()];
+            let sizes = Self::resolve_line(line, main, gap);
+            let used = sizes.iter().sum::<i32>() + gap * (sizes.len() as i32 - 1);
+            let (before, between) = self.justify(main - used, sizes.len() as i32);
+
+            let mut u = before;
+            for ((child, item), size) in children[range].iter_mut().zip(line).zip(sizes) {
+                let item_v = item.cross.unwrap_or(0).min(line_size);
+                let (v, v_size) = match item.align {
+                    EAlignItems::Stretch if !item.fixed_cross => {
+                        (0, item.cross_limits.clamp(line_size))
+                    }
+                    EAlignItems::Start | EAlignItems::Stretch => (0, item_v),
+                    EAlignItems::End => (line_size - item_v, item_v),
+                    EAlignItems::Center => ((line_size - item_v) / 2, item_v),
+                };
+
+                let mut u0 = u.round() as i32;
+                let mut v0 = line_v + v;
+                if reverse_u {
+                    u0 = main - u0 - size;
+                }
+                if reverse_v {
+                    v0 = cross - v0 - v_size;
+                }
+
+                let rc = if self.is_row() {
+                    IRect::from_xywh(real_rc.left + u0, real_rc.top + v0, size, v_size)
+                } else {
+                    IRect::from_xywh(real_rc.left + v0, real_rc.top + u0, v_size, size)
+                };
+                let rc = match child.get_inner().margin {
+                    Some(v) => IRect::new(
+                        rc.left + v.left,
+                        rc.top + v.top,
+                        rc.right - v.right,
+                        rc.bottom - v.bottom,
+                    ),
+                    None => rc,
+                };
+                child.get_mut_inner().real_rc = rc;
+
+                u += (size + gap) as f32 + between;
+            }
+            line_v += line_size + line_gap;
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////
+/// Grid
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ETrack {
+    /// logical pixels
+    Fixed(i32),
+    /// as big as the largest child in it
+    Auto,
+    /// share of what fixed and auto tracks leave
+    Fraction(f32),
+}
+
+/// Where a child of a `LayoutGrid` goes, rows and columns count from 0.
+#[derive(PartialEq, Clone, Debug)]
+pub struct GridItem {
+    /// the next free cell in row order when `None`
+    pub row: Option<usize>,
+    pub col: Option<usize>,
+    pub row_span: usize,
+    pub col_span: usize,
+    /// override `LayoutGrid::align_x` / `align_y`
+    pub align_x: Option<EAlignItems>,
+    pub align_y: Option<EAlignItems>,
+}
+
+impl Default for GridItem {
+    fn default() -> Self {
+        Self {
+            row: None,
+            col: None,
+            row_span: 1,
+            col_span: 1,
+            align_x: None,
+            align_y: None,
+        }
+    }
+}
+
+/// Rows and columns of tracks, children set their cell with `TCtrlInner::grid`.
+/// Rows are added as `ETrack::Auto` when the children need more.
+pub struct LayoutGrid {
+    pub rows: Vec<ETrack>,
+    pub cols: Vec<ETrack>,
+    /// logical pixels
+    pub row_gap: i32,
+    pub col_gap: i32,
+    /// placement inside the cell
+    pub align_x: EAlignItems,
+    pub align_y: EAlignItems,
+    // children past the last row are hidden instead
+    fixed_rows: bool,
+}
+
+impl Default for LayoutGrid {
+    fn default() -> Self {
+        Self {
+            rows: Vec::new(),
+            cols: Vec::new(),
+            row_gap: 0,
+            col_gap: 0,
+            align_x: EAlignItems::Stretch,
+            align_y: EAlignItems::Stretch,
+            fixed_rows: false,
+        }
+    }
+}
+
+struct GridCell {
+    rows: Range<usize>,
+    cols: Range<usize>,
+    // outer size set on the child, with the margin
+    size: ChildSize,
+    // `size` or the preferred size when not set, for auto tracks and `align`
+    pref: ChildSize,
+}
+
+impl LayoutGrid {
+    pub fn new(rows: Vec<ETrack>, cols: Vec<ETrack>) -> Box<dyn TLayout> {
+        Box::new(Self {
+            rows,
+            cols,
+            ..Default::default()
+        })
+    }
+
+    fn get_cols(&self) -> Vec<ETrack> {
+        if self.cols.is_empty() {
+            vec![ETrack::Fraction(1.)]
+        } else {
+            self.cols.clone()
+        }
+    }
+
+    // the cell of every child, `None` when it does not fit
+    fn place(&self, c: &Container, max: ISize, dpi: &ScaleDpi) -> Vec<Option<GridCell>> {
+        let ncols = self.get_cols().len();
+        let mut used: Vec<Vec<bool>> = Vec::new();
+        let free = |used: &Vec<Vec<bool>>, rows: &Range<usize>, cols: &Range<usize>| {
+            rows.clone()
+                .all(|r| cols.clone().all(|c| !used.get(r).map_or(false, |l| l[c])))
+        };
+
+        let mut next = 0;
+        c.get_children()
+            .iter()
+            .map(|child| {
+                let grid = &child.get_inner().grid;
+                let col_span = grid.col_span.clamp(1, ncols);
+                let row_span = grid.row_span.max(1);
+
+                let (row, col) = match (grid.row, grid.col) {
+                    (Some(r), Some(c)) => (r, c.min(ncols - col_span)),
+                    (r, c) => {
+                        let c = c.map(|c| c.min(ncols - col_span));
+                        // scans row by row from the last auto placed cell
+                        let mut at = match r {
+                            Some(r) => r * ncols,
+                            None => next,
+                        };
+                        loop {
+                            let (r0, c0) = (at / ncols, c.unwrap_or(at % ncols));
+                            if c0 + col_span <= ncols
+                                && free(&used, &(r0..r0 + row_span), &(c0..c0 + col_span))
+                            {
+                                if grid.row.is_none() && grid.col.is_none() {
+                                    next = at + col_span;
+                                }
+                                break (r0, c0);
+                            }
+                            at += if c.is_some() { ncols } else { 1 };
+                        }
+                    }
+                };
+
+                let rows = row..row + row_span;
+                let cols = col..col + col_span;
+                if self.fixed_rows && rows.end > self.rows.len() {
+                    return None;
+                }
+                while used.len() < rows.end {
+                    used.push(vec![false; ncols]);
+                }
+                for r in rows.clone() {
+                    for c in cols.clone() {
+                        used[r][c] = true;
+                    }
+                }
+
+                let size = get_child_size(child.as_ref(), max, dpi);
+                let mut pref = size;
+                if size.width.is_none() || size.height.is_none() {
+                    let margin = child.get_inner().margin.unwrap_or_default();
+                    let (lw, lh) = get_child_limits(child.as_ref(), max, dpi);
+                    if let Some(p) = child.get_preferred_size(max, dpi) {
+                        pref.width = size
+                            .width
+                            .or(Some(lw.clamp(p.width + margin.left + margin.right)));
+                        pref.height = size
+                            .height
+                            .or(Some(lh.clamp(p.height + margin.top + margin.bottom)));
+                    }
+                }
+                Some(GridCell {
+                    rows,
+                    cols,
+                    size,
+                    pref,
+                })
+            })
+            .collect()
+    }
+
+    // physical sizes of the tracks, fractions only get space when `avail` is set
+    fn resolve_tracks(
+        tracks: &[ETrack],
+        cells: &[(Range<usize>, Option<i32>)],
+        avail: Option<i32>,
+        gap: i32,
+        dpi: &ScaleDpi,
+    ) -> Vec<i32> {
+        // auto tracks, and fractions when measuring, fit the children in one track only
+        let content = |i: usize| {
+            cells
+                .iter()
+                .filter(|(r, _)| r.len() == 1 && r.start == i)
+                .filter_map(|(_, s)| *s)
+                .max()
+                .unwrap_or(0)
+        };
+
+        let mut sizes = tracks
+            .iter()
+            .enumerate()
+            .map(|(i, t)| match (t, avail) {
+                (ETrack::Fixed(v), _) => dpi.scale(*v),
+                (ETrack::Auto, _) | (ETrack::Fraction(_), None) => content(i),
+                (ETrack::Fraction(_), Some(_)) => 0,
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(avail) = avail {
+            let weights = tracks
+                .iter()
+                .map(|t| match t {
+                    ETrack::Fraction(f) => f.max(0.),
+                    _ => 0.,
+                })
+                .collect::<Vec<_>>();
+            let total = weights.iter().sum::<f32>();
+            let left = avail - sizes.iter().sum::<i32>() - gap * (tracks.len() as i32 - 1).max(0);
+            if total > 0. && left > 0 {
+                let mut acc = 0.;
+                let mut given = 0;
+                for (s, w) in sizes.iter_mut().zip(weights) {
+                    acc += left as f32 * w / total;
+                    let d = acc.round() as i32 - given;
+                    given += d;
+                    *s += d;
+                }
+            }
+        }
+        sizes
+    }
+
+    fn resolve(
+        &self,
+        cells: &[Option<GridCell>],
+        avail: Option<ISize>,
+        dpi: &ScaleDpi,
+    ) -> (Vec<i32>, Vec<i32>) {
+        let cells = cells.iter().flatten();
+        let nrows = cells.clone().map(|c| c.rows.end).max().unwrap_or(0);
+        let mut rows = self.rows.clone();
+        if rows.len() < nrows {
+            rows.resize(nrows, ETrack::Auto);
+        }
+
+        let col_cells = cells
+            .clone()
+            .map(|c| (c.cols.clone(), c.pref.width))
+            .collect::<Vec<_>>();
+        let row_cells = cells
+            .map(|c| (c.rows.clone(), c.pref.height))
+            .collect::<Vec<_>>();
+        (
+            Self::resolve_tracks(
+                &rows,
+                &row_cells,
+                avail.map(|s| s.height),
+                dpi.scale(self.row_gap),
+                dpi,
+            ),
+            Self::resolve_tracks(
+                &self.get_cols(),
+                &col_cells,
+                avail.map(|s| s.width),
+                dpi.scale(self.col_gap),
+                dpi,
+            ),
+        )
+    }
+}
+
+// start and length of `range` in `tracks` laid out from `start`
+fn track_span(tracks: &[i32], range: &Range<usize>, start: i32, gap: i32) -> (i32, i32) {
+    let offset = tracks[..range.start].iter().map(|t| t + gap).sum::<i32>();
+    let len = tracks[range.clone()].iter().sum::<i32>() + gap * (range.len() as i32 - 1);
+    (start + offset, len)
+}
+
+// start and length of a child in a cell, stretched unless `size` is set
+fn align_in(
+    align: EAlignItems,
+    start: i32,
+    cell: i32,
+    size: Option<i32>,
+    pref: Option<i32>,
+) -> (i32, i32) {
+    let size = match (align, size) {
+        (EAlignItems::Stretch, None) => return (start, cell),
+        (EAlignItems::Stretch, Some(v)) => v.min(cell),
+        _ => pref.unwrap_or(0).min(cell),
+    };
+    match align {
+        EAlignItems::Start | EAlignItems::Stretch => (start, size),
+        EAlignItems::End => (start + cell - size, size),
+        EAlignItems::Center => (start + (cell - size) / 2, size),
+    }
+}
+
+impl TLayout for LayoutGrid {
+    fn measure(&self, c: &Container, max: ISize, dpi: &ScaleDpi) -> Option<ISize> {
+        let padding = get_padding(c, dpi);
+        let max = ISize::new(
+            max.width - padding.left - padding.right,
+            max.height - padding.top - padding.bottom,
+        );
+
+        let cells = self.place(c, max, dpi);
+        let (rows, cols) = self.resolve(&cells, None, dpi);
+        let total = |tracks: &[i32], gap: i32| {
+            tracks.iter().sum::<i32>() + dpi.scale(gap) * (tracks.len() as i32 - 1).max(0)
+        };
+        Some(ISize::new(
+            total(&cols, self.col_gap) + padding.left + padding.right,
+            total(&rows, self.row_gap) + padding.top + padding.bottom,
+        ))
+    }
+
+    fn update(&self, c: &mut Container, dpi: &ScaleDpi) {
+        let real_rc = match get_layout_real_rc(c, dpi) {
+            Some(v) => v,
+            None => return,
+        };
+
+        let cells = self.place(c, real_rc.size(), dpi);
+        let (rows, cols) = self.resolve(&cells, Some(real_rc.size()), dpi);
+        let row_gap = dpi.scale(self.row_gap);
+        let col_gap = dpi.scale(self.col_gap);
+
+        for (child, cell) in c.get_mut_children().iter_mut().zip(cells) {
+            let cell = match cell {
+                Some(v) => v,
+                None => {
+                    child.get_mut_inner().real_rc = IRect::default();
+                    continue;
+                }
+            };
+
+            let grid = &child.get_inner().grid;
+            let (x, w) = track_span(&cols, &cell.cols, real_rc.left, col_gap);
+            let (y, h) = track_span(&rows, &cell.rows, real_rc.top, row_gap);
+            let align_x = grid.align_x.unwrap_or(self.align_x);
+            let align_y = grid.align_y.unwrap_or(self.align_y);
+            let (x, w) = align_in(align_x, x, w, cell.size.width, cell.pref.width);
+            let (y, h) = align_in(align_y, y, h, cell.size.height, cell.pref.height);
+            // stretched children stay within their limits
+            let (lw, lh) = get_child_limits(child.as_ref(), real_rc.size(), dpi);
+            let (w, h) = (lw.clamp(w), lh.clamp(h));
+
+            let rc = IRect::from_xywh(x, y, w, h);
+            let rc = match child.get_inner().margin {
+                Some(v) => IRect::new(
+                    rc.left + v.left,
+                    rc.top + v.top,
+                    rc.right - v.right,
+                    rc.bottom - v.bottom,
+                ),
+                None => rc,
+            };
+            child.get_mut_inner().real_rc = rc;
         }
     }
 }
@@ -1,30 +1,64 @@
+pub mod event;
 pub mod layout;
 pub mod loader;
 pub mod styles;
 pub mod ui_button;
+pub mod ui_check_box;
 pub mod ui_container;
 pub mod ui_ctrl;
+pub mod ui_edit;
+pub mod ui_list_view;
+pub mod ui_radio_button;
+pub mod ui_rich_text;
+pub mod ui_scroll_bar;
+pub mod ui_scroll_view;
 pub mod ui_text;
+pub mod ui_text_area;
+pub mod ui_tree_view;
 
+pub use event::{EEventPhase, EUIEvent, EventCtx, TEventHandler, UIEvent};
 pub use layout::TLayout;
 pub use ui_ctrl::TCtrl;
 
 pub use ui_button::Button;
-pub use ui_container::Container;
+pub use ui_check_box::{CheckBox, ECheckState};
+pub use ui_container::{Container, EClip};
 pub use ui_ctrl::Ctrl;
-pub use ui_text::Text;
+pub use ui_edit::Edit;
+pub use ui_list_view::{ListView, TListSource};
+pub use ui_radio_button::{RadioButton, RadioGroup};
+pub use ui_rich_text::{RichText, TextSpan};
+pub use ui_scroll_bar::ScrollBar;
+pub use ui_scroll_view::{EScrollBarMode, ScrollView};
+pub use ui_text::{EAlign, EOverflow, EWrapMode, Text};
+pub use ui_text_area::TextArea;
+pub use ui_tree_view::{TTreeSource, TreeView};
 
-pub use layout::{Layout, LayoutHorizontal, LayoutVertical};
+pub use layout::{
+    EAlignItems, EFlexDirection, EFlexWrap, EJustify, ETrack, FlexItem, GridItem, Layout,
+    LayoutFlex, LayoutGrid, LayoutHorizontal, LayoutVertical,
+};
 
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum ECtrlType {
     Unknow,
     BaseCtrl,
     Container,
     Text,
     Button,
+    Edit,
+    TextArea,
+    RichText,
+    ScrollBar,
+    ScrollView,
+    ListView,
+    TreeView,
+    CheckBox,
+    RadioButton,
+    RadioGroup,
 }
 
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum ECtrlStatus {
     Default,
     Hover,
@@ -5,6 +5,8 @@ pub enum EUIStatus {
     Hover,
     Press,
     Disable,
+    Focused,
+    Checked,
 }
 impl Default for EUIStatus {
     fn default() -> Self {
@@ -29,6 +31,9 @@ pub struct Styles<T: Default + TStyle> {
     pub hover: Option<T>,
     pub press: Option<T>,
     pub disable: Option<T>,
+    pub focused: Option<T>,
+    /// a checked `CheckBox` or `RadioButton`
+    pub checked: Option<T>,
 }
 
 impl<T: Default + TStyle> Styles<T> {
@@ -38,6 +43,8 @@ impl<T: Default + TStyle> Styles<T> {
             EUIStatus::Hover => self.hover.as_ref().map(|f| f.get_inner()),
             EUIStatus::Press => self.press.as_ref().map(|f| f.get_inner()),
             EUIStatus::Disable => self.disable.as_ref().map(|f| f.get_inner()),
+            EUIStatus::Focused => self.focused.as_ref().map(|f| f.get_inner()),
+            EUIStatus::Checked => self.checked.as_ref().map(|f| f.get_inner()),
         }
     }
 
@@ -47,6 +54,8 @@ impl<T: Default + TStyle> Styles<T> {
             EUIStatus::Hover => self.hover.as_mut().map(|f| f.get_mut_inner()),
             EUIStatus::Press => self.press.as_mut().map(|f| f.get_mut_inner()),
             EUIStatus::Disable => self.disable.as_mut().map(|f| f.get_mut_inner()),
+            EUIStatus::Focused => self.focused.as_mut().map(|f| f.get_mut_inner()),
+            EUIStatus::Checked => self.checked.as_mut().map(|f| f.get_mut_inner()),
         }
     }
 }
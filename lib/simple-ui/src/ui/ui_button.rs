@@ -1,16 +1,21 @@
-use crate::ui::{
-    styles::{EUIStatus, StyleInner, TStyle},
-    ui_ctrl::{BaseCtrl, CtrlStyle, TCtrlInner},
-    ECtrlType, TCtrl,
+use crate::{
+    font::{self, FontDesc},
+    input::EKey,
+    ui::{
+        styles::{EUIStatus, StyleInner, TStyle},
+        ui_ctrl::{BaseCtrl, CtrlStyle, TCtrlInner},
+        ECtrlStatus, ECtrlType, EUIEvent, TCtrl, UIEvent,
+    },
+    utils::ScaleDpi,
+    Color, IPoint, ISize,
 };
+use skia_safe::{Canvas, Font, Paint, Point};
 
-pub trait TButtonDelegate {
-    fn on_click(&self);
-}
-
-#[derive(Default)]
 pub struct ButtonStyle {
     base: CtrlStyle,
+
+    pub font: FontDesc,
+    pub font_color: Color,
 }
 
 impl TStyle for ButtonStyle {
@@ -22,16 +27,51 @@ impl TStyle for ButtonStyle {
     }
 }
 
-#[derive(Default)]
+impl Default for ButtonStyle {
+    fn default() -> Self {
+        Self {
+            base: CtrlStyle::default(),
+            font: FontDesc::default(),
+            font_color: Color::BLACK,
+        }
+    }
+}
+
+/// Clicks arrive as `EUIEvent::Click`, see `TCtrlInner::add_handler`.
+/// Focusable by default, Enter or Space clicks it while focused.
 pub struct Button {
     pub ctrl: BaseCtrl<ButtonStyle>,
-    delegate: Option<Box<dyn TButtonDelegate>>,
+
+    label: String,
+    font: Font,
+    line_height: f32,
+    ascent: f32,
+}
+
+impl Default for Button {
+    fn default() -> Self {
+        let mut ctrl = BaseCtrl::<ButtonStyle>::default();
+        ctrl.inner.focusable = true;
+        Self {
+            ctrl,
+            label: String::new(),
+            font: Font::default(),
+            line_height: 0.,
+            ascent: 0.,
+        }
+    }
 }
 
 impl Button {
-    pub fn set_delegate(&mut self, delegate: Box<dyn TButtonDelegate>) -> &mut Self {
-        self.delegate = Some(delegate);
-        self
+    pub fn new(label: &str) -> Self {
+        let mut btn = Self::default();
+        btn.label = label.to_string();
+        btn
+    }
+
+    /// one row centered in the button
+    pub fn set_label(&mut self, t: &str) {
+        self.label = t.to_string();
     }
 }
 
@@ -55,9 +95,61 @@ impl TCtrl for Button {
     fn type_(&self) -> ECtrlType {
         ECtrlType::Button
     }
-    fn render(&self, canvas: &mut skia_safe::Canvas, dpi: &crate::utils::ScaleDpi) {
-        self.ctrl
-            .inner
-            .render(canvas, dpi, &self.ctrl.get_cur_style().base);
+    fn get_status(&self) -> ECtrlStatus {
+        self.ctrl.status
+    }
+    fn set_status(&mut self, status: ECtrlStatus) {
+        self.ctrl.status = status;
+    }
+    fn get_text(&self) -> Option<&str> {
+        Some(&self.label)
+    }
+
+    /// the label with the `padding` around it
+    fn get_preferred_size(&self, _max: ISize, dpi: &ScaleDpi) -> Option<ISize> {
+        let padding = dpi.scale(self.ctrl.inner.padding.unwrap_or_default());
+        let w = font::measure_text(&self.font, &self.label).ceil() as i32;
+        let h = self.line_height.ceil() as i32;
+        Some(ISize::new(
+            w + padding.left + padding.right,
+            h + padding.top + padding.bottom,
+        ))
+    }
+
+    fn update_dpi(&mut self, dpi: &ScaleDpi) {
+        self.font = self.ctrl.get_cur_style().font.make_font(dpi);
+        let (_, m) = self.font.metrics();
+        self.line_height = m.descent - m.ascent + m.leading;
+        self.ascent = -m.ascent;
+    }
+
+    fn on_event(&mut self, event: &mut UIEvent) {
+        if !event.is_target() || self.ctrl.status == ECtrlStatus::Disable {
+            return;
+        }
+        if let EUIEvent::KeyDown(input) = &event.kind {
+            if matches!(input.key, EKey::Enter | EKey::Space) {
+                let rc = self.ctrl.inner.real_rc;
+                let pos = IPoint::new(rc.left + rc.width() / 2, rc.top + rc.height() / 2);
+                event.post(EUIEvent::Click { pos });
+                event.stop_propagation();
+            }
+        }
+    }
+    fn render(&self, canvas: &mut Canvas, dpi: &ScaleDpi) {
+        let style = self.ctrl.get_cur_style();
+        self.ctrl.inner.render(canvas, dpi, &style.base);
+        if self.label.is_empty() {
+            return;
+        }
+
+        let mut paint = Paint::default();
+        paint.set_color(style.font_color);
+        paint.set_anti_alias(true);
+        let rc = self.ctrl.inner.real_rc;
+        let w = font::measure_text(&self.font, &self.label);
+        let x = rc.left as f32 + (rc.width() as f32 - w) / 2.;
+        let y = rc.top as f32 + (rc.height() as f32 - self.line_height) / 2. + self.ascent;
+        font::draw_text(canvas, &self.label, Point::new(x, y), &self.font, &paint);
     }
 }
@@ -0,0 +1,293 @@
+use crate::{
+    font::{self, FontDesc},
+    input::EKey,
+    ui::{
+        styles::{EUIStatus, StyleInner, TStyle},
+        ui_ctrl::{BaseCtrl, CtrlStyle, TCtrlInner},
+        ECtrlStatus, ECtrlType, EUIEvent, TCtrl, UIEvent,
+    },
+    utils::ScaleDpi,
+    Color, IPoint, IRect, ISize,
+};
+use skia_safe::{paint::Style, Canvas, Font, Paint, Path, Point, Rect};
+
+// logical pixels between the box and the label
+const GAP: i32 = 6;
+
+/// Style of a `CheckBox` or `RadioButton`, `Styles::checked` is used while
+/// it is checked.
+pub struct CheckStyle {
+    base: CtrlStyle,
+
+    pub font: FontDesc,
+    pub font_color: Color,
+    /// logical pixels, the box or circle left of the label
+    pub box_size: i32,
+    pub box_color: Color,
+    pub box_border_color: Color,
+    /// the check, dash or dot
+    pub mark_color: Color,
+}
+
+impl TStyle for CheckStyle {
+    fn get_inner(&self) -> &StyleInner {
+        &self.base
+    }
+    fn get_mut_inner(&mut self) -> &mut StyleInner {
+        &mut self.base
+    }
+}
+
+impl Default for CheckStyle {
+    fn default() -> Self {
+        Self {
+            base: CtrlStyle::default(),
+            font: FontDesc::default(),
+            font_color: Color::BLACK,
+            box_size: 14,
+            box_color: Color::WHITE,
+            box_border_color: Color::from_rgb(0x76, 0x76, 0x76),
+            mark_color: Color::from_rgb(0x1a, 0x5f, 0xd0),
+        }
+    }
+}
+
+// the box on the left and one row of text after it, shared with `RadioButton`
+pub(crate) struct CheckLabel {
+    pub(crate) text: String,
+    font: Font,
+    line_height: f32,
+    ascent: f32,
+}
+
+impl CheckLabel {
+    pub(crate) fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            font: Font::default(),
+            line_height: 0.,
+            ascent: 0.,
+        }
+    }
+
+    pub(crate) fn update(&mut self, style: &CheckStyle, dpi: &ScaleDpi) {
+        self.font = style.font.make_font(dpi);
+        let (_, m) = self.font.metrics();
+        self.line_height = m.descent - m.ascent + m.leading;
+        self.ascent = -m.ascent;
+    }
+
+    pub(crate) fn measure(&self, style: &CheckStyle, dpi: &ScaleDpi) -> ISize {
+        let size = dpi.scale(style.box_size);
+        if self.text.is_empty() {
+            return ISize::new(size, size);
+        }
+        let w = font::measure_text(&self.font, &self.text).ceil() as i32;
+        ISize::new(
+            size + dpi.scale(GAP) + w,
+            size.max(self.line_height.ceil() as i32),
+        )
+    }
+
+    // vertically centered at the left of `rc`
+    pub(crate) fn get_box_rc(&self, rc: &IRect, style: &CheckStyle, dpi: &ScaleDpi) -> Rect {
+        let size = dpi.scale(style.box_size);
+        let top = rc.top + (rc.height() - size) / 2;
+        Rect::from(IRect::new(rc.left, top, rc.left + size, top + size))
+    }
+
+    pub(crate) fn render(
+        &self,
+        canvas: &mut Canvas,
+        rc: &IRect,
+        style: &CheckStyle,
+        dpi: &ScaleDpi,
+    ) {
+        let mut paint = Paint::default();
+        paint.set_color(style.font_color);
+        paint.set_anti_alias(true);
+
+        let x = rc.left + dpi.scale(style.box_size) + dpi.scale(GAP);
+        let y = rc.top as f32 + (rc.height() as f32 - self.line_height) / 2. + self.ascent;
+        font::draw_text(
+            canvas,
+            &self.text,
+            Point::new(x as f32, y),
+            &self.font,
+            &paint,
+        );
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ECheckState {
+    Unchecked,
+    Checked,
+    /// neither, e.g. some of the items it stands for are checked
+    Indeterminate,
+}
+
+/// A box with a label. Click or Space while focused flips it and posts
+/// `EUIEvent::Toggled`, `tri_state` lets the user pick `Indeterminate` too.
+pub struct CheckBox {
+    pub ctrl: BaseCtrl<CheckStyle>,
+
+    /// a click goes Unchecked, Checked, Indeterminate and over again,
+    /// otherwise only `set_state` gives `Indeterminate`
+    pub tri_state: bool,
+
+    state: ECheckState,
+    label: CheckLabel,
+    dpi_cache: ScaleDpi,
+}
+
+impl CheckBox {
+    pub fn new(label: &str) -> Self {
+        let mut ctrl = BaseCtrl::<CheckStyle>::default();
+        ctrl.inner.focusable = true;
+
+        Self {
+            ctrl,
+            tri_state: false,
+            state: ECheckState::Unchecked,
+            label: CheckLabel::new(label),
+            dpi_cache: ScaleDpi::default(),
+        }
+    }
+
+    pub fn set_label(&mut self, t: &str) {
+        self.label.text = t.to_string();
+    }
+
+    pub fn get_state(&self) -> ECheckState {
+        self.state
+    }
+    /// no event is posted
+    pub fn set_state(&mut self, state: ECheckState) {
+        self.state = state;
+        self.ctrl.inner.checked = state == ECheckState::Checked;
+        self.label
+            .update(self.ctrl.get_cur_style(), &self.dpi_cache);
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.state == ECheckState::Checked
+    }
+    pub fn set_checked(&mut self, checked: bool) {
+        self.set_state(match checked {
+            true => ECheckState::Checked,
+            false => ECheckState::Unchecked,
+        });
+    }
+
+    // the state after a click
+    fn get_next_state(&self) -> ECheckState {
+        match self.state {
+            ECheckState::Unchecked => ECheckState::Checked,
+            ECheckState::Checked if self.tri_state => ECheckState::Indeterminate,
+            ECheckState::Checked => ECheckState::Unchecked,
+            ECheckState::Indeterminate if self.tri_state => ECheckState::Unchecked,
+            ECheckState::Indeterminate => ECheckState::Checked,
+        }
+    }
+}
+
+impl TCtrl for CheckBox {
+    fn get_inner(&self) -> &TCtrlInner {
+        &self.ctrl.inner
+    }
+    fn get_mut_inner(&mut self) -> &mut TCtrlInner {
+        &mut self.ctrl.inner
+    }
+    fn get_style(&self, status: EUIStatus) -> Option<&StyleInner> {
+        self.ctrl.styles.get_inner_style(status)
+    }
+    fn get_mut_style(&mut self, status: EUIStatus) -> Option<&mut StyleInner> {
+        self.ctrl.styles.get_mut_inner_style(status)
+    }
+    fn type_name(&self) -> &str {
+        "CheckBox"
+    }
+    fn type_(&self) -> ECtrlType {
+        ECtrlType::CheckBox
+    }
+    fn get_status(&self) -> ECtrlStatus {
+        self.ctrl.status
+    }
+    fn set_status(&mut self, status: ECtrlStatus) {
+        self.ctrl.status = status;
+    }
+    fn get_text(&self) -> Option<&str> {
+        Some(&self.label.text)
+    }
+
+    fn get_preferred_size(&self, _max: ISize, dpi: &ScaleDpi) -> Option<ISize> {
+        Some(self.label.measure(self.ctrl.get_cur_style(), dpi))
+    }
+
+    fn update_dpi(&mut self, dpi: &ScaleDpi) {
+        self.dpi_cache = dpi.clone();
+        self.label.update(self.ctrl.get_cur_style(), dpi);
+    }
+
+    fn on_event(&mut self, event: &mut UIEvent) {
+        if !event.is_target() || self.ctrl.status == ECtrlStatus::Disable {
+            return;
+        }
+        match &event.kind {
+            EUIEvent::Click { .. } => {
+                self.set_state(self.get_next_state());
+                event.post(EUIEvent::Toggled);
+                event.request_redraw();
+            }
+            EUIEvent::KeyDown(input) if input.key == EKey::Space => {
+                let rc = self.ctrl.inner.real_rc;
+                let pos = IPoint::new(rc.left + rc.width() / 2, rc.top + rc.height() / 2);
+                event.post(EUIEvent::Click { pos });
+                event.stop_propagation();
+            }
+            _ => (),
+        }
+    }
+
+    fn render(&self, canvas: &mut Canvas, dpi: &ScaleDpi) {
+        if self.ctrl.inner.empty_paint() {
+            return;
+        }
+        let style = self.ctrl.get_cur_style();
+        self.ctrl.inner.render(canvas, dpi, &style.base);
+
+        let rc = self.ctrl.inner.real_rc;
+        let b = self.label.get_box_rc(&rc, style, dpi);
+        let round = dpi.scale(2) as f32;
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_color(style.box_color);
+        canvas.draw_round_rect(b, round, round, &paint);
+
+        let w = dpi.scale(1) as f32;
+        paint.set_style(Style::Stroke);
+        paint.set_stroke_width(w);
+        paint.set_color(style.box_border_color);
+        canvas.draw_round_rect(b.with_inset((w / 2., w / 2.)), round, round, &paint);
+
+        paint.set_stroke_width(dpi.scale(2) as f32);
+        paint.set_color(style.mark_color);
+        let at = |x: f32, y: f32| Point::new(b.left + b.width() * x, b.top + b.height() * y);
+        match self.state {
+            ECheckState::Checked => {
+                let mut path = Path::new();
+                path.move_to(at(0.22, 0.52));
+                path.line_to(at(0.42, 0.72));
+                path.line_to(at(0.78, 0.3));
+                canvas.draw_path(&path, &paint);
+            }
+            ECheckState::Indeterminate => {
+                canvas.draw_line(at(0.25, 0.5), at(0.75, 0.5), &paint);
+            }
+            ECheckState::Unchecked => (),
+        }
+
+        self.label.render(canvas, &rc, style, dpi);
+    }
+}
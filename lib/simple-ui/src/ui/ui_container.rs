@@ -2,19 +2,32 @@ use crate::{
     ui::{
         styles::{EUIStatus, StyleInner},
         ui_ctrl::{BaseCtrl, CtrlStyle, TCtrlInner},
-        ECtrlType, TCtrl, TLayout,
+        ECtrlStatus, ECtrlType, TCtrl, TLayout,
     },
     utils::{in_rc, ScaleDpi},
     IPoint, IRect, ISize,
 };
-use as_any::Downcast;
+use skia_safe::{RRect, Rect};
 use std::rc::Rc;
 
+/// Where a `Container` draws and hits its children, `Rect` by default.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum EClip {
+    /// anywhere, children pushed out by `margin` or `pos` draw over the siblings
+    None,
+    Rect,
+    /// the rect with the corners of `TCtrlInner::round`
+    Round,
+}
+
 pub struct Container {
     pub ctrl: BaseCtrl<CtrlStyle>,
 
     layout: Rc<Box<dyn TLayout>>,
     children: Vec<Box<dyn TCtrl>>,
+    pub clip: EClip,
+    // replaces `clip` for the content of a `ScrollView`
+    pub(crate) viewport: Option<IRect>,
 }
 
 impl Container {
@@ -23,6 +36,8 @@ impl Container {
             ctrl: BaseCtrl::default(),
             layout: Rc::new(layout),
             children: Vec::new(),
+            clip: EClip::Rect,
+            viewport: None,
         }
     }
 
@@ -51,6 +66,21 @@ impl Container {
         self.children.push(c);
     }
 
+    /// the area the children are drawn and hit in, `None` is anywhere
+    pub fn get_clip(&self) -> Option<RRect> {
+        if let Some(vp) = self.viewport {
+            return Some(RRect::new_rect(Rect::from(vp)));
+        }
+
+        let rc = Rect::from(self.ctrl.inner.real_rc);
+        let round = self.ctrl.inner.round as f32;
+        match self.clip {
+            EClip::None => None,
+            EClip::Rect => Some(RRect::new_rect(rc)),
+            EClip::Round => Some(RRect::new_rect_xy(rc, round, round)),
+        }
+    }
+
     pub fn get_ctrl_by_name(&self, name: &str) -> Option<&dyn TCtrl> {
         for child in self.children.iter().rev() {
             let child = child.as_ref();
@@ -58,8 +88,7 @@ impl Container {
                 return Some(child);
             }
 
-            if child.is_container() {
-                let r = child.downcast_ref::<Self>().unwrap();
+            if let Some(r) = child.as_container() {
                 let r = r.get_ctrl_by_name(name);
                 if r.is_some() {
                     return r;
@@ -77,8 +106,7 @@ impl Container {
                 return Some(child);
             }
 
-            if child.is_container() {
-                let r = child.downcast_mut::<Self>().unwrap();
+            if let Some(r) = child.as_mut_container() {
                 let r = r.get_mut_ctrl_by_name(name);
                 if r.is_some() {
                     return r;
@@ -89,18 +117,94 @@ impl Container {
     }
 
     pub fn get_ctrl_by_pos(&self, point: &IPoint) -> &dyn TCtrl {
-        for child in self.children.iter().rev() {
-            if in_rc(&child.get_inner().real_rc, &point) {
-                let child = child.as_ref();
-                return if child.is_container() {
-                    let s = child.downcast_ref::<Self>().unwrap();
-                    s.get_ctrl_by_pos(point)
-                } else {
-                    child
-                };
+        let path = self.get_ctrl_path_by_pos(point);
+        self.get_ctrl_by_path(&path).unwrap_or(self)
+    }
+
+    /// child indexes from this container down to the control at `point`,
+    /// empty if it is the container itself
+    pub fn get_ctrl_path_by_pos(&self, point: &IPoint) -> Vec<usize> {
+        let hit = Rect::from_xywh(point.x as f32, point.y as f32, 1., 1.);
+        if self.get_clip().map_or(false, |clip| !clip.contains(hit)) {
+            return Vec::new();
+        }
+
+        for (i, child) in self.children.iter().enumerate().rev() {
+            let child = child.as_ref();
+            // without a clip the children can be hit outside of the container
+            let mut sub = child
+                .as_container()
+                .map(|s| s.get_ctrl_path_by_pos(point))
+                .unwrap_or_default();
+            if !sub.is_empty() || in_rc(&child.get_inner().real_rc, point) {
+                let mut path = vec![i];
+                path.append(&mut sub);
+                return path;
             }
         }
-        self
+        Vec::new()
+    }
+
+    pub fn get_ctrl_by_path(&self, path: &[usize]) -> Option<&dyn TCtrl> {
+        let (i, rest) = match path.split_first() {
+            Some(v) => v,
+            None => return Some(self),
+        };
+        let child = self.children.get(*i)?.as_ref();
+        if rest.is_empty() {
+            return Some(child);
+        }
+
+        child.as_container()?.get_ctrl_by_path(rest)
+    }
+
+    pub fn get_mut_ctrl_by_path(&mut self, path: &[usize]) -> Option<&mut dyn TCtrl> {
+        let (i, rest) = match path.split_first() {
+            Some(v) => v,
+            None => return Some(self),
+        };
+        let child = self.children.get_mut(*i)?.as_mut();
+        if rest.is_empty() {
+            return Some(child);
+        }
+
+        child.as_mut_container()?.get_mut_ctrl_by_path(rest)
+    }
+
+    /// Paths of the controls Tab visits, in order, see `TCtrlInner::tab_index`.
+    /// Disabled and zero sized controls are left out.
+    pub fn get_tab_order(&self) -> Vec<Vec<usize>> {
+        let mut all = Vec::new();
+        self.collect_focusable(&mut Vec::new(), &mut all);
+
+        // stable, so the tree order is kept for equal keys
+        all.sort_by_key(|(index, _)| match index {
+            Some(i) if *i > 0 => (0, *i),
+            _ => (1, 0),
+        });
+        all.into_iter()
+            .filter(|(index, _)| !matches!(index, Some(i) if *i < 0))
+            .map(|(_, path)| path)
+            .collect()
+    }
+
+    fn collect_focusable(&self, prefix: &mut Vec<usize>, out: &mut Vec<(Option<i32>, Vec<usize>)>) {
+        for (i, child) in self.children.iter().enumerate() {
+            let child = child.as_ref();
+            let inner = child.get_inner();
+            if child.get_status() == ECtrlStatus::Disable || inner.empty_paint() {
+                continue;
+            }
+
+            prefix.push(i);
+            if inner.focusable {
+                out.push((inner.tab_index, prefix.clone()));
+            }
+            if let Some(s) = child.as_container() {
+                s.collect_focusable(prefix, out);
+            }
+            prefix.pop();
+        }
     }
 }
 
@@ -124,6 +228,12 @@ impl TCtrl for Container {
     fn type_(&self) -> ECtrlType {
         ECtrlType::Container
     }
+    fn get_status(&self) -> ECtrlStatus {
+        self.ctrl.status
+    }
+    fn set_status(&mut self, status: ECtrlStatus) {
+        self.ctrl.status = status;
+    }
 
     fn update(&mut self, dpi: &ScaleDpi) {
         self.layout.clone().update(self, dpi);
@@ -133,6 +243,15 @@ impl TCtrl for Container {
             child.update(dpi);
         }
     }
+    fn get_preferred_size(&self, max: ISize, dpi: &ScaleDpi) -> Option<ISize> {
+        self.layout.measure(self, max, dpi)
+    }
+    fn as_container(&self) -> Option<&Container> {
+        Some(self)
+    }
+    fn as_mut_container(&mut self) -> Option<&mut Container> {
+        Some(self)
+    }
     fn render(&self, canvas: &mut skia_safe::Canvas, dpi: &ScaleDpi) {
         if self.ctrl.inner.empty_paint() {
             return;
@@ -141,9 +260,17 @@ impl TCtrl for Container {
             .inner
             .render(canvas, dpi, self.ctrl.get_cur_style());
 
+        let clip = self.get_clip();
+        if let Some(clip) = clip {
+            canvas.save();
+            canvas.clip_rrect(clip, None, true);
+        }
         for child in &self.children {
             child.render(canvas, dpi);
         }
+        if clip.is_some() {
+            canvas.restore();
+        }
     }
 
     fn update_dpi(&mut self, dpi: &ScaleDpi) {
@@ -151,8 +278,8 @@ impl TCtrl for Container {
             let child = child.as_mut();
             child.update_dpi(dpi);
 
-            if child.is_container() {
-                child.downcast_mut::<Self>().unwrap().update_dpi(dpi);
+            if let Some(s) = child.as_mut_container() {
+                s.update_dpi(dpi);
             }
         }
     }
@@ -1,10 +1,12 @@
 use crate::{
     ui::{
+        event::{EUIEvent, EventCtx, TEventHandler, TargetHandler, UIEvent},
+        layout::{FlexItem, GridItem},
         styles::{EUIStatus, StyleInner, Styles, TStyle},
-        ECtrlStatus, ECtrlType,
+        Container, ECtrlStatus, ECtrlType,
     },
     utils::{IRectOpt, ISizeOpt, ScaleDpi},
-    IRect,
+    IRect, ISize,
 };
 use as_any::AsAny;
 use skia_safe::{Canvas, Paint, Rect};
@@ -18,7 +20,22 @@ pub trait TCtrl: AsAny {
     fn type_name(&self) -> &str;
     fn type_(&self) -> ECtrlType;
     fn is_container(&self) -> bool {
-        self.type_() == ECtrlType::Container
+        self.as_container().is_some()
+    }
+    /// the `Container` holding the children, e.g. the content of a `ScrollView`
+    fn as_container(&self) -> Option<&Container> {
+        None
+    }
+    fn as_mut_container(&mut self) -> Option<&mut Container> {
+        None
+    }
+    /// controls without states are always `ECtrlStatus::Default`
+    fn get_status(&self) -> ECtrlStatus {
+        ECtrlStatus::Default
+    }
+    fn set_status(&mut self, _status: ECtrlStatus) {}
+    fn get_text(&self) -> Option<&str> {
+        None
     }
 
     fn init(&mut self) -> Result<(), ()> {
@@ -28,6 +45,15 @@ pub trait TCtrl: AsAny {
     fn render(&self, canvas: &mut Canvas, dpi: &ScaleDpi);
     fn update_dpi(&mut self, _dpi: &ScaleDpi) {}
 
+    /// Size the content wants inside `max`, both in physical pixels. Layouts
+    /// ask for it when `TCtrlInner::auto_size` is set, `None` has no such size.
+    fn get_preferred_size(&self, _max: ISize, _dpi: &ScaleDpi) -> Option<ISize> {
+        None
+    }
+
+    /// built-in behavior of the control, called before the handlers in `TCtrlInner`
+    fn on_event(&mut self, _event: &mut UIEvent) {}
+
     fn get_real_rc(&self) -> &IRect {
         &self.get_inner().real_rc
     }
@@ -52,12 +78,130 @@ pub struct TCtrlInner {
     pub content_rc: IRect,
     pub size: ISizeOpt,
     pub pos: IRectOpt,
+    /// bounds of the size, the layouts keep the control in between and give
+    /// the space it can not take to the others
+    pub min_size: ISizeOpt,
+    pub max_size: ISizeOpt,
 
     pub padding: Option<IRect>,
     pub margin: Option<IRect>,
+    /// sized by `TCtrl::get_preferred_size` where `size` is not set,
+    /// instead of sharing the space left
+    pub auto_size: bool,
+    /// used when the parent has a `LayoutFlex`
+    pub flex: FlexItem,
+    /// used when the parent has a `LayoutGrid`
+    pub grid: GridItem,
+
+    /// can take keyboard focus by click or Tab
+    pub focusable: bool,
+    /// Tab visits positive indexes first in ascending order, then the
+    /// controls without one in tree order, negative ones are skipped
+    pub tab_index: Option<i32>,
+    pub(crate) focused: bool,
+    pub(crate) checked: bool,
+
+    pub handlers: Vec<Box<dyn TEventHandler>>,
 }
 
 impl TCtrlInner {
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    pub fn add_handler(&mut self, h: Box<dyn TEventHandler>) -> &mut Self {
+        self.handlers.push(h);
+        self
+    }
+
+    fn add_target_handler<F>(&mut self, filter: fn(&EUIEvent) -> bool, f: F) -> &mut Self
+    where
+        F: FnMut(&mut EventCtx, &mut UIEvent) + 'static,
+    {
+        self.add_handler(Box::new(TargetHandler { filter, f }))
+    }
+
+    pub fn on_click<F>(&mut self, f: F) -> &mut Self
+    where
+        F: FnMut(&mut EventCtx, &mut UIEvent) + 'static,
+    {
+        self.add_target_handler(|e| matches!(e, EUIEvent::Click { .. }), f)
+    }
+
+    pub fn on_double_click<F>(&mut self, f: F) -> &mut Self
+    where
+        F: FnMut(&mut EventCtx, &mut UIEvent) + 'static,
+    {
+        self.add_target_handler(|e| matches!(e, EUIEvent::DoubleClick { .. }), f)
+    }
+
+    pub fn on_change<F>(&mut self, f: F) -> &mut Self
+    where
+        F: FnMut(&mut EventCtx, &mut UIEvent) + 'static,
+    {
+        self.add_target_handler(|e| matches!(e, EUIEvent::Change), f)
+    }
+
+    pub fn on_submit<F>(&mut self, f: F) -> &mut Self
+    where
+        F: FnMut(&mut EventCtx, &mut UIEvent) + 'static,
+    {
+        self.add_target_handler(|e| matches!(e, EUIEvent::Submit), f)
+    }
+
+    /// `Link` of a `RichText`, the url is in the event
+    pub fn on_link<F>(&mut self, f: F) -> &mut Self
+    where
+        F: FnMut(&mut EventCtx, &mut UIEvent) + 'static,
+    {
+        self.add_target_handler(|e| matches!(e, EUIEvent::Link(_)), f)
+    }
+
+    pub fn on_selection_changed<F>(&mut self, f: F) -> &mut Self
+    where
+        F: FnMut(&mut EventCtx, &mut UIEvent) + 'static,
+    {
+        self.add_target_handler(|e| matches!(e, EUIEvent::SelectionChanged), f)
+    }
+
+    /// `ItemActivate` of a `ListView`, the index is in the event
+    pub fn on_item_activate<F>(&mut self, f: F) -> &mut Self
+    where
+        F: FnMut(&mut EventCtx, &mut UIEvent) + 'static,
+    {
+        self.add_target_handler(|e| matches!(e, EUIEvent::ItemActivate(_)), f)
+    }
+
+    /// `Toggled` of a `CheckBox` or `RadioButton`
+    pub fn on_toggled<F>(&mut self, f: F) -> &mut Self
+    where
+        F: FnMut(&mut EventCtx, &mut UIEvent) + 'static,
+    {
+        self.add_target_handler(|e| matches!(e, EUIEvent::Toggled), f)
+    }
+
+    /// `MouseEnter` and `MouseLeave`
+    pub fn on_hover<F>(&mut self, f: F) -> &mut Self
+    where
+        F: FnMut(&mut EventCtx, &mut UIEvent) + 'static,
+    {
+        self.add_target_handler(
+            |e| matches!(e, EUIEvent::MouseEnter | EUIEvent::MouseLeave),
+            f,
+        )
+    }
+
+    /// `FocusIn` and `FocusOut`
+    pub fn on_focus<F>(&mut self, f: F) -> &mut Self
+    where
+        F: FnMut(&mut EventCtx, &mut UIEvent) + 'static,
+    {
+        self.add_target_handler(|e| matches!(e, EUIEvent::FocusIn | EUIEvent::FocusOut), f)
+    }
+
     pub fn render(&self, canvas: &mut Canvas, dpi: &ScaleDpi, sytle: &CtrlStyle) {
         if self.empty_paint() {
             return;
@@ -125,26 +269,40 @@ pub struct BaseCtrl<T: Default + TStyle> {
 }
 
 impl<T: Default + TStyle> BaseCtrl<T> {
+    /// The focused style only replaces the default one, hover and press
+    /// still win. The checked one replaces it too and is kept when there is
+    /// no style for the status.
     pub fn get_cur_style(&self) -> &T {
+        let checked = self.inner.checked && self.styles.checked.is_some();
+        let base = match checked {
+            true => self.styles.checked.as_ref().unwrap(),
+            false => &self.styles.default,
+        };
         match self.status {
-            ECtrlStatus::Default => &self.styles.default,
+            ECtrlStatus::Default => {
+                if !checked && self.inner.focused && self.styles.focused.is_some() {
+                    self.styles.focused.as_ref().unwrap()
+                } else {
+                    base
+                }
+            }
             ECtrlStatus::Hover => {
                 if self.styles.hover.is_none() {
-                    &self.styles.default
+                    base
                 } else {
                     self.styles.hover.as_ref().unwrap()
                 }
             }
             ECtrlStatus::Press => {
                 if self.styles.press.is_none() {
-                    &self.styles.default
+                    base
                 } else {
                     self.styles.press.as_ref().unwrap()
                 }
             }
             ECtrlStatus::Disable => {
                 if self.styles.disable.is_none() {
-                    &self.styles.default
+                    base
                 } else {
                     self.styles.disable.as_ref().unwrap()
                 }
@@ -173,6 +331,12 @@ impl TCtrl for Ctrl {
     fn type_(&self) -> ECtrlType {
         ECtrlType::BaseCtrl
     }
+    fn get_status(&self) -> ECtrlStatus {
+        self.status
+    }
+    fn set_status(&mut self, status: ECtrlStatus) {
+        self.status = status;
+    }
 
     fn render(&self, canvas: &mut Canvas, dpi: &ScaleDpi) {
         self.get_inner().render(canvas, dpi, self.get_cur_style());
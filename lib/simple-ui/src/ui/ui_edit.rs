@@ -0,0 +1,677 @@
+use crate::{
+    clipboard,
+    font::{self, FontDesc},
+    input::{EKey, EMouseButton, KeyInput},
+    ui::{
+        styles::{EUIStatus, StyleInner, TStyle},
+        ui_ctrl::{BaseCtrl, CtrlStyle, TCtrlInner},
+        ui_text::char_offsets,
+        ECtrlStatus, ECtrlType, EUIEvent, TCtrl, UIEvent,
+    },
+    utils::ScaleDpi,
+    Color, IRect,
+};
+use skia_safe::{paint::Paint, Font, Point, Rect};
+use std::{ops::Range, time::Duration};
+
+const CARET_TIMER: u32 = 1;
+const CARET_BLINK: Duration = Duration::from_millis(530);
+const PASSWORD_CHAR: char = '•';
+
+pub struct EditStyle {
+    base: CtrlStyle,
+
+    pub font: FontDesc,
+    pub font_color: Color,
+    pub placeholder_color: Color,
+    pub selection_color: Color,
+    pub caret_color: Color,
+}
+
+impl TStyle for EditStyle {
+    fn get_inner(&self) -> &StyleInner {
+        &self.base
+    }
+    fn get_mut_inner(&mut self) -> &mut StyleInner {
+        &mut self.base
+    }
+}
+
+impl Default for EditStyle {
+    fn default() -> Self {
+        Self {
+            base: CtrlStyle::default(),
+            font: FontDesc::default(),
+            font_color: Color::BLACK,
+            placeholder_color: Color::from_rgb(0xa0, 0xa0, 0xa0),
+            selection_color: Color::from_rgb(0xb4, 0xd5, 0xfe),
+            caret_color: Color::BLACK,
+        }
+    }
+}
+
+/// Single line text input.
+///
+/// Positions (caret, selection) count chars, not bytes. Editing posts
+/// `EUIEvent::Change`, Enter posts `EUIEvent::Submit`, see
+/// `TCtrlInner::on_change` and `TCtrlInner::on_submit`.
+pub struct Edit {
+    pub ctrl: BaseCtrl<EditStyle>,
+
+    text: String,
+    placeholder: String,
+    password: bool,
+    max_length: Option<usize>,
+
+    caret: usize,
+    // the other end of the selection, equal to `caret` when nothing is selected
+    anchor: usize,
+    caret_visible: bool,
+    selecting: bool,
+
+    font: Font,
+    paint: Paint,
+    // x of every char boundary of the shown text, from 0, in pixels
+    offsets: Vec<f32>,
+    scroll_x: f32,
+
+    dpi_cache: ScaleDpi,
+}
+
+impl Edit {
+    pub fn new(font_size: i32) -> Self {
+        let mut ctrl = BaseCtrl::<EditStyle>::default();
+        ctrl.inner.focusable = true;
+        ctrl.styles.default.font.size = font_size;
+
+        Self {
+            ctrl,
+
+            text: String::new(),
+            placeholder: String::new(),
+            password: false,
+            max_length: None,
+
+            caret: 0,
+            anchor: 0,
+            caret_visible: false,
+            selecting: false,
+
+            font: Font::default(),
+            paint: Paint::default(),
+            offsets: vec![0.],
+            scroll_x: 0.,
+
+            dpi_cache: ScaleDpi::default(),
+        }
+    }
+
+    /// Replaces the text and puts the caret at its end, no `Change` is posted.
+    pub fn set_text(&mut self, t: &str) {
+        self.text = self.clean(t, usize::MAX);
+        self.caret = self.len();
+        self.anchor = self.caret;
+        self.update_text();
+    }
+
+    pub fn set_placeholder(&mut self, t: &str) {
+        self.placeholder = t.to_string();
+    }
+
+    /// shows every char as a dot, copy and cut are disabled
+    pub fn set_password(&mut self, v: bool) {
+        self.password = v;
+        self.update_text();
+    }
+
+    /// in chars, longer text is cut by `set_text` and rejected when typed
+    pub fn set_max_length(&mut self, v: Option<usize>) {
+        self.max_length = v;
+        if let Some(max) = v {
+            if self.len() > max {
+                let t = self.text.chars().take(max).collect::<String>();
+                self.set_text(&t);
+            }
+        }
+    }
+
+    pub fn get_caret(&self) -> usize {
+        self.caret
+    }
+
+    pub fn get_selection(&self) -> Range<usize> {
+        self.caret.min(self.anchor)..self.caret.max(self.anchor)
+    }
+
+    /// `anchor` stays put when the selection is extended with Shift
+    pub fn set_selection(&mut self, anchor: usize, caret: usize) {
+        self.anchor = anchor.min(self.len());
+        self.caret = caret.min(self.len());
+        self.update_scroll();
+    }
+
+    pub fn get_selected_text(&self) -> &str {
+        let sel = self.get_selection();
+        &self.text[self.byte_index(sel.start)..self.byte_index(sel.end)]
+    }
+
+    pub fn is_caret_visible(&self) -> bool {
+        self.caret_visible
+    }
+
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    fn byte_index(&self, pos: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(pos)
+            .map(|(i, _)| i)
+            .unwrap_or(self.text.len())
+    }
+
+    // keeps the first line without control chars, at most `room` chars
+    fn clean(&self, t: &str, room: usize) -> String {
+        let room = match self.max_length {
+            Some(max) => room.min(max),
+            None => room,
+        };
+        t.lines()
+            .next()
+            .unwrap_or("")
+            .chars()
+            .filter(|c| !c.is_control())
+            .take(room)
+            .collect()
+    }
+
+    fn shown_text(&self) -> String {
+        if self.password {
+            PASSWORD_CHAR.to_string().repeat(self.len())
+        } else {
+            self.text.clone()
+        }
+    }
+
+    fn update_ctx(&mut self) {
+        self.font = self.ctrl.get_cur_style().font.make_font(&self.dpi_cache);
+        self.paint.set_anti_alias(true);
+    }
+
+    fn update_text(&mut self) {
+        let shown = self.shown_text();
+        self.offsets = char_offsets(&self.font, &shown);
+
+        self.update_scroll();
+    }
+
+    fn text_rc(&self) -> IRect {
+        let mut rc = self.ctrl.inner.real_rc;
+        if let Some(p) = self.ctrl.inner.padding {
+            let p = self.dpi_cache.scale(p);
+            rc = IRect::new(
+                rc.left + p.left,
+                rc.top + p.top,
+                rc.right - p.right,
+                rc.bottom - p.bottom,
+            );
+        }
+        rc
+    }
+
+    // scrolls just enough to keep the caret inside
+    fn update_scroll(&mut self) {
+        let width = self.text_rc().width() as f32;
+        let caret_w = self.dpi_cache.scale(1) as f32;
+        let total = *self.offsets.last().unwrap();
+        let x = self.offsets[self.caret.min(self.offsets.len() - 1)];
+
+        if x + caret_w - self.scroll_x > width {
+            self.scroll_x = x + caret_w - width;
+        }
+        if x < self.scroll_x {
+            self.scroll_x = x;
+        }
+        self.scroll_x = self.scroll_x.min((total + caret_w - width).max(0.));
+    }
+
+    // `x` is physical like `real_rc`
+    fn pos_at(&self, x: i32) -> usize {
+        let x = (x - self.text_rc().left) as f32 + self.scroll_x;
+        let i = self.offsets.partition_point(|o| *o < x);
+        if i == 0 {
+            return 0;
+        }
+        if i >= self.offsets.len() {
+            return self.offsets.len() - 1;
+        }
+        if x - self.offsets[i - 1] < self.offsets[i] - x {
+            i - 1
+        } else {
+            i
+        }
+    }
+
+    fn word_left(&self, pos: usize) -> usize {
+        if self.password {
+            return 0;
+        }
+        word_left(&self.text.chars().collect::<Vec<_>>(), pos)
+    }
+
+    fn word_right(&self, pos: usize) -> usize {
+        if self.password {
+            return self.len();
+        }
+        word_right(&self.text.chars().collect::<Vec<_>>(), pos)
+    }
+
+    fn move_caret(&mut self, pos: usize, extend: bool) {
+        self.caret = pos;
+        if !extend {
+            self.anchor = pos;
+        }
+        self.update_scroll();
+    }
+
+    fn replace(&mut self, range: Range<usize>, s: &str) {
+        let (start, end) = (self.byte_index(range.start), self.byte_index(range.end));
+        self.text.replace_range(start..end, s);
+        self.caret = range.start + s.chars().count();
+        self.anchor = self.caret;
+        self.update_text();
+    }
+
+    // returns false if nothing changed
+    fn insert(&mut self, s: &str) -> bool {
+        let sel = self.get_selection();
+        let room = self.len() - sel.len();
+        let room = self
+            .max_length
+            .map_or(usize::MAX, |m| m.saturating_sub(room));
+        let s = self.clean(s, room);
+        if s.is_empty() && sel.is_empty() {
+            return false;
+        }
+        self.replace(sel, &s);
+        true
+    }
+
+    // deletes the selection or from the caret to `to`
+    fn delete_to(&mut self, to: usize) -> bool {
+        let mut sel = self.get_selection();
+        if sel.is_empty() {
+            sel = self.caret.min(to)..self.caret.max(to);
+        }
+        if sel.is_empty() {
+            return false;
+        }
+        self.replace(sel, "");
+        true
+    }
+
+    fn copy(&self) {
+        let sel = self.get_selection();
+        if !self.password && !sel.is_empty() {
+            clipboard::set_text(self.get_selected_text());
+        }
+    }
+
+    fn restart_blink(&mut self, event: &mut UIEvent) {
+        self.caret_visible = true;
+        event.set_timer(CARET_TIMER, CARET_BLINK);
+        event.request_redraw();
+    }
+
+    // returns (the key was used, the text changed)
+    fn on_key(&mut self, input: &KeyInput) -> (bool, bool) {
+        let m = input.modifiers;
+        let shift = m.shift;
+        // Alt moves by words on macOS, Cmd to the line ends
+        let (word, line) = if cfg!(target_os = "macos") {
+            (m.alt, m.logo)
+        } else {
+            (m.ctrl, false)
+        };
+        let sel = self.get_selection();
+
+        let changed = match input.key {
+            EKey::Left => {
+                let pos = if line {
+                    0
+                } else if word {
+                    self.word_left(self.caret)
+                } else if !shift && !sel.is_empty() {
+                    sel.start
+                } else {
+                    self.caret.saturating_sub(1)
+                };
+                self.move_caret(pos, shift);
+                false
+            }
+            EKey::Right => {
+                let pos = if line {
+                    self.len()
+                } else if word {
+                    self.word_right(self.caret)
+                } else if !shift && !sel.is_empty() {
+                    sel.end
+                } else {
+                    (self.caret + 1).min(self.len())
+                };
+                self.move_caret(pos, shift);
+                false
+            }
+            EKey::Home | EKey::Up => {
+                self.move_caret(0, shift);
+                false
+            }
+            EKey::End | EKey::Down => {
+                self.move_caret(self.len(), shift);
+                false
+            }
+            EKey::Backspace => {
+                let to = if line {
+                    0
+                } else if word {
+                    self.word_left(self.caret)
+                } else {
+                    self.caret.saturating_sub(1)
+                };
+                self.delete_to(to)
+            }
+            EKey::Delete => {
+                let to = if line {
+                    self.len()
+                } else if word {
+                    self.word_right(self.caret)
+                } else {
+                    (self.caret + 1).min(self.len())
+                };
+                self.delete_to(to)
+            }
+            EKey::Char('a') if m.command() => {
+                self.set_selection(0, self.len());
+                false
+            }
+            EKey::Char('c') if m.command() => {
+                self.copy();
+                false
+            }
+            EKey::Char('x') if m.command() => {
+                self.copy();
+                !self.password && self.delete_to(self.caret)
+            }
+            EKey::Char('v') if m.command() => match clipboard::get_text() {
+                Some(t) => self.insert(&t),
+                None => false,
+            },
+            _ => return (false, false),
+        };
+        (true, changed)
+    }
+}
+
+#[derive(PartialEq)]
+enum ECharClass {
+    Word,
+    Space,
+    Other,
+}
+
+fn char_class(c: char) -> ECharClass {
+    if c.is_alphanumeric() || c == '_' {
+        ECharClass::Word
+    } else if c.is_whitespace() {
+        ECharClass::Space
+    } else {
+        ECharClass::Other
+    }
+}
+
+// start of the word before `pos`, spaces in between are skipped
+pub(crate) fn word_left(chars: &[char], pos: usize) -> usize {
+    let mut i = pos.min(chars.len());
+    while i > 0 && chars[i - 1].is_whitespace() {
+        i -= 1;
+    }
+    if i > 0 {
+        let class = char_class(chars[i - 1]);
+        while i > 0 && char_class(chars[i - 1]) == class {
+            i -= 1;
+        }
+    }
+    i
+}
+
+// end of the word at `pos` and the spaces after it
+pub(crate) fn word_right(chars: &[char], pos: usize) -> usize {
+    let mut i = pos.min(chars.len());
+    if let Some(c) = chars.get(i) {
+        let class = char_class(*c);
+        while i < chars.len() && !chars[i].is_whitespace() && char_class(chars[i]) == class {
+            i += 1;
+        }
+    }
+    while i < chars.len() && chars[i].is_whitespace() {
+        i += 1;
+    }
+    i
+}
+
+impl TCtrl for Edit {
+    fn get_inner(&self) -> &TCtrlInner {
+        &self.ctrl.inner
+    }
+    fn get_mut_inner(&mut self) -> &mut TCtrlInner {
+        &mut self.ctrl.inner
+    }
+    fn get_style(&self, status: EUIStatus) -> Option<&StyleInner> {
+        self.ctrl.styles.get_inner_style(status)
+    }
+    fn get_mut_style(&mut self, status: EUIStatus) -> Option<&mut StyleInner> {
+        self.ctrl.styles.get_mut_inner_style(status)
+    }
+    fn type_name(&self) -> &str {
+        "Edit"
+    }
+    fn type_(&self) -> ECtrlType {
+        ECtrlType::Edit
+    }
+    fn get_status(&self) -> ECtrlStatus {
+        self.ctrl.status
+    }
+    fn set_status(&mut self, status: ECtrlStatus) {
+        self.ctrl.status = status;
+    }
+    fn get_text(&self) -> Option<&str> {
+        Some(&self.text)
+    }
+
+    fn update(&mut self, _dpi: &ScaleDpi) {
+        self.update_scroll();
+    }
+
+    fn update_dpi(&mut self, dpi: &ScaleDpi) {
+        self.dpi_cache = dpi.clone();
+        self.update_ctx();
+        self.update_text();
+    }
+
+    fn on_event(&mut self, event: &mut UIEvent) {
+        if !event.is_target() || self.ctrl.status == ECtrlStatus::Disable {
+            return;
+        }
+
+        match event.kind.clone() {
+            EUIEvent::FocusIn => self.restart_blink(event),
+            EUIEvent::FocusOut => {
+                self.caret_visible = false;
+                self.selecting = false;
+                event.request_redraw();
+            }
+            EUIEvent::Timer(CARET_TIMER) => {
+                if self.ctrl.inner.is_focused() {
+                    self.caret_visible = !self.caret_visible;
+                    event.set_timer(CARET_TIMER, CARET_BLINK);
+                    event.request_redraw();
+                }
+            }
+            EUIEvent::MouseDown {
+                pos,
+                button: EMouseButton::Left,
+            } => {
+                let pos = self.pos_at(pos.x);
+                self.move_caret(pos, event.modifiers.shift);
+                self.selecting = true;
+                self.restart_blink(event);
+            }
+            EUIEvent::MouseMove { pos } if self.selecting => {
+                let pos = self.pos_at(pos.x);
+                if pos != self.caret {
+                    self.move_caret(pos, true);
+                    self.restart_blink(event);
+                }
+            }
+            EUIEvent::MouseUp {
+                button: EMouseButton::Left,
+                ..
+            } => self.selecting = false,
+            EUIEvent::KeyDown(input) => {
+                if input.key == EKey::Enter {
+                    event.post(EUIEvent::Submit);
+                    event.stop_propagation();
+                    return;
+                }
+
+                let (used, changed) = self.on_key(&input);
+                if used {
+                    if changed {
+                        event.post(EUIEvent::Change);
+                    }
+                    self.restart_blink(event);
+                    event.stop_propagation();
+                }
+            }
+            EUIEvent::Char(c) => {
+                // shortcuts, AltGr (Ctrl+Alt) still types
+                let m = event.modifiers;
+                if m.logo || (m.ctrl && !m.alt) {
+                    return;
+                }
+                if self.insert(&c.to_string()) {
+                    event.post(EUIEvent::Change);
+                    self.restart_blink(event);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn render(&self, canvas: &mut skia_safe::Canvas, dpi: &ScaleDpi) {
+        let style = self.ctrl.get_cur_style();
+        self.ctrl.inner.render(canvas, dpi, &style.base);
+        if self.ctrl.inner.empty_paint() {
+            return;
+        }
+
+        let rc = self.text_rc();
+        let (_, metrics) = self.font.metrics();
+        let baseline = rc.top as f32
+            + (rc.height() as f32 - (metrics.descent - metrics.ascent)) / 2.
+            - metrics.ascent;
+        let left = rc.left as f32 - self.scroll_x;
+        let focused = self.ctrl.inner.is_focused();
+
+        canvas.save();
+        canvas.clip_rect(Rect::from(rc), None, None);
+
+        let mut paint = self.paint.clone();
+        let sel = self.get_selection();
+        if focused && !sel.is_empty() {
+            paint.set_color(style.selection_color);
+            canvas.draw_rect(
+                Rect::new(
+                    left + self.offsets[sel.start],
+                    baseline + metrics.ascent,
+                    left + self.offsets[sel.end],
+                    baseline + metrics.descent,
+                ),
+                &paint,
+            );
+        }
+
+        if self.text.is_empty() {
+            paint.set_color(style.placeholder_color);
+            font::draw_text(
+                canvas,
+                &self.placeholder,
+                Point::new(left, baseline),
+                &self.font,
+                &paint,
+            );
+        } else {
+            paint.set_color(style.font_color);
+            font::draw_text(
+                canvas,
+                &self.shown_text(),
+                Point::new(left, baseline),
+                &self.font,
+                &paint,
+            );
+        }
+
+        if focused && self.caret_visible {
+            let x = left + self.offsets[self.caret];
+            paint.set_color(style.caret_color);
+            canvas.draw_rect(
+                Rect::new(
+                    x,
+                    baseline + metrics.ascent,
+                    x + dpi.scale(1) as f32,
+                    baseline + metrics.descent,
+                ),
+                &paint,
+            );
+        }
+
+        canvas.restore();
+    }
+}
+
+#[test]
+fn test_word_nav() {
+    let mut e = Edit::new(12);
+    e.set_text("hello, world  foo_bar");
+
+    assert_eq!(e.word_left(21), 14);
+    assert_eq!(e.word_left(14), 7);
+    assert_eq!(e.word_left(7), 5);
+    assert_eq!(e.word_left(5), 0);
+    assert_eq!(e.word_right(0), 5);
+    assert_eq!(e.word_right(5), 7);
+    assert_eq!(e.word_right(7), 14);
+
+    e.set_password(true);
+    assert_eq!(e.word_left(10), 0);
+}
+
+#[test]
+fn test_edit_text() {
+    let mut e = Edit::new(12);
+    e.set_max_length(Some(5));
+    e.set_text("ab\ncd");
+    assert_eq!(e.get_text(), Some("ab"));
+
+    assert!(e.insert("日本語xyz"));
+    assert_eq!(e.get_text(), Some("ab日本語"));
+    assert_eq!(e.get_caret(), 5);
+    assert!(!e.insert("q"));
+
+    e.set_selection(1, 3);
+    assert_eq!(e.get_selected_text(), "b日");
+    assert!(e.delete_to(0));
+    assert_eq!(e.get_text(), Some("a本語"));
+    assert_eq!(e.get_caret(), 1);
+}
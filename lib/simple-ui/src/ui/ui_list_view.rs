@@ -0,0 +1,537 @@
+use crate::{
+    input::{EKey, EMouseButton, KeyInput},
+    ui::{
+        layout::Layout,
+        styles::{EUIStatus, StyleInner, TStyle},
+        ui_ctrl::{BaseCtrl, CtrlStyle, TCtrlInner},
+        Container, ECtrlStatus, ECtrlType, EEventPhase, EUIEvent, ScrollBar, TCtrl, UIEvent,
+    },
+    utils::{in_rc, ScaleDpi},
+    Color, IPoint, IRect,
+};
+use skia_safe::{Canvas, Paint, Rect};
+use std::{collections::BTreeSet, ops::Range};
+
+/// The items of a `ListView`. Only the rows on screen exist, they are
+/// created once and then filled again with other items while scrolling.
+pub trait TListSource {
+    fn get_count(&self) -> usize;
+    /// logical pixels, the same for every row
+    fn get_row_height(&self) -> i32 {
+        24
+    }
+    /// an empty row, `update_row` fills it
+    fn create_row(&mut self) -> Box<dyn TCtrl>;
+    /// shows the item at `index` in a new or recycled row
+    fn update_row(&mut self, row: &mut dyn TCtrl, index: usize);
+}
+
+pub struct ListViewStyle {
+    base: CtrlStyle,
+
+    pub select_color: Color,
+    /// outline of the current item while focused
+    pub current_color: Option<Color>,
+}
+
+impl TStyle for ListViewStyle {
+    fn get_inner(&self) -> &StyleInner {
+        &self.base
+    }
+    fn get_mut_inner(&mut self) -> &mut StyleInner {
+        &mut self.base
+    }
+}
+
+impl Default for ListViewStyle {
+    fn default() -> Self {
+        Self {
+            base: CtrlStyle::default(),
+            select_color: Color::from_rgb(0xcc, 0xe4, 0xf7),
+            current_color: Some(Color::from_rgb(0x1a, 0x5f, 0xd0)),
+        }
+    }
+}
+
+/// Rows of a `TListSource`, virtualized so the count can be in the millions.
+/// Click selects, Ctrl toggles and Shift extends with `multi_select`; the
+/// arrows, PageUp/PageDown and Home/End move the current item the same way.
+/// Posts `EUIEvent::SelectionChanged`, and `EUIEvent::ItemActivate` on a
+/// double click or Enter. The rows are reached like the children of a
+/// `Container`, offsets are in physical pixels.
+pub struct ListView {
+    pub ctrl: BaseCtrl<ListViewStyle>,
+
+    pub multi_select: bool,
+    /// thickness of the bar, logical pixels
+    pub bar_size: i32,
+
+    source: Box<dyn TListSource>,
+    // the rows, item `i` is in row `i % rows.len()`
+    rows: Container,
+    bound: Vec<Option<usize>>,
+    vbar: ScrollBar,
+
+    selected: BTreeSet<usize>,
+    current: Option<usize>,
+    // where Shift extends from
+    anchor: Option<usize>,
+
+    offset: i32,
+    row_height: i32,
+    viewport: IRect,
+    dpi_cache: ScaleDpi,
+}
+
+impl ListView {
+    pub fn new(source: Box<dyn TListSource>) -> Self {
+        let mut ctrl = BaseCtrl::<ListViewStyle>::default();
+        ctrl.inner.focusable = true;
+
+        Self {
+            ctrl,
+
+            multi_select: false,
+            bar_size: 10,
+
+            source,
+            rows: Container::new(Layout::new()),
+            bound: Vec::new(),
+            vbar: ScrollBar::new(true),
+
+            selected: BTreeSet::new(),
+            current: None,
+            anchor: None,
+
+            offset: 0,
+            row_height: 0,
+            viewport: IRect::default(),
+            dpi_cache: ScaleDpi::default(),
+        }
+    }
+
+    pub fn get_source(&self) -> &dyn TListSource {
+        self.source.as_ref()
+    }
+    pub fn get_mut_source(&mut self) -> &mut dyn TListSource {
+        self.source.as_mut()
+    }
+
+    /// Call after the items changed, the rows are filled again and the
+    /// selection past the end is dropped.
+    pub fn reload(&mut self) {
+        let count = self.source.get_count();
+        self.selected = self.selected.range(..count).copied().collect();
+        self.current = self.current.filter(|i| *i < count);
+        self.anchor = self.anchor.filter(|i| *i < count);
+
+        self.rebind();
+        self.relayout();
+    }
+
+    // fills every row again on the next `update`, e.g. after a change of
+    // what the rows are drawn with
+    pub(crate) fn rebind(&mut self) {
+        self.bound.iter_mut().for_each(|b| *b = None);
+    }
+
+    /// Moves the selection along with rows inserted or removed, `f` maps an
+    /// old index to the new one, `None` for a removed row. The current item
+    /// goes to `fallback` when its row is removed.
+    pub(crate) fn remap(&mut self, f: impl Fn(usize) -> Option<usize>, fallback: Option<usize>) {
+        self.selected = self.selected.iter().filter_map(|i| f(*i)).collect();
+        self.current = self.current.and_then(|i| f(i).or(fallback));
+        self.anchor = self.anchor.and_then(|i| f(i).or(fallback));
+        self.reload();
+    }
+
+    pub fn get_count(&self) -> usize {
+        self.source.get_count()
+    }
+
+    /// in ascending order
+    pub fn get_selection(&self) -> Vec<usize> {
+        self.selected.iter().copied().collect()
+    }
+    pub fn is_selected(&self, index: usize) -> bool {
+        self.selected.contains(&index)
+    }
+    /// the last one is the current item, no event is posted
+    pub fn set_selection(&mut self, items: &[usize]) {
+        let count = self.source.get_count();
+        let items = items
+            .iter()
+            .copied()
+            .filter(|i| *i < count)
+            .collect::<Vec<_>>();
+        self.current = items.last().copied();
+        self.anchor = self.current;
+        self.selected = match self.multi_select {
+            true => items.into_iter().collect(),
+            false => self.current.into_iter().collect(),
+        };
+    }
+    pub fn clear_selection(&mut self) {
+        self.selected.clear();
+    }
+
+    /// the item moved by the keys, outlined while focused
+    pub fn get_current(&self) -> Option<usize> {
+        self.current
+    }
+
+    pub fn get_offset(&self) -> i32 {
+        self.offset
+    }
+    pub fn get_max_offset(&self) -> i32 {
+        let total = self.source.get_count() as i64 * self.row_height as i64;
+        (total - self.viewport.height() as i64).clamp(0, i32::MAX as i64) as i32
+    }
+    /// the part of the control showing the rows, without the bar
+    pub fn get_viewport(&self) -> IRect {
+        self.viewport
+    }
+
+    /// items with a row on screen
+    pub fn get_visible_range(&self) -> Range<usize> {
+        if self.row_height <= 0 {
+            return 0..0;
+        }
+        let h = self.row_height as i64;
+        let first = self.offset as i64 / h;
+        let end = (self.offset as i64 + self.viewport.height() as i64 + h - 1) / h;
+        let count = self.source.get_count();
+        (first as usize).min(count)..(end as usize).min(count)
+    }
+
+    /// where the row of `index` is drawn now
+    pub fn get_item_rc(&self, index: usize) -> IRect {
+        let vp = self.viewport;
+        let top = vp.top as i64 + index as i64 * self.row_height as i64 - self.offset as i64;
+        let top = top.clamp(i32::MIN as i64 / 2, i32::MAX as i64 / 2) as i32;
+        IRect::new(vp.left, top, vp.right, top + self.row_height)
+    }
+
+    /// the item under `pos`, in physical pixels
+    pub fn get_item_at(&self, pos: IPoint) -> Option<usize> {
+        if !in_rc(&self.viewport, &pos) || self.row_height <= 0 {
+            return None;
+        }
+        let y = (pos.y - self.viewport.top) as i64 + self.offset as i64;
+        let index = (y / self.row_height as i64) as usize;
+        (index < self.source.get_count()).then_some(index)
+    }
+
+    /// clamped, returns whether the offset changed
+    pub fn scroll_to(&mut self, offset: i32) -> bool {
+        let offset = offset.clamp(0, self.get_max_offset());
+        if offset == self.offset {
+            return false;
+        }
+        self.offset = offset;
+        self.place();
+        true
+    }
+
+    /// scrolls as little as needed to show the whole row of `index`
+    pub fn scroll_to_item(&mut self, index: usize) -> bool {
+        let h = self.row_height as i64;
+        let top = index as i64 * h;
+        let (offset, height) = (self.offset as i64, self.viewport.height() as i64);
+        let to = if top < offset {
+            top
+        } else if top + h > offset + height {
+            top + h - height
+        } else {
+            return false;
+        };
+        self.scroll_to(to.clamp(0, i32::MAX as i64) as i32)
+    }
+
+    fn relayout(&mut self) {
+        let rc = self.ctrl.inner.real_rc;
+        self.row_height = self.dpi_cache.scale(self.source.get_row_height()).max(1);
+
+        let total = self.source.get_count() as i64 * self.row_height as i64;
+        let bar = if total > rc.height() as i64 {
+            self.dpi_cache.scale(self.bar_size)
+        } else {
+            0
+        };
+        self.viewport = IRect::new(rc.left, rc.top, rc.right - bar, rc.bottom);
+        self.vbar.ctrl.inner.real_rc = match bar {
+            0 => IRect::default(),
+            _ => IRect::new(rc.right - bar, rc.top, rc.right, rc.bottom),
+        };
+        let range = total.min(i32::MAX as i64) as i32;
+        self.vbar.set_range(range, self.viewport.height());
+
+        self.offset = self.offset.clamp(0, self.get_max_offset());
+        self.place();
+    }
+
+    // fills and moves the rows on screen, the others are hidden
+    fn place(&mut self) {
+        let vp = self.viewport;
+        let slots = match vp.height() {
+            h if h > 0 => (h / self.row_height + 2) as usize,
+            _ => 0,
+        };
+
+        // a new count changes which row an item goes to
+        let children = self.rows.get_mut_children();
+        if children.len() != slots {
+            children.truncate(slots);
+            while children.len() < slots {
+                let mut row = self.source.create_row();
+                row.update_dpi(&self.dpi_cache);
+                children.push(row);
+            }
+            self.bound = vec![None; slots];
+        }
+
+        let visible = self.get_visible_range();
+        let dpi = self.dpi_cache.clone();
+        for k in 0..slots {
+            let first = visible.start;
+            let index = first + (k + slots - first % slots) % slots;
+            let rc = if index < visible.end {
+                self.get_item_rc(index)
+            } else {
+                IRect::default()
+            };
+
+            let row = self.rows.get_mut_children()[k].as_mut();
+            if index < visible.end && self.bound[k] != Some(index) {
+                self.source.update_row(row, index);
+                self.bound[k] = Some(index);
+            }
+            row.get_mut_inner().real_rc = rc;
+            row.update(&dpi);
+        }
+
+        self.rows.ctrl.inner.real_rc = vp;
+        self.rows.viewport = Some(vp);
+        self.vbar.set_value(self.offset);
+    }
+
+    /// Makes `index` the current item and changes the selection the way a
+    /// click does with the modifiers. `toggle` is Ctrl flipping the item,
+    /// without it Ctrl only moves the current item. Returns whether the
+    /// selection changed.
+    pub(crate) fn select(&mut self, index: usize, shift: bool, ctrl: bool, toggle: bool) -> bool {
+        let old = self.selected.clone();
+        if !self.multi_select {
+            if !ctrl || toggle {
+                self.selected = BTreeSet::from([index]);
+            }
+            self.anchor = Some(index);
+        } else if shift {
+            let anchor = self.anchor.unwrap_or(index);
+            let range = anchor.min(index)..=anchor.max(index);
+            if !ctrl {
+                self.selected.clear();
+            }
+            self.selected.extend(range);
+        } else if ctrl {
+            if toggle && !self.selected.remove(&index) {
+                self.selected.insert(index);
+            }
+            self.anchor = Some(index);
+        } else {
+            self.selected = BTreeSet::from([index]);
+            self.anchor = Some(index);
+        }
+
+        self.current = Some(index);
+        self.scroll_to_item(index);
+        old != self.selected
+    }
+
+    // returns (used, selection changed)
+    fn on_key(&mut self, input: &KeyInput) -> (bool, bool) {
+        let count = self.source.get_count();
+        if count == 0 {
+            return (false, false);
+        }
+
+        let m = input.modifiers;
+        let last = count - 1;
+        let cur = self.current.unwrap_or(0);
+        let page = (self.viewport.height() / self.row_height).max(1) as usize;
+        let to = match input.key {
+            EKey::Up => cur.saturating_sub(1),
+            EKey::Down => (cur + 1).min(last),
+            EKey::PageUp => cur.saturating_sub(page),
+            EKey::PageDown => (cur + page).min(last),
+            EKey::Home => 0,
+            EKey::End => last,
+            EKey::Space => return (true, self.select(cur, false, m.command(), true)),
+            EKey::Char('a') if m.command() && self.multi_select => {
+                let all = self.selected.len() == count;
+                self.selected.extend(0..count);
+                return (true, !all);
+            }
+            _ => return (false, false),
+        };
+        (true, self.select(to, m.shift, m.command(), false))
+    }
+}
+
+impl TCtrl for ListView {
+    fn get_inner(&self) -> &TCtrlInner {
+        &self.ctrl.inner
+    }
+    fn get_mut_inner(&mut self) -> &mut TCtrlInner {
+        &mut self.ctrl.inner
+    }
+    fn get_style(&self, status: EUIStatus) -> Option<&StyleInner> {
+        self.ctrl.styles.get_inner_style(status)
+    }
+    fn get_mut_style(&mut self, status: EUIStatus) -> Option<&mut StyleInner> {
+        self.ctrl.styles.get_mut_inner_style(status)
+    }
+    fn type_name(&self) -> &str {
+        "ListView"
+    }
+    fn type_(&self) -> ECtrlType {
+        ECtrlType::ListView
+    }
+    fn as_container(&self) -> Option<&Container> {
+        Some(&self.rows)
+    }
+    fn as_mut_container(&mut self) -> Option<&mut Container> {
+        Some(&mut self.rows)
+    }
+    fn get_status(&self) -> ECtrlStatus {
+        self.ctrl.status
+    }
+    fn set_status(&mut self, status: ECtrlStatus) {
+        self.ctrl.status = status;
+    }
+
+    fn update(&mut self, dpi: &ScaleDpi) {
+        self.dpi_cache = dpi.clone();
+        self.relayout();
+    }
+
+    fn update_dpi(&mut self, dpi: &ScaleDpi) {
+        self.dpi_cache = dpi.clone();
+        self.rows.update_dpi(dpi);
+        self.vbar.update_dpi(dpi);
+    }
+
+    fn on_event(&mut self, event: &mut UIEvent) {
+        if event.phase == EEventPhase::Capture || self.ctrl.status == ECtrlStatus::Disable {
+            return;
+        }
+
+        let m = event.modifiers;
+        match event.kind.clone() {
+            EUIEvent::Wheel { delta, .. } => {
+                if self.scroll_to(self.offset - delta.y as i32) {
+                    event.request_redraw();
+                    event.stop_propagation();
+                }
+            }
+            EUIEvent::MouseDown {
+                pos,
+                button: EMouseButton::Left,
+            } if !in_rc(&self.vbar.ctrl.inner.real_rc, &pos) => {
+                let changed = match self.get_item_at(pos) {
+                    Some(i) => self.select(i, m.shift, m.command(), true),
+                    // the empty space below the items
+                    None if !m.command() && !self.selected.is_empty() => {
+                        self.selected.clear();
+                        true
+                    }
+                    None => false,
+                };
+                if changed {
+                    event.post(EUIEvent::SelectionChanged);
+                }
+                event.request_redraw();
+            }
+            EUIEvent::DoubleClick { pos } => {
+                if let Some(i) = self.get_item_at(pos) {
+                    event.post(EUIEvent::ItemActivate(i));
+                }
+            }
+            EUIEvent::MouseDown { .. } | EUIEvent::MouseMove { .. } | EUIEvent::MouseUp { .. }
+                if event.is_target() =>
+            {
+                let dragging = self.vbar.is_dragging();
+                let kind = event.kind.clone();
+                let hit = match kind {
+                    EUIEvent::MouseDown { pos, .. } => in_rc(&self.vbar.ctrl.inner.real_rc, &pos),
+                    EUIEvent::MouseMove { .. } => dragging,
+                    _ => true,
+                };
+                if hit && self.vbar.handle(&kind) {
+                    self.scroll_to(self.vbar.get_value());
+                    event.request_redraw();
+                } else if dragging != self.vbar.is_dragging() {
+                    event.request_redraw();
+                }
+            }
+            EUIEvent::KeyDown(input) if event.is_target() => {
+                if input.key == EKey::Enter {
+                    if let Some(i) = self.current {
+                        event.post(EUIEvent::ItemActivate(i));
+                        event.stop_propagation();
+                    }
+                    return;
+                }
+
+                let (used, changed) = self.on_key(&input);
+                if changed {
+                    event.post(EUIEvent::SelectionChanged);
+                }
+                if used {
+                    event.request_redraw();
+                    event.stop_propagation();
+                }
+            }
+            EUIEvent::FocusIn | EUIEvent::FocusOut if event.is_target() => {
+                event.request_redraw();
+            }
+            _ => (),
+        }
+    }
+
+    fn render(&self, canvas: &mut Canvas, dpi: &ScaleDpi) {
+        if self.ctrl.inner.empty_paint() {
+            return;
+        }
+        let style = self.ctrl.get_cur_style();
+        self.ctrl.inner.render(canvas, dpi, &style.base);
+
+        canvas.save();
+        canvas.clip_rect(Rect::from(self.viewport), None, None);
+        let mut paint = Paint::default();
+        paint.set_color(style.select_color);
+        for i in self.get_visible_range() {
+            if self.selected.contains(&i) {
+                canvas.draw_rect(Rect::from(self.get_item_rc(i)), &paint);
+            }
+        }
+        self.rows.render(canvas, dpi);
+
+        if let (Some(i), Some(c), true) = (
+            self.current,
+            style.current_color,
+            self.ctrl.inner.is_focused(),
+        ) {
+            let w = dpi.scale(1) as f32;
+            let mut paint = Paint::default();
+            paint.set_style(skia_safe::paint::Style::Stroke);
+            paint.set_stroke_width(w);
+            paint.set_color(c);
+            let rc = Rect::from(self.get_item_rc(i)).with_inset((w / 2., w / 2.));
+            canvas.draw_rect(rc, &paint);
+        }
+        canvas.restore();
+
+        self.vbar.render(canvas, dpi);
+    }
+}
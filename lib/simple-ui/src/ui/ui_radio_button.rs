@@ -0,0 +1,267 @@
+use crate::{
+    input::EKey,
+    ui::{
+        styles::{EUIStatus, StyleInner},
+        ui_check_box::{CheckLabel, CheckStyle},
+        ui_ctrl::{BaseCtrl, TCtrlInner},
+        Container, ECtrlStatus, ECtrlType, EEventPhase, EUIEvent, TCtrl, TLayout, UIEvent,
+    },
+    utils::ScaleDpi,
+    IPoint, ISize,
+};
+use as_any::Downcast;
+use skia_safe::{paint::Style, Canvas, Paint};
+
+/// A circle with a label, checked by click or Space while focused, which
+/// posts `EUIEvent::Toggled`. Only code unchecks it, a `RadioGroup` does
+/// when another one below it is checked.
+pub struct RadioButton {
+    pub ctrl: BaseCtrl<CheckStyle>,
+
+    label: CheckLabel,
+    dpi_cache: ScaleDpi,
+}
+
+impl RadioButton {
+    pub fn new(label: &str) -> Self {
+        let mut ctrl = BaseCtrl::<CheckStyle>::default();
+        ctrl.inner.focusable = true;
+
+        Self {
+            ctrl,
+            label: CheckLabel::new(label),
+            dpi_cache: ScaleDpi::default(),
+        }
+    }
+
+    pub fn set_label(&mut self, t: &str) {
+        self.label.text = t.to_string();
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.ctrl.inner.checked
+    }
+    /// no event is posted and the others in the group are left alone, see
+    /// `RadioGroup::set_checked`
+    pub fn set_checked(&mut self, checked: bool) {
+        self.ctrl.inner.checked = checked;
+        self.label
+            .update(self.ctrl.get_cur_style(), &self.dpi_cache);
+    }
+}
+
+impl TCtrl for RadioButton {
+    fn get_inner(&self) -> &TCtrlInner {
+        &self.ctrl.inner
+    }
+    fn get_mut_inner(&mut self) -> &mut TCtrlInner {
+        &mut self.ctrl.inner
+    }
+    fn get_style(&self, status: EUIStatus) -> Option<&StyleInner> {
+        self.ctrl.styles.get_inner_style(status)
+    }
+    fn get_mut_style(&mut self, status: EUIStatus) -> Option<&mut StyleInner> {
+        self.ctrl.styles.get_mut_inner_style(status)
+    }
+    fn type_name(&self) -> &str {
+        "RadioButton"
+    }
+    fn type_(&self) -> ECtrlType {
+        ECtrlType::RadioButton
+    }
+    fn get_status(&self) -> ECtrlStatus {
+        self.ctrl.status
+    }
+    fn set_status(&mut self, status: ECtrlStatus) {
+        self.ctrl.status = status;
+    }
+    fn get_text(&self) -> Option<&str> {
+        Some(&self.label.text)
+    }
+
+    fn get_preferred_size(&self, _max: ISize, dpi: &ScaleDpi) -> Option<ISize> {
+        Some(self.label.measure(self.ctrl.get_cur_style(), dpi))
+    }
+
+    fn update_dpi(&mut self, dpi: &ScaleDpi) {
+        self.dpi_cache = dpi.clone();
+        self.label.update(self.ctrl.get_cur_style(), dpi);
+    }
+
+    fn on_event(&mut self, event: &mut UIEvent) {
+        if !event.is_target() || self.ctrl.status == ECtrlStatus::Disable {
+            return;
+        }
+        match &event.kind {
+            EUIEvent::Click { .. } if !self.is_checked() => {
+                self.set_checked(true);
+                event.post(EUIEvent::Toggled);
+                event.request_redraw();
+            }
+            EUIEvent::KeyDown(input) if input.key == EKey::Space => {
+                let rc = self.ctrl.inner.real_rc;
+                let pos = IPoint::new(rc.left + rc.width() / 2, rc.top + rc.height() / 2);
+                event.post(EUIEvent::Click { pos });
+                event.stop_propagation();
+            }
+            _ => (),
+        }
+    }
+
+    fn render(&self, canvas: &mut Canvas, dpi: &ScaleDpi) {
+        if self.ctrl.inner.empty_paint() {
+            return;
+        }
+        let style = self.ctrl.get_cur_style();
+        self.ctrl.inner.render(canvas, dpi, &style.base);
+
+        let rc = self.ctrl.inner.real_rc;
+        let b = self.label.get_box_rc(&rc, style, dpi);
+        let center = b.center();
+        let r = b.width() / 2.;
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_color(style.box_color);
+        canvas.draw_circle(center, r, &paint);
+
+        let w = dpi.scale(1) as f32;
+        paint.set_style(Style::Stroke);
+        paint.set_stroke_width(w);
+        paint.set_color(style.box_border_color);
+        canvas.draw_circle(center, r - w / 2., &paint);
+
+        if self.is_checked() {
+            paint.set_style(Style::Fill);
+            paint.set_color(style.mark_color);
+            canvas.draw_circle(center, r / 2., &paint);
+        }
+
+        self.label.render(canvas, &rc, style, dpi);
+    }
+}
+
+/// Holds `RadioButton`s anywhere below it and keeps at most one of them
+/// checked, other controls can be mixed in. Posts `EUIEvent::Change` when
+/// the user checks another one. Buttons of a nested `RadioGroup` are its own.
+pub struct RadioGroup {
+    /// the children, its `ctrl` is the control of the group
+    pub items: Container,
+}
+
+impl RadioGroup {
+    pub fn new(layout: Box<dyn TLayout>) -> Self {
+        Self {
+            items: Container::new(layout),
+        }
+    }
+
+    pub fn append_child(&mut self, c: Box<dyn TCtrl>) {
+        self.items.append_child(c);
+    }
+
+    /// index among the `RadioButton`s in tree order
+    pub fn get_checked(&self) -> Option<usize> {
+        let mut paths = Vec::new();
+        collect_radios(&self.items, &mut Vec::new(), &mut paths);
+        paths.iter().position(|p| {
+            self.items
+                .get_ctrl_by_path(p)
+                .and_then(|c| c.downcast_ref::<RadioButton>())
+                .map_or(false, |r| r.is_checked())
+        })
+    }
+
+    /// checks the button at `index` and unchecks the others, no event is posted
+    pub fn set_checked(&mut self, index: Option<usize>) {
+        let mut paths = Vec::new();
+        collect_radios(&self.items, &mut Vec::new(), &mut paths);
+        for (i, p) in paths.iter().enumerate() {
+            if let Some(r) = self
+                .items
+                .get_mut_ctrl_by_path(p)
+                .and_then(|c| c.downcast_mut::<RadioButton>())
+            {
+                r.set_checked(Some(i) == index);
+            }
+        }
+    }
+}
+
+// paths of the `RadioButton`s below `c`, not those of nested groups
+fn collect_radios(c: &Container, prefix: &mut Vec<usize>, out: &mut Vec<Vec<usize>>) {
+    for (i, child) in c.get_children().iter().enumerate() {
+        let child = child.as_ref();
+        prefix.push(i);
+        if child.downcast_ref::<RadioButton>().is_some() {
+            out.push(prefix.clone());
+        } else if let (Some(s), false) = (
+            child.as_container(),
+            child.downcast_ref::<RadioGroup>().is_some(),
+        ) {
+            collect_radios(s, prefix, out);
+        }
+        prefix.pop();
+    }
+}
+
+impl TCtrl for RadioGroup {
+    fn get_inner(&self) -> &TCtrlInner {
+        self.items.get_inner()
+    }
+    fn get_mut_inner(&mut self) -> &mut TCtrlInner {
+        self.items.get_mut_inner()
+    }
+    fn get_style(&self, status: EUIStatus) -> Option<&StyleInner> {
+        self.items.get_style(status)
+    }
+    fn get_mut_style(&mut self, status: EUIStatus) -> Option<&mut StyleInner> {
+        self.items.get_mut_style(status)
+    }
+    fn type_name(&self) -> &str {
+        "RadioGroup"
+    }
+    fn type_(&self) -> ECtrlType {
+        ECtrlType::RadioGroup
+    }
+    fn as_container(&self) -> Option<&Container> {
+        Some(&self.items)
+    }
+    fn as_mut_container(&mut self) -> Option<&mut Container> {
+        Some(&mut self.items)
+    }
+    fn get_status(&self) -> ECtrlStatus {
+        self.items.get_status()
+    }
+    fn set_status(&mut self, status: ECtrlStatus) {
+        self.items.set_status(status);
+    }
+
+    fn update(&mut self, dpi: &ScaleDpi) {
+        self.items.update(dpi);
+    }
+    fn get_preferred_size(&self, max: ISize, dpi: &ScaleDpi) -> Option<ISize> {
+        self.items.get_preferred_size(max, dpi)
+    }
+
+    fn on_event(&mut self, event: &mut UIEvent) {
+        if event.phase != EEventPhase::Bubble || event.kind != EUIEvent::Toggled {
+            return;
+        }
+        // the button below this group that was just checked
+        let path = event.target[event.current.len()..].to_vec();
+        let mut paths = Vec::new();
+        collect_radios(&self.items, &mut Vec::new(), &mut paths);
+        let index = match paths.iter().position(|p| *p == path) {
+            Some(i) => i,
+            None => return,
+        };
+
+        self.set_checked(Some(index));
+        event.post(EUIEvent::Change);
+        event.request_redraw();
+    }
+
+    fn render(&self, canvas: &mut Canvas, dpi: &ScaleDpi) {
+        self.items.render(canvas, dpi);
+    }
+}
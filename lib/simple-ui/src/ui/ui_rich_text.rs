@@ -0,0 +1,329 @@
+use crate::{
+    font::{self, FontDesc},
+    ui::{
+        styles::{EUIStatus, StyleInner, TStyle},
+        ui_ctrl::{BaseCtrl, CtrlStyle, TCtrlInner},
+        EAlign, ECtrlStatus, ECtrlType, EUIEvent, TCtrl, UIEvent,
+    },
+    utils::ScaleDpi,
+    Color, IPoint, ISize,
+};
+use skia_safe::{
+    font_style::{Slant, Weight, Width},
+    textlayout::{
+        Paragraph, ParagraphBuilder, ParagraphStyle, RectHeightStyle, RectWidthStyle, TextAlign,
+        TextDecoration, TextStyle as SkTextStyle,
+    },
+    FontStyle, Paint, Point, Rect,
+};
+use std::ops::Range;
+
+/// A piece of `RichText` with its own look, what is not set comes from the style.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct TextSpan {
+    pub text: String,
+    pub bold: bool,
+    pub italic: bool,
+    pub color: Option<Color>,
+    pub size: Option<i32>,
+    pub underline: bool,
+    pub strikethrough: bool,
+    pub background: Option<Color>,
+    /// clicking it posts `EUIEvent::Link` with this url
+    pub link: Option<String>,
+}
+
+impl TextSpan {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+    pub fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+    pub fn color(mut self, c: Color) -> Self {
+        self.color = Some(c);
+        self
+    }
+    pub fn size(mut self, size: i32) -> Self {
+        self.size = Some(size);
+        self
+    }
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+    pub fn strikethrough(mut self) -> Self {
+        self.strikethrough = true;
+        self
+    }
+    pub fn background(mut self, c: Color) -> Self {
+        self.background = Some(c);
+        self
+    }
+    pub fn link(mut self, url: &str) -> Self {
+        self.link = Some(url.to_string());
+        self
+    }
+}
+
+pub struct RichTextStyle {
+    base: CtrlStyle,
+
+    pub font: FontDesc,
+    pub font_color: Color,
+    pub link_color: Color,
+    pub align_x: EAlign,
+}
+
+impl TStyle for RichTextStyle {
+    fn get_inner(&self) -> &StyleInner {
+        &self.base
+    }
+    fn get_mut_inner(&mut self) -> &mut StyleInner {
+        &mut self.base
+    }
+}
+
+impl Default for RichTextStyle {
+    fn default() -> Self {
+        Self {
+            base: CtrlStyle::default(),
+            font: FontDesc::default(),
+            font_color: Color::BLACK,
+            link_color: Color::from_rgb(0x1a, 0x5f, 0xd0),
+            align_x: EAlign::Start,
+        }
+    }
+}
+
+/// Styled spans laid out by skia's paragraph engine, which does the shaping,
+/// bidi and line breaking. Wraps to the width of the control, top aligned.
+pub struct RichText {
+    pub ctrl: BaseCtrl<RichTextStyle>,
+
+    spans: Vec<TextSpan>,
+    text: String,
+    // utf-16 ranges of the links, what the paragraph counts in
+    links: Vec<(Range<usize>, String)>,
+
+    paragraph: Option<Paragraph>,
+    dpi_cache: ScaleDpi,
+}
+
+impl RichText {
+    pub fn new(font_size: i32) -> Self {
+        let mut ctrl = BaseCtrl::<RichTextStyle>::default();
+        ctrl.styles.default.font.size = font_size;
+
+        Self {
+            ctrl,
+
+            spans: Vec::new(),
+            text: String::new(),
+            links: Vec::new(),
+
+            paragraph: None,
+            dpi_cache: ScaleDpi::default(),
+        }
+    }
+
+    pub fn set_spans(&mut self, spans: Vec<TextSpan>) {
+        self.spans = spans;
+        self.update_spans();
+    }
+
+    pub fn append(&mut self, span: TextSpan) {
+        self.spans.push(span);
+        self.update_spans();
+    }
+
+    pub fn clear(&mut self) {
+        self.set_spans(Vec::new());
+    }
+
+    pub fn get_spans(&self) -> &[TextSpan] {
+        &self.spans
+    }
+
+    /// url of the link under `pos`, in physical pixels
+    pub fn get_link_at(&self, pos: IPoint) -> Option<&str> {
+        let p = self.paragraph.as_ref()?;
+        let rc = self.ctrl.inner.real_rc;
+        let pt = Point::new((pos.x - rc.left) as f32, (pos.y - rc.top) as f32);
+
+        self.links
+            .iter()
+            .find(|(r, _)| {
+                p.get_rects_for_range(r.clone(), RectHeightStyle::Max, RectWidthStyle::Tight)
+                    .iter()
+                    .any(|b| b.rect.contains(pt))
+            })
+            .map(|(_, url)| url.as_str())
+    }
+
+    /// Size of the laid out spans in pixels, wrapped into `width` if any.
+    pub fn measure(&self, width: Option<i32>) -> ISize {
+        let mut p = self.build();
+        p.layout(width.map_or(f32::INFINITY, |w| w as f32));
+        ISize::new(p.longest_line().ceil() as i32, p.height().ceil() as i32)
+    }
+
+    fn update_spans(&mut self) {
+        self.text = self.spans.iter().map(|s| s.text.as_str()).collect();
+
+        self.links.clear();
+        let mut at = 0;
+        for span in &self.spans {
+            let len = span.text.encode_utf16().count();
+            if let Some(url) = &span.link {
+                self.links.push((at..at + len, url.clone()));
+            }
+            at += len;
+        }
+
+        self.relayout();
+    }
+
+    fn build(&self) -> Paragraph {
+        let style = self.ctrl.get_cur_style();
+
+        let mut ps = ParagraphStyle::new();
+        ps.set_text_align(match style.align_x {
+            EAlign::Start => TextAlign::Left,
+            EAlign::Center => TextAlign::Center,
+            EAlign::End => TextAlign::Right,
+        });
+        let mut builder = ParagraphBuilder::new(&ps, font::get_collection());
+
+        let families = font::get_families(style.font.family.as_deref());
+        let base = style.font.font_style();
+        for span in &self.spans {
+            let color = match (span.color, &span.link) {
+                (Some(c), _) => c,
+                (None, Some(_)) => style.link_color,
+                (None, None) => style.font_color,
+            };
+            let size = span.size.unwrap_or(style.font.size);
+            let weight = if span.bold {
+                style.font.weight.max(700)
+            } else {
+                style.font.weight
+            };
+            let slant = if span.italic {
+                Slant::Italic
+            } else {
+                base.slant()
+            };
+
+            let mut ts = SkTextStyle::new();
+            ts.set_color(color);
+            ts.set_font_size(self.dpi_cache.scale(size) as f32);
+            ts.set_font_families(&families);
+            ts.set_font_style(FontStyle::new(Weight::from(weight), Width::NORMAL, slant));
+
+            let mut deco = TextDecoration::NO_DECORATION;
+            if span.underline || span.link.is_some() {
+                deco |= TextDecoration::UNDERLINE;
+            }
+            if span.strikethrough {
+                deco |= TextDecoration::LINE_THROUGH;
+            }
+            ts.set_decoration_type(deco);
+            ts.set_decoration_color(color);
+
+            if let Some(bg) = span.background {
+                let mut paint = Paint::default();
+                paint.set_color(bg);
+                ts.set_background_paint(&paint);
+            }
+
+            builder.push_style(&ts);
+            builder.add_text(&span.text);
+            builder.pop();
+        }
+        builder.build()
+    }
+
+    fn relayout(&mut self) {
+        let mut p = self.build();
+        p.layout(self.ctrl.inner.real_rc.width() as f32);
+        self.paragraph = Some(p);
+    }
+}
+
+impl TCtrl for RichText {
+    fn get_inner(&self) -> &TCtrlInner {
+        &self.ctrl.inner
+    }
+    fn get_mut_inner(&mut self) -> &mut TCtrlInner {
+        &mut self.ctrl.inner
+    }
+    fn get_style(&self, status: EUIStatus) -> Option<&StyleInner> {
+        self.ctrl.styles.get_inner_style(status)
+    }
+    fn get_mut_style(&mut self, status: EUIStatus) -> Option<&mut StyleInner> {
+        self.ctrl.styles.get_mut_inner_style(status)
+    }
+    fn type_name(&self) -> &str {
+        "RichText"
+    }
+    fn type_(&self) -> ECtrlType {
+        ECtrlType::RichText
+    }
+    fn get_status(&self) -> ECtrlStatus {
+        self.ctrl.status
+    }
+    fn set_status(&mut self, status: ECtrlStatus) {
+        self.ctrl.status = status;
+    }
+    fn get_text(&self) -> Option<&str> {
+        Some(&self.text)
+    }
+
+    fn update(&mut self, _dpi: &ScaleDpi) {
+        self.relayout();
+    }
+
+    fn get_preferred_size(&self, max: ISize, _dpi: &ScaleDpi) -> Option<ISize> {
+        Some(self.measure(Some(max.width)))
+    }
+
+    fn update_dpi(&mut self, dpi: &ScaleDpi) {
+        self.dpi_cache = dpi.clone();
+        self.relayout();
+    }
+
+    fn on_event(&mut self, event: &mut UIEvent) {
+        if !event.is_target() || self.ctrl.status == ECtrlStatus::Disable {
+            return;
+        }
+        if let EUIEvent::Click { pos } = event.kind {
+            if let Some(url) = self.get_link_at(pos) {
+                event.post(EUIEvent::Link(url.to_string()));
+            }
+        }
+    }
+
+    fn render(&self, canvas: &mut skia_safe::Canvas, dpi: &ScaleDpi) {
+        self.ctrl
+            .inner
+            .render(canvas, dpi, &self.ctrl.get_cur_style().base);
+
+        let rc = Rect::from(self.ctrl.inner.real_rc);
+        if let Some(p) = &self.paragraph {
+            canvas.save();
+            canvas.clip_rect(rc, None, None);
+            p.paint(canvas, Point::new(rc.left, rc.top));
+            canvas.restore();
+        }
+    }
+}
@@ -0,0 +1,326 @@
+use crate::{
+    input::EMouseButton,
+    ui::{
+        styles::{EUIStatus, StyleInner, TStyle},
+        ui_ctrl::{BaseCtrl, CtrlStyle, TCtrlInner},
+        ECtrlStatus, ECtrlType, EUIEvent, TCtrl, UIEvent,
+    },
+    utils::ScaleDpi,
+    Color, IPoint, IRect,
+};
+use skia_safe::{Canvas, Paint, Rect};
+
+/// shortest thumb, logical pixels
+const MIN_THUMB: i32 = 16;
+
+pub struct ScrollBarStyle {
+    base: CtrlStyle,
+
+    pub track_color: Option<Color>,
+    pub thumb_color: Color,
+    /// while the thumb is dragged
+    pub thumb_drag_color: Color,
+}
+
+impl TStyle for ScrollBarStyle {
+    fn get_inner(&self) -> &StyleInner {
+        &self.base
+    }
+    fn get_mut_inner(&mut self) -> &mut StyleInner {
+        &mut self.base
+    }
+}
+
+impl Default for ScrollBarStyle {
+    fn default() -> Self {
+        Self {
+            base: CtrlStyle::default(),
+            track_color: Some(Color::from_rgb(0xf0, 0xf0, 0xf0)),
+            thumb_color: Color::from_rgb(0xc0, 0xc0, 0xc0),
+            thumb_drag_color: Color::from_rgb(0x90, 0x90, 0x90),
+        }
+    }
+}
+
+/// Moves a `page` long window over `range`, the value is where the window
+/// starts, from 0 to `range - page`. The thumb is dragged, a click on the
+/// track moves by a page. Changes made by the user post `EUIEvent::Change`.
+pub struct ScrollBar {
+    pub ctrl: BaseCtrl<ScrollBarStyle>,
+
+    vertical: bool,
+    range: i32,
+    page: i32,
+    value: i32,
+
+    // (mouse position, value) when the thumb was grabbed
+    drag: Option<(i32, i32)>,
+    dpi_cache: ScaleDpi,
+}
+
+impl ScrollBar {
+    pub fn new(vertical: bool) -> Self {
+        Self {
+            ctrl: BaseCtrl::default(),
+
+            vertical,
+            range: 0,
+            page: 0,
+            value: 0,
+
+            drag: None,
+            dpi_cache: ScaleDpi::default(),
+        }
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        self.vertical
+    }
+
+    pub fn set_range(&mut self, range: i32, page: i32) {
+        self.range = range.max(0);
+        self.page = page.max(0);
+        self.value = self.value.clamp(0, self.get_max_value());
+    }
+
+    pub fn get_range(&self) -> i32 {
+        self.range
+    }
+
+    pub fn get_page(&self) -> i32 {
+        self.page
+    }
+
+    pub fn get_value(&self) -> i32 {
+        self.value
+    }
+
+    pub fn get_max_value(&self) -> i32 {
+        (self.range - self.page).max(0)
+    }
+
+    /// clamped to the range, returns whether the value changed
+    pub fn set_value(&mut self, v: i32) -> bool {
+        let v = v.clamp(0, self.get_max_value());
+        if v == self.value {
+            return false;
+        }
+        self.value = v;
+        true
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    /// in physical pixels, empty when the whole range fits in a page
+    pub fn get_thumb_rc(&self) -> IRect {
+        let rc = self.ctrl.inner.real_rc;
+        match self.thumb() {
+            Some((start, len)) if self.vertical => {
+                IRect::new(rc.left, start, rc.right, start + len)
+            }
+            Some((start, len)) => IRect::new(start, rc.top, start + len, rc.bottom),
+            None => IRect::default(),
+        }
+    }
+
+    // (start, length) of the track on the main axis
+    fn track(&self) -> (i32, i32) {
+        let rc = self.ctrl.inner.real_rc;
+        if self.vertical {
+            (rc.top, rc.height())
+        } else {
+            (rc.left, rc.width())
+        }
+    }
+
+    // (start, length) of the thumb on the main axis
+    fn thumb(&self) -> Option<(i32, i32)> {
+        let (start, len) = self.track();
+        if self.range <= self.page || len <= 0 {
+            return None;
+        }
+
+        let min = self.dpi_cache.scale(MIN_THUMB).min(len);
+        let thumb = ((len as i64 * self.page as i64 / self.range as i64) as i32).max(min);
+        let room = (len - thumb) as i64;
+        let offset = room * self.value as i64 / self.get_max_value() as i64;
+        Some((start + offset as i32, thumb))
+    }
+
+    fn along(&self, pos: IPoint) -> i32 {
+        if self.vertical {
+            pos.y
+        } else {
+            pos.x
+        }
+    }
+
+    /// Mouse and wheel input in physical pixels, returns whether the value
+    /// changed. `ScrollView` forwards its events to its bars with it.
+    pub(crate) fn handle(&mut self, kind: &EUIEvent) -> bool {
+        match *kind {
+            EUIEvent::MouseDown {
+                pos,
+                button: EMouseButton::Left,
+            } => {
+                let (start, len) = match self.thumb() {
+                    Some(v) => v,
+                    None => return false,
+                };
+                let u = self.along(pos);
+                if u < start {
+                    self.set_value(self.value - self.page)
+                } else if u >= start + len {
+                    self.set_value(self.value + self.page)
+                } else {
+                    self.drag = Some((u, self.value));
+                    false
+                }
+            }
+            EUIEvent::MouseMove { pos } => {
+                let ((from, value), (_, len), (_, thumb)) =
+                    match (self.drag, self.track(), self.thumb()) {
+                        (Some(d), t, Some(th)) => (d, t, th),
+                        _ => return false,
+                    };
+                let room = (len - thumb).max(1) as i64;
+                let moved = (self.along(pos) - from) as i64 * self.get_max_value() as i64 / room;
+                self.set_value(value + moved as i32)
+            }
+            EUIEvent::MouseUp {
+                button: EMouseButton::Left,
+                ..
+            } => {
+                self.drag = None;
+                false
+            }
+            EUIEvent::Wheel { delta, .. } => {
+                // a plain wheel scrolls horizontal bars too
+                let d = if self.vertical || delta.x == 0. {
+                    delta.y
+                } else {
+                    delta.x
+                };
+                self.set_value(self.value - d as i32)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl TCtrl for ScrollBar {
+    fn get_inner(&self) -> &TCtrlInner {
+        &self.ctrl.inner
+    }
+    fn get_mut_inner(&mut self) -> &mut TCtrlInner {
+        &mut self.ctrl.inner
+    }
+    fn get_style(&self, status: EUIStatus) -> Option<&StyleInner> {
+        self.ctrl.styles.get_inner_style(status)
+    }
+    fn get_mut_style(&mut self, status: EUIStatus) -> Option<&mut StyleInner> {
+        self.ctrl.styles.get_mut_inner_style(status)
+    }
+    fn type_name(&self) -> &str {
+        "ScrollBar"
+    }
+    fn type_(&self) -> ECtrlType {
+        ECtrlType::ScrollBar
+    }
+    fn get_status(&self) -> ECtrlStatus {
+        self.ctrl.status
+    }
+    fn set_status(&mut self, status: ECtrlStatus) {
+        self.ctrl.status = status;
+    }
+
+    fn update_dpi(&mut self, dpi: &ScaleDpi) {
+        self.dpi_cache = dpi.clone();
+    }
+
+    fn on_event(&mut self, event: &mut UIEvent) {
+        if !event.is_target() || self.ctrl.status == ECtrlStatus::Disable {
+            return;
+        }
+
+        let is_wheel = matches!(event.kind, EUIEvent::Wheel { .. });
+        if self.handle(&event.kind) {
+            event.post(EUIEvent::Change);
+            event.request_redraw();
+            if is_wheel {
+                event.stop_propagation();
+            }
+        } else if matches!(
+            event.kind,
+            EUIEvent::MouseDown { .. } | EUIEvent::MouseUp { .. }
+        ) {
+            // the thumb color follows the drag
+            event.request_redraw();
+        }
+    }
+
+    fn render(&self, canvas: &mut Canvas, dpi: &ScaleDpi) {
+        let style = self.ctrl.get_cur_style();
+        self.ctrl.inner.render(canvas, dpi, &style.base);
+        if self.ctrl.inner.empty_paint() {
+            return;
+        }
+
+        let mut paint = Paint::default();
+        if let Some(c) = style.track_color {
+            paint.set_color(c);
+            canvas.draw_rect(Rect::from(self.ctrl.inner.real_rc), &paint);
+        }
+
+        let thumb = self.get_thumb_rc();
+        if thumb.is_empty() {
+            return;
+        }
+        let inset = dpi.scale(2) as f32;
+        let rc = Rect::from(thumb).with_inset((inset, inset));
+        let round = rc.width().min(rc.height()) / 2.;
+        paint.set_anti_alias(true);
+        paint.set_color(if self.is_dragging() {
+            style.thumb_drag_color
+        } else {
+            style.thumb_color
+        });
+        canvas.draw_round_rect(rc, round, round, &paint);
+    }
+}
+
+#[test]
+fn test_scroll_bar() {
+    let mut bar = ScrollBar::new(true);
+    bar.ctrl.inner.real_rc = IRect::new(0, 0, 10, 100);
+    bar.set_range(400, 100);
+    assert_eq!(bar.get_max_value(), 300);
+    assert_eq!(bar.get_thumb_rc(), IRect::new(0, 0, 10, 25));
+
+    // below the thumb is a page down
+    let down = |y| EUIEvent::MouseDown {
+        pos: IPoint::new(5, y),
+        button: EMouseButton::Left,
+    };
+    assert!(bar.handle(&down(90)));
+    assert_eq!(bar.get_value(), 100);
+    assert_eq!(bar.get_thumb_rc(), IRect::new(0, 25, 10, 50));
+
+    // 75 pixels of track move the thumb through 300
+    assert!(!bar.handle(&down(30)));
+    assert!(bar.is_dragging());
+    bar.handle(&EUIEvent::MouseMove {
+        pos: IPoint::new(5, 55),
+    });
+    assert_eq!(bar.get_value(), 200);
+    bar.handle(&EUIEvent::MouseMove {
+        pos: IPoint::new(5, 500),
+    });
+    assert_eq!(bar.get_value(), 300);
+
+    bar.set_range(50, 100);
+    assert_eq!(bar.get_value(), 0);
+    assert!(bar.get_thumb_rc().is_empty());
+}
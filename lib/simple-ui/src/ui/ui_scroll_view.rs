@@ -0,0 +1,422 @@
+use crate::{
+    ui::{
+        styles::{EUIStatus, StyleInner},
+        ui_ctrl::{BaseCtrl, CtrlStyle, TCtrlInner},
+        Container, ECtrlStatus, ECtrlType, EEventPhase, EUIEvent, ScrollBar, TCtrl, TLayout,
+        UIEvent,
+    },
+    utils::{in_rc, ScaleDpi},
+    IPoint, IRect, ISize, Point,
+};
+use skia_safe::{Canvas, Rect};
+use std::time::Duration;
+
+const INERTIA_TIMER: u32 = 1;
+const INERTIA_STEP: Duration = Duration::from_millis(16);
+// kept of the speed each step
+const INERTIA_FRICTION: f32 = 0.85;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum EScrollBarMode {
+    /// shown when the content does not fit
+    Auto,
+    Always,
+    /// the content is fit to the viewport on that axis and never scrolls
+    Never,
+}
+
+/// Shows a part of a content `Container` that can be larger than itself.
+/// The content is measured with its layout, scrolled by the wheel (Shift
+/// for horizontal) or by the bars, and the focused control is scrolled into
+/// view. Children are reached through the content like those of a
+/// `Container`. Scrolling posts `EUIEvent::Change`. Offsets and sizes are
+/// in physical pixels.
+pub struct ScrollView {
+    pub ctrl: BaseCtrl<CtrlStyle>,
+
+    pub h_mode: EScrollBarMode,
+    pub v_mode: EScrollBarMode,
+    /// thickness of the bars, logical pixels
+    pub bar_size: i32,
+    /// the wheel keeps scrolling for a while after it stops, slowing down
+    pub inertia: bool,
+
+    content: Container,
+    hbar: ScrollBar,
+    vbar: ScrollBar,
+
+    offset: IPoint,
+    content_size: ISize,
+    viewport: IRect,
+    // physical pixels a step
+    velocity: Point,
+    dpi_cache: ScaleDpi,
+}
+
+impl ScrollView {
+    pub fn new(layout: Box<dyn TLayout>) -> Self {
+        Self {
+            ctrl: BaseCtrl::default(),
+
+            h_mode: EScrollBarMode::Auto,
+            v_mode: EScrollBarMode::Auto,
+            bar_size: 10,
+            inertia: false,
+
+            content: Container::new(layout),
+            hbar: ScrollBar::new(false),
+            vbar: ScrollBar::new(true),
+
+            offset: IPoint::default(),
+            content_size: ISize::default(),
+            viewport: IRect::default(),
+            velocity: Point::default(),
+            dpi_cache: ScaleDpi::default(),
+        }
+    }
+
+    pub fn get_content(&self) -> &Container {
+        &self.content
+    }
+    pub fn get_mut_content(&mut self) -> &mut Container {
+        &mut self.content
+    }
+    pub fn append_child(&mut self, c: Box<dyn TCtrl>) {
+        self.content.append_child(c);
+    }
+
+    pub fn get_hbar(&self) -> &ScrollBar {
+        &self.hbar
+    }
+    pub fn get_vbar(&self) -> &ScrollBar {
+        &self.vbar
+    }
+    pub fn get_mut_hbar(&mut self) -> &mut ScrollBar {
+        &mut self.hbar
+    }
+    pub fn get_mut_vbar(&mut self) -> &mut ScrollBar {
+        &mut self.vbar
+    }
+
+    pub fn get_offset(&self) -> IPoint {
+        self.offset
+    }
+    pub fn get_content_size(&self) -> ISize {
+        self.content_size
+    }
+    /// the part of the control showing the content, without the bars
+    pub fn get_viewport(&self) -> IRect {
+        self.viewport
+    }
+    pub fn get_max_offset(&self) -> IPoint {
+        IPoint::new(
+            (self.content_size.width - self.viewport.width()).max(0),
+            (self.content_size.height - self.viewport.height()).max(0),
+        )
+    }
+
+    /// clamped to the content, returns whether the offset changed
+    pub fn scroll_to(&mut self, offset: IPoint) -> bool {
+        let max = self.get_max_offset();
+        let offset = IPoint::new(offset.x.clamp(0, max.x), offset.y.clamp(0, max.y));
+        if offset == self.offset {
+            return false;
+        }
+        self.offset = offset;
+        self.place();
+        true
+    }
+
+    pub fn scroll_by(&mut self, delta: IPoint) -> bool {
+        self.scroll_to(self.offset + delta)
+    }
+
+    /// Scrolls as little as needed to show `rc`, its start wins when it is
+    /// larger than the viewport. `rc` is where it is drawn now.
+    pub fn scroll_rect_into_view(&mut self, rc: IRect) -> bool {
+        let vp = self.viewport;
+        let along = |start: i32, end: i32, vp_start: i32, vp_end: i32| {
+            if start < vp_start {
+                start - vp_start
+            } else if end > vp_end {
+                (end - vp_end).min(start - vp_start)
+            } else {
+                0
+            }
+        };
+        let dx = along(rc.left, rc.right, vp.left, vp.right);
+        let dy = along(rc.top, rc.bottom, vp.top, vp.bottom);
+        self.scroll_by(IPoint::new(dx, dy))
+    }
+
+    /// `scroll_rect_into_view` with a control of the content
+    pub fn scroll_into_view(&mut self, name: &str) -> bool {
+        match self.content.get_ctrl_by_name(name) {
+            Some(c) => {
+                let rc = c.get_inner().real_rc;
+                self.scroll_rect_into_view(rc)
+            }
+            None => false,
+        }
+    }
+
+    // size of the content shown in `vp`, at least `vp`
+    fn measure_content(&self, vp: ISize) -> ISize {
+        let dpi = &self.dpi_cache;
+        let unbound = i32::MAX / 2;
+        let max = ISize::new(
+            match self.h_mode {
+                EScrollBarMode::Never => vp.width,
+                _ => unbound,
+            },
+            match self.v_mode {
+                EScrollBarMode::Never => vp.height,
+                _ => unbound,
+            },
+        );
+        let pref = self
+            .content
+            .get_preferred_size(max, dpi)
+            .unwrap_or_default();
+
+        let size = &self.content.get_inner().size;
+        let width = size
+            .width
+            .and_then(|u| u.resolve(vp.width, dpi))
+            .unwrap_or(pref.width);
+        let height = size
+            .height
+            .and_then(|u| u.resolve(vp.height, dpi))
+            .unwrap_or(pref.height);
+        ISize::new(
+            match self.h_mode {
+                EScrollBarMode::Never => vp.width,
+                _ => width.max(vp.width),
+            },
+            match self.v_mode {
+                EScrollBarMode::Never => vp.height,
+                _ => height.max(vp.height),
+            },
+        )
+    }
+
+    fn relayout(&mut self) {
+        let rc = self.ctrl.inner.real_rc;
+        let bar = self.dpi_cache.scale(self.bar_size);
+
+        // a bar takes space from the other axis, so it can bring the other
+        // bar in, never out
+        let mut show_h = self.h_mode == EScrollBarMode::Always;
+        let mut show_v = self.v_mode == EScrollBarMode::Always;
+        let (vp, size) = loop {
+            let vp = IRect::new(
+                rc.left,
+                rc.top,
+                rc.right - if show_v { bar } else { 0 },
+                rc.bottom - if show_h { bar } else { 0 },
+            );
+            let size = self.measure_content(vp.size());
+            let need_h = self.h_mode == EScrollBarMode::Auto && size.width > vp.width();
+            let need_v = self.v_mode == EScrollBarMode::Auto && size.height > vp.height();
+            if (need_h && !show_h) || (need_v && !show_v) {
+                show_h |= need_h;
+                show_v |= need_v;
+                continue;
+            }
+            break (vp, size);
+        };
+
+        self.viewport = vp;
+        self.content_size = size;
+        self.hbar.ctrl.inner.real_rc = if show_h {
+            IRect::new(vp.left, vp.bottom, vp.right, rc.bottom)
+        } else {
+            IRect::default()
+        };
+        self.vbar.ctrl.inner.real_rc = if show_v {
+            IRect::new(vp.right, vp.top, rc.right, vp.bottom)
+        } else {
+            IRect::default()
+        };
+        self.hbar.set_range(size.width, vp.width());
+        self.vbar.set_range(size.height, vp.height());
+
+        let max = self.get_max_offset();
+        self.offset = IPoint::new(self.offset.x.clamp(0, max.x), self.offset.y.clamp(0, max.y));
+        self.place();
+    }
+
+    // moves the content to the offset
+    fn place(&mut self) {
+        let vp = self.viewport;
+        self.content.ctrl.inner.real_rc = IRect::from_xywh(
+            vp.left - self.offset.x,
+            vp.top - self.offset.y,
+            self.content_size.width,
+            self.content_size.height,
+        );
+        self.content.viewport = Some(vp);
+        self.content.update(&self.dpi_cache);
+
+        self.hbar.set_value(self.offset.x);
+        self.vbar.set_value(self.offset.y);
+    }
+
+    // mouse input over the bars, returns whether it scrolled
+    fn bar_event(&mut self, kind: &EUIEvent) -> bool {
+        let mut changed = false;
+        for bar in [&mut self.hbar, &mut self.vbar] {
+            let hit = match kind {
+                EUIEvent::MouseDown { pos, .. } => in_rc(&bar.ctrl.inner.real_rc, pos),
+                EUIEvent::MouseMove { .. } => bar.is_dragging(),
+                EUIEvent::MouseUp { .. } => true,
+                _ => false,
+            };
+            if hit {
+                changed |= bar.handle(kind);
+            }
+        }
+
+        changed && self.scroll_to(IPoint::new(self.hbar.get_value(), self.vbar.get_value()))
+    }
+
+    fn on_wheel(&mut self, delta: Point, event: &mut UIEvent) {
+        // Shift turns a vertical wheel sideways
+        let delta = if event.modifiers.shift && delta.x == 0. {
+            Point::new(delta.y, 0.)
+        } else {
+            delta
+        };
+        let moved = IPoint::new(-delta.x as i32, -delta.y as i32);
+        if !self.scroll_by(moved) {
+            return;
+        }
+
+        if self.inertia {
+            self.velocity = Point::new(moved.x as f32, moved.y as f32) * 0.5;
+            event.set_timer(INERTIA_TIMER, INERTIA_STEP);
+        }
+        event.post(EUIEvent::Change);
+        event.request_redraw();
+        event.stop_propagation();
+    }
+
+    fn on_inertia(&mut self, event: &mut UIEvent) {
+        self.velocity = self.velocity * INERTIA_FRICTION;
+        if self.velocity.length() < 0.5 {
+            self.velocity = Point::default();
+            return;
+        }
+
+        let step = IPoint::new(
+            self.velocity.x.round() as i32,
+            self.velocity.y.round() as i32,
+        );
+        if self.scroll_by(step) {
+            event.set_timer(INERTIA_TIMER, INERTIA_STEP);
+            event.post(EUIEvent::Change);
+            event.request_redraw();
+        } else {
+            self.velocity = Point::default();
+        }
+    }
+}
+
+impl TCtrl for ScrollView {
+    fn get_inner(&self) -> &TCtrlInner {
+        &self.ctrl.inner
+    }
+    fn get_mut_inner(&mut self) -> &mut TCtrlInner {
+        &mut self.ctrl.inner
+    }
+    fn get_style(&self, status: EUIStatus) -> Option<&StyleInner> {
+        self.ctrl.styles.get_inner_style(status)
+    }
+    fn get_mut_style(&mut self, status: EUIStatus) -> Option<&mut StyleInner> {
+        self.ctrl.styles.get_mut_inner_style(status)
+    }
+    fn type_name(&self) -> &str {
+        "ScrollView"
+    }
+    fn type_(&self) -> ECtrlType {
+        ECtrlType::ScrollView
+    }
+    fn as_container(&self) -> Option<&Container> {
+        Some(&self.content)
+    }
+    fn as_mut_container(&mut self) -> Option<&mut Container> {
+        Some(&mut self.content)
+    }
+    fn get_status(&self) -> ECtrlStatus {
+        self.ctrl.status
+    }
+    fn set_status(&mut self, status: ECtrlStatus) {
+        self.ctrl.status = status;
+    }
+
+    fn update(&mut self, dpi: &ScaleDpi) {
+        self.dpi_cache = dpi.clone();
+        self.relayout();
+    }
+
+    fn update_dpi(&mut self, dpi: &ScaleDpi) {
+        self.dpi_cache = dpi.clone();
+        self.content.update_dpi(dpi);
+        self.hbar.update_dpi(dpi);
+        self.vbar.update_dpi(dpi);
+    }
+
+    fn on_event(&mut self, event: &mut UIEvent) {
+        if event.phase == EEventPhase::Capture || self.ctrl.status == ECtrlStatus::Disable {
+            return;
+        }
+
+        match event.kind.clone() {
+            EUIEvent::Wheel { delta, .. } => self.on_wheel(delta, event),
+            EUIEvent::FocusIn if event.phase == EEventPhase::Bubble => {
+                let rc = match self
+                    .content
+                    .get_ctrl_by_path(&event.target[event.current.len()..])
+                {
+                    Some(c) => c.get_inner().real_rc,
+                    None => return,
+                };
+                if self.scroll_rect_into_view(rc) {
+                    event.post(EUIEvent::Change);
+                    event.request_redraw();
+                }
+            }
+            EUIEvent::Timer(INERTIA_TIMER) => self.on_inertia(event),
+            EUIEvent::MouseDown { .. } | EUIEvent::MouseMove { .. } | EUIEvent::MouseUp { .. }
+                if event.is_target() =>
+            {
+                let kind = event.kind.clone();
+                let dragging = self.hbar.is_dragging() || self.vbar.is_dragging();
+                if self.bar_event(&kind) {
+                    event.post(EUIEvent::Change);
+                    event.request_redraw();
+                } else if dragging != (self.hbar.is_dragging() || self.vbar.is_dragging()) {
+                    event.request_redraw();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn render(&self, canvas: &mut Canvas, dpi: &ScaleDpi) {
+        if self.ctrl.inner.empty_paint() {
+            return;
+        }
+        self.ctrl
+            .inner
+            .render(canvas, dpi, self.ctrl.get_cur_style());
+
+        canvas.save();
+        canvas.clip_rect(Rect::from(self.viewport), None, None);
+        self.content.render(canvas, dpi);
+        canvas.restore();
+
+        self.hbar.render(canvas, dpi);
+        self.vbar.render(canvas, dpi);
+    }
+}
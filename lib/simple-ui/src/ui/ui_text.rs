@@ -1,18 +1,119 @@
 use crate::{
+    font::{self, FontDesc},
     ui::{
         styles::{EUIStatus, StyleInner, TStyle},
         ui_ctrl::{BaseCtrl, CtrlStyle, TCtrlInner},
-        ECtrlType, TCtrl,
+        ECtrlStatus, ECtrlType, TCtrl,
     },
     utils::ScaleDpi,
-    Color,
+    Color, ISize,
 };
-use skia_safe::{paint::Paint, Font, Point, Typeface};
+use skia_safe::{paint::Paint, Font, Point, Rect, Shader, TileMode};
+use std::ops::Range;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum EWrapMode {
+    None,
+    /// breaks at spaces and around CJK chars, words longer than a row are cut
+    Word,
+    Char,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum EAlign {
+    Start,
+    Center,
+    End,
+}
+
+/// What happens to rows wider than the control.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum EOverflow {
+    /// drawn past the edges
+    Visible,
+    Clip,
+    /// cut at the end with "…", also marks rows hidden by `set_max_lines`
+    Ellipsis,
+    /// keeps both ends and puts "…" in between
+    EllipsisMiddle,
+    /// clipped with the cut edges fading out
+    Fade,
+}
+
+const ELLIPSIS: &str = "…";
+
+// chars a row may break before or after without a space
+fn is_wide(c: char) -> bool {
+    matches!(c,
+        '\u{2E80}'..='\u{9FFF}'
+        | '\u{AC00}'..='\u{D7AF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{FF00}'..='\u{FFEF}')
+}
+
+/// x of every char boundary of `text` from 0, one more than the chars
+pub(crate) fn char_offsets(font: &Font, text: &str) -> Vec<f32> {
+    let mut offsets = vec![0.];
+    for (f, s) in font::split_runs(font, text) {
+        let f = f.as_ref().unwrap_or(font);
+        let glyphs = f.str_to_glyphs_vec(s);
+        let mut widths = vec![0.; glyphs.len()];
+        f.get_widths(&glyphs, &mut widths);
+
+        let x = *offsets.last().unwrap();
+        let len = offsets.len() + s.chars().count();
+        offsets.extend(widths.iter().scan(x, |x, w| {
+            *x += w;
+            Some(*x)
+        }));
+        offsets.resize(len, *offsets.last().unwrap());
+    }
+    offsets
+}
+
+/// The char every row starts at when `chars` is wrapped into `width`, the
+/// first one is 0. Greedy, spaces at the end of a row may overflow it.
+pub(crate) fn wrap_rows(
+    chars: &[char],
+    offsets: &[f32],
+    width: f32,
+    mode: EWrapMode,
+) -> Vec<usize> {
+    let mut rows = vec![0];
+    if mode == EWrapMode::None || width <= 0. {
+        return rows;
+    }
+
+    let mut start = 0;
+    let mut last_break = None;
+    for (i, c) in chars.iter().enumerate() {
+        if mode == EWrapMode::Word && i > 0 && (is_wide(*c) || is_wide(chars[i - 1])) {
+            last_break = Some(i);
+        }
+        if i > start && offsets[i + 1] - offsets[start] > width && !c.is_whitespace() {
+            start = match last_break {
+                Some(b) if b > start && mode == EWrapMode::Word => b,
+                _ => i,
+            };
+            rows.push(start);
+            last_break = None;
+        }
+        if c.is_whitespace() {
+            last_break = Some(i + 1);
+        }
+    }
+    rows
+}
 
 pub struct TextStyle {
     base: CtrlStyle,
 
-    font_color: Color,
+    pub font: FontDesc,
+    pub font_color: Color,
+    pub align_x: EAlign,
+    /// `End` keeps the last row on the bottom edge
+    pub align_y: EAlign,
+    pub overflow: EOverflow,
 }
 
 impl TStyle for TextStyle {
@@ -28,7 +129,11 @@ impl Default for TextStyle {
     fn default() -> Self {
         Self {
             base: CtrlStyle::default(),
+            font: FontDesc::default(),
             font_color: Color::BLACK,
+            align_x: EAlign::Start,
+            align_y: EAlign::End,
+            overflow: EOverflow::Visible,
         }
     }
 }
@@ -37,28 +142,47 @@ pub struct Text {
     pub ctrl: BaseCtrl<TextStyle>,
 
     text: String,
-    font_size: i32,
+    wrap: EWrapMode,
+    line_spacing: f32,
+    max_lines: Option<usize>,
 
     font: Font,
     paint: Paint,
 
     dpi_cache: ScaleDpi,
     offset_y: f32,
+    line_height: f32,
+    ascent: f32,
+    rows: Vec<Row>,
+}
+
+// a row as drawn, after the ellipsis if any
+struct Row {
+    text: String,
+    width: f32,
 }
 
 impl Text {
     pub fn new(d: &str, font_size: i32) -> Self {
+        let mut ctrl = BaseCtrl::<TextStyle>::default();
+        ctrl.styles.default.font.size = font_size;
+
         Self {
-            ctrl: BaseCtrl::default(),
+            ctrl,
 
             text: d.to_string(),
-            font_size,
+            wrap: EWrapMode::None,
+            line_spacing: 1.,
+            max_lines: None,
 
             font: Font::default(),
             paint: Paint::default(),
 
             dpi_cache: ScaleDpi::default(),
             offset_y: 0.,
+            line_height: 0.,
+            ascent: 0.,
+            rows: Vec::new(),
         }
     }
 
@@ -67,22 +191,186 @@ impl Text {
         self.update_rc()
     }
 
+    /// `\n` always starts a new row, wrapping adds more to fit the width
+    pub fn set_wrap(&mut self, mode: EWrapMode) {
+        self.wrap = mode;
+        self.update_rows();
+    }
+
+    /// multiple of the font's line height
+    pub fn set_line_spacing(&mut self, v: f32) {
+        self.line_spacing = v;
+        self.update_rc();
+    }
+
+    /// rows after the last one are not shown
+    pub fn set_max_lines(&mut self, v: Option<usize>) {
+        self.max_lines = v;
+        self.update_rows();
+    }
+
+    /// rows shown after wrapping
+    pub fn get_line_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// a row as drawn, with the "…" of the ellipsis overflows
+    pub fn get_shown_line(&self, i: usize) -> Option<&str> {
+        self.rows.get(i).map(|r| r.text.as_str())
+    }
+
+    /// Size of the text in pixels, wrapped into `width` if wrapping is on.
+    /// Layouts use it to size the control before `real_rc` is known.
+    pub fn measure(&self, width: Option<i32>) -> ISize {
+        let (rows, _) = self.layout_rows(width.map(|w| w as f32));
+        let w = rows
+            .iter()
+            .map(|r| font::measure_text(&self.font, self.text[r.clone()].trim_end()))
+            .fold(0., f32::max);
+
+        ISize::new(
+            w.ceil() as i32,
+            (rows.len() as f32 * self.line_height).ceil() as i32,
+        )
+    }
+
     fn update_ctx(&mut self) {
-        self.font
-            .set_size(self.dpi_cache.scale(self.font_size) as f32);
-        let fm = Typeface::new("PingFang SC", skia_safe::FontStyle::default());
-        self.font.set_typeface(fm.unwrap());
+        let style = self.ctrl.get_cur_style();
+        self.font = style.font.make_font(&self.dpi_cache);
+        self.paint.set_color(style.font_color);
+        self.paint.set_anti_alias(true);
 
-        self.paint.set_color(self.ctrl.get_cur_style().font_color);
+        let (_, m) = self.font.metrics();
+        self.line_height = (m.descent - m.ascent + m.leading) * self.line_spacing;
+        self.ascent = -m.ascent;
     }
 
     fn update_rc(&mut self) {
         self.update_ctx();
+        self.update_rows();
+    }
+
+    // byte ranges of the rows in `text`, true if `max_lines` hid some
+    fn layout_rows(&self, width: Option<f32>) -> (Vec<Range<usize>>, bool) {
+        let mut rows = Vec::new();
+        let mut at = 0;
+
+        for para in self.text.split('\n') {
+            let start = at;
+            at += para.len() + 1;
+            let para = para.strip_suffix('\r').unwrap_or(para);
+
+            let width = match width {
+                Some(w) if self.wrap != EWrapMode::None => w,
+                _ => {
+                    rows.push(start..start + para.len());
+                    continue;
+                }
+            };
+
+            let chars = para.chars().collect::<Vec<_>>();
+            let bytes = para
+                .char_indices()
+                .map(|(i, _)| i)
+                .chain(std::iter::once(para.len()))
+                .collect::<Vec<_>>();
+            let offsets = char_offsets(&self.font, para);
+
+            let breaks = wrap_rows(&chars, &offsets, width, self.wrap);
+            for (k, b) in breaks.iter().enumerate() {
+                let end = breaks.get(k + 1).copied().unwrap_or(chars.len());
+                rows.push(start + bytes[*b]..start + bytes[end]);
+            }
+        }
+
+        let max = self.max_lines.unwrap_or(usize::MAX).max(1);
+        let cut = rows.len() > max;
+        rows.truncate(max);
+        (rows, cut)
+    }
+
+    fn update_rows(&mut self) {
+        let width = self.ctrl.inner.real_rc.width() as f32;
+        let overflow = self.ctrl.get_cur_style().overflow;
+        let (ranges, cut) = self.layout_rows(Some(width));
 
-        let (_s, rc) = self.font.measure_str(&self.text, Some(&self.paint));
+        let n = ranges.len();
+        self.rows = ranges
+            .into_iter()
+            .enumerate()
+            .map(|(i, r)| {
+                let more = cut && i + 1 == n;
+                self.fit_row(self.text[r].trim_end(), width, overflow, more)
+            })
+            .collect();
+
+        // the last row sits on the bottom like a single line always did
+        let last = self.rows.last().map_or("", |r| r.text.as_str());
+        let (_s, rc) = self.font.measure_str(last, Some(&self.paint));
 
         self.offset_y = rc.bottom;
     }
+
+    // `more` when rows after this one are hidden
+    fn fit_row(&self, s: &str, width: f32, overflow: EOverflow, more: bool) -> Row {
+        let offsets = char_offsets(&self.font, s);
+        let w = *offsets.last().unwrap();
+        let ellipsis = matches!(overflow, EOverflow::Ellipsis | EOverflow::EllipsisMiddle);
+        if !ellipsis || (w <= width && !more) {
+            return Row {
+                text: s.to_string(),
+                width: w,
+            };
+        }
+
+        let room = (width - font::measure_text(&self.font, ELLIPSIS)).max(0.);
+        let chars = s.chars().collect::<Vec<_>>();
+        let text = if overflow == EOverflow::EllipsisMiddle && !more {
+            let head = offsets.partition_point(|o| *o <= room / 2.) - 1;
+            let left = room - offsets[head];
+            let tail = offsets.partition_point(|o| w - *o > left).max(head);
+            let head = chars[..head].iter().collect::<String>();
+            let tail = chars[tail..].iter().collect::<String>();
+            format!("{}{}{}", head, ELLIPSIS, tail)
+        } else {
+            let head = offsets.partition_point(|o| *o <= room) - 1;
+            let head = chars[..head].iter().collect::<String>();
+            format!("{}{}", head.trim_end(), ELLIPSIS)
+        };
+
+        Row {
+            width: font::measure_text(&self.font, &text),
+            text,
+        }
+    }
+
+    // fades the sides of `rc` the row runs past
+    fn fade_paint(&self, rc: &Rect, x: f32, row_width: f32) -> Paint {
+        let color = self.paint.color();
+        let clear = color.with_a(0);
+        let fade = (self.font.size() * 2.).min(rc.width() / 3.) / rc.width().max(1.);
+        let colors = [
+            if x < rc.left { clear } else { color },
+            color,
+            color,
+            if x + row_width > rc.right {
+                clear
+            } else {
+                color
+            },
+        ];
+
+        let mut paint = self.paint.clone();
+        paint.set_shader(Shader::linear_gradient(
+            (Point::new(rc.left, 0.), Point::new(rc.right, 0.)),
+            &colors[..],
+            &[0., fade, 1. - fade, 1.][..],
+            TileMode::Clamp,
+            None,
+            None,
+        ));
+        paint
+    }
 }
 
 impl TCtrl for Text {
@@ -104,6 +392,23 @@ impl TCtrl for Text {
     fn type_(&self) -> ECtrlType {
         ECtrlType::Text
     }
+    fn get_status(&self) -> ECtrlStatus {
+        self.ctrl.status
+    }
+    fn set_status(&mut self, status: ECtrlStatus) {
+        self.ctrl.status = status;
+    }
+    fn get_text(&self) -> Option<&str> {
+        Some(&self.text)
+    }
+
+    fn update(&mut self, _dpi: &ScaleDpi) {
+        self.update_rows();
+    }
+
+    fn get_preferred_size(&self, max: ISize, _dpi: &ScaleDpi) -> Option<ISize> {
+        Some(self.measure(Some(max.width)))
+    }
 
     fn render(&self, canvas: &mut skia_safe::Canvas, dpi: &ScaleDpi) {
         assert_eq!(dpi, &self.dpi_cache);
@@ -111,14 +416,47 @@ impl TCtrl for Text {
         self.ctrl
             .inner
             .render(canvas, dpi, &self.ctrl.get_cur_style().base);
-        let real_rc = self.ctrl.inner.real_rc;
+        let style = self.ctrl.get_cur_style();
+        let rc = Rect::from(self.ctrl.inner.real_rc);
 
-        canvas.draw_str(
-            &self.text,
-            Point::new(real_rc.left as f32, real_rc.bottom as f32 - self.offset_y),
-            &self.font,
-            &self.paint,
-        );
+        let n = self.rows.len();
+        let block = n as f32 * self.line_height;
+        let first_y = match style.align_y {
+            EAlign::Start => rc.top + self.ascent,
+            EAlign::Center => rc.top + (rc.height() - block) / 2. + self.ascent,
+            EAlign::End => {
+                rc.bottom - self.offset_y - n.saturating_sub(1) as f32 * self.line_height
+            }
+        };
+
+        let clip = style.overflow != EOverflow::Visible;
+        if clip {
+            canvas.save();
+            canvas.clip_rect(rc, None, None);
+        }
+
+        for (i, row) in self.rows.iter().enumerate() {
+            let x = match style.align_x {
+                EAlign::Start => rc.left,
+                EAlign::Center => rc.left + (rc.width() - row.width) / 2.,
+                EAlign::End => rc.right - row.width,
+            };
+            let y = first_y + i as f32 * self.line_height;
+
+            let fade = style.overflow == EOverflow::Fade && row.width > rc.width();
+            let faded;
+            let paint = if fade {
+                faded = self.fade_paint(&rc, x, row.width);
+                &faded
+            } else {
+                &self.paint
+            };
+            font::draw_text(canvas, &row.text, Point::new(x, y), &self.font, paint);
+        }
+
+        if clip {
+            canvas.restore();
+        }
     }
 
     fn update_dpi(&mut self, dpi: &ScaleDpi) {
@@ -126,3 +464,26 @@ impl TCtrl for Text {
         self.update_rc();
     }
 }
+
+#[test]
+fn test_wrap_rows() {
+    let chars = "ab cd efgh".chars().collect::<Vec<_>>();
+    // every char is 10 wide
+    let offsets = (0..=chars.len())
+        .map(|i| i as f32 * 10.)
+        .collect::<Vec<_>>();
+
+    assert_eq!(wrap_rows(&chars, &offsets, 45., EWrapMode::None), [0]);
+    assert_eq!(wrap_rows(&chars, &offsets, 45., EWrapMode::Word), [0, 3, 6]);
+    assert_eq!(wrap_rows(&chars, &offsets, 45., EWrapMode::Char), [0, 4, 8]);
+    assert_eq!(
+        wrap_rows(&chars, &offsets, 25., EWrapMode::Word),
+        [0, 3, 6, 8]
+    );
+
+    let chars = "中文ab".chars().collect::<Vec<_>>();
+    assert_eq!(
+        wrap_rows(&chars, &offsets[..5], 25., EWrapMode::Word),
+        [0, 2]
+    );
+}
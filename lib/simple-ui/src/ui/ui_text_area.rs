@@ -0,0 +1,1025 @@
+use crate::{
+    clipboard, font,
+    input::{EKey, EMouseButton, KeyInput},
+    ui::{
+        styles::{EUIStatus, StyleInner, TStyle},
+        ui_ctrl::{BaseCtrl, TCtrlInner},
+        ui_edit::{word_left, word_right, EditStyle},
+        ui_text::{char_offsets, wrap_rows, EWrapMode},
+        ECtrlStatus, ECtrlType, EUIEvent, TCtrl, UIEvent,
+    },
+    utils::ScaleDpi,
+    IRect,
+};
+use skia_safe::{paint::Paint, Font, Point, Rect};
+use std::{cell::OnceCell, ops::Range, time::Duration};
+
+const CARET_TIMER: u32 = 1;
+const CARET_BLINK: Duration = Duration::from_millis(530);
+const MAX_UNDO: usize = 1000;
+
+/// A place in the text, `col` counts chars.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default, Debug)]
+pub struct TextPos {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl TextPos {
+    pub fn new(line: usize, col: usize) -> Self {
+        Self { line, col }
+    }
+
+    // where `s` ends when it is inserted here
+    fn after(self, s: &str) -> Self {
+        match s.rfind('\n') {
+            Some(i) => Self::new(
+                self.line + s.matches('\n').count(),
+                s[i + 1..].chars().count(),
+            ),
+            None => Self::new(self.line, self.col + s.chars().count()),
+        }
+    }
+}
+
+struct Line {
+    text: String,
+    // x of every char boundary, from 0, in pixels
+    offsets: Vec<f32>,
+    // the char every visual row starts at, the first one is 0
+    rows: Vec<usize>,
+}
+
+impl Line {
+    fn new(text: String, font: &Font, wrap_width: Option<f32>) -> Self {
+        let mut line = Self {
+            text,
+            offsets: Vec::new(),
+            rows: Vec::new(),
+        };
+        line.layout(font, wrap_width);
+        line
+    }
+
+    fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    fn byte_index(&self, col: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(col)
+            .map(|(i, _)| i)
+            .unwrap_or(self.text.len())
+    }
+
+    fn row_range(&self, k: usize) -> Range<usize> {
+        self.rows[k]..self.rows.get(k + 1).copied().unwrap_or(self.len())
+    }
+
+    // the row showing the caret at `col`, a wrap point belongs to the next row
+    fn row_of(&self, col: usize) -> usize {
+        self.rows.partition_point(|s| *s <= col) - 1
+    }
+
+    fn layout(&mut self, font: &Font, wrap_width: Option<f32>) {
+        self.offsets = char_offsets(font, &self.text);
+
+        let chars = self.text.chars().collect::<Vec<_>>();
+        self.rows = match wrap_width {
+            Some(w) => wrap_rows(&chars, &self.offsets, w, EWrapMode::Word),
+            None => vec![0],
+        };
+    }
+}
+
+struct EditRecord {
+    start: TextPos,
+    removed: String,
+    inserted: String,
+    // (anchor, caret) before the edit
+    before: (TextPos, TextPos),
+}
+
+/// Multi line text editor with wrapping, scrolling and undo / redo.
+///
+/// The text is kept per line, an edit only lays out the lines it touches and
+/// only the visible rows are drawn, so long documents stay cheap.
+/// Editing posts `EUIEvent::Change`.
+pub struct TextArea {
+    pub ctrl: BaseCtrl<EditStyle>,
+
+    lines: Vec<Line>,
+    // the first visual row of every line, plus the total row count at the end
+    row_starts: Vec<usize>,
+    // joined lines for `get_text`, dropped on every edit
+    text_cache: OnceCell<String>,
+
+    placeholder: String,
+    wrap: bool,
+    read_only: bool,
+
+    caret: TextPos,
+    anchor: TextPos,
+    // x kept while moving up and down through shorter rows
+    preferred_x: Option<f32>,
+    caret_visible: bool,
+    selecting: bool,
+
+    undo: Vec<EditRecord>,
+    redo: Vec<EditRecord>,
+    // typing more chars extends the last undo record
+    merge_typing: bool,
+
+    font: Font,
+    paint: Paint,
+    line_height: f32,
+    scroll_x: f32,
+    scroll_y: f32,
+    wrap_width: Option<f32>,
+
+    dpi_cache: ScaleDpi,
+}
+
+impl TextArea {
+    pub fn new(font_size: i32) -> Self {
+        let mut ctrl = BaseCtrl::<EditStyle>::default();
+        ctrl.inner.focusable = true;
+        ctrl.styles.default.font.size = font_size;
+
+        let font = Font::default();
+        Self {
+            ctrl,
+
+            lines: vec![Line::new(String::new(), &font, None)],
+            row_starts: vec![0, 1],
+            text_cache: OnceCell::new(),
+
+            placeholder: String::new(),
+            wrap: true,
+            read_only: false,
+
+            caret: TextPos::default(),
+            anchor: TextPos::default(),
+            preferred_x: None,
+            caret_visible: false,
+            selecting: false,
+
+            undo: Vec::new(),
+            redo: Vec::new(),
+            merge_typing: false,
+
+            font,
+            paint: Paint::default(),
+            line_height: 0.,
+            scroll_x: 0.,
+            scroll_y: 0.,
+            wrap_width: None,
+
+            dpi_cache: ScaleDpi::default(),
+        }
+    }
+
+    /// Replaces the whole text and clears the undo history, no `Change` is posted.
+    pub fn set_text(&mut self, t: &str) {
+        let t = t.replace('\r', "");
+        self.lines = t
+            .split('\n')
+            .map(|l| Line::new(l.to_string(), &self.font, self.wrap_width))
+            .collect();
+        self.rebuild_rows(0);
+        self.text_cache = OnceCell::new();
+
+        self.undo.clear();
+        self.redo.clear();
+        self.merge_typing = false;
+        self.caret = TextPos::default();
+        self.anchor = self.caret;
+        self.scroll_x = 0.;
+        self.scroll_y = 0.;
+    }
+
+    /// Adds `t` at the end without an undo record, the view follows
+    /// if it was scrolled to the bottom. Meant for logs.
+    pub fn append(&mut self, t: &str) {
+        let at_bottom = self.scroll_y >= self.max_scroll_y() - 1.;
+        let end = self.end_pos();
+        self.replace_range(end, end, &t.replace('\r', ""));
+        if at_bottom {
+            self.scroll_y = self.max_scroll_y();
+        }
+    }
+
+    pub fn set_placeholder(&mut self, t: &str) {
+        self.placeholder = t.to_string();
+    }
+
+    /// without wrapping long lines scroll horizontally
+    pub fn set_wrap(&mut self, v: bool) {
+        self.wrap = v;
+        self.wrap_width = v.then(|| self.text_rc().width() as f32);
+        self.relayout();
+    }
+
+    /// the text can still be selected and copied
+    pub fn set_read_only(&mut self, v: bool) {
+        self.read_only = v;
+    }
+
+    pub fn get_line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn get_line(&self, i: usize) -> Option<&str> {
+        self.lines.get(i).map(|l| l.text.as_str())
+    }
+
+    /// rows on screen, a wrapped line has more than one
+    pub fn get_row_count(&self) -> usize {
+        *self.row_starts.last().unwrap()
+    }
+
+    pub fn get_caret(&self) -> TextPos {
+        self.caret
+    }
+
+    pub fn get_selection(&self) -> Range<TextPos> {
+        self.caret.min(self.anchor)..self.caret.max(self.anchor)
+    }
+
+    /// positions past the end are clamped
+    pub fn set_selection(&mut self, anchor: TextPos, caret: TextPos) {
+        self.anchor = self.clamp(anchor);
+        self.caret = self.clamp(caret);
+        self.preferred_x = None;
+        self.merge_typing = false;
+        self.scroll_to_caret();
+    }
+
+    pub fn get_selected_text(&self) -> String {
+        let sel = self.get_selection();
+        self.text_range(sel.start, sel.end)
+    }
+
+    /// pixels scrolled from the top
+    pub fn get_scroll_y(&self) -> f32 {
+        self.scroll_y
+    }
+
+    pub fn set_scroll_y(&mut self, y: f32) {
+        self.scroll_y = y.clamp(0., self.max_scroll_y());
+    }
+
+    pub fn is_caret_visible(&self) -> bool {
+        self.caret_visible
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn undo(&mut self) -> bool {
+        let r = match self.undo.pop() {
+            Some(r) => r,
+            None => return false,
+        };
+        let end = r.start.after(&r.inserted);
+        self.replace_range(r.start, end, &r.removed);
+        (self.anchor, self.caret) = r.before;
+        self.after_history(r, true);
+        true
+    }
+
+    pub fn redo(&mut self) -> bool {
+        let r = match self.redo.pop() {
+            Some(r) => r,
+            None => return false,
+        };
+        let end = r.start.after(&r.removed);
+        let caret = self.replace_range(r.start, end, &r.inserted);
+        self.caret = caret;
+        self.anchor = caret;
+        self.after_history(r, false);
+        true
+    }
+
+    fn after_history(&mut self, r: EditRecord, undone: bool) {
+        if undone {
+            self.redo.push(r);
+        } else {
+            self.undo.push(r);
+        }
+        self.merge_typing = false;
+        self.preferred_x = None;
+        self.scroll_to_caret();
+    }
+
+    fn end_pos(&self) -> TextPos {
+        let line = self.lines.len() - 1;
+        TextPos::new(line, self.lines[line].len())
+    }
+
+    fn clamp(&self, p: TextPos) -> TextPos {
+        if p.line >= self.lines.len() {
+            return self.end_pos();
+        }
+        TextPos::new(p.line, p.col.min(self.lines[p.line].len()))
+    }
+
+    fn text_range(&self, start: TextPos, end: TextPos) -> String {
+        if start.line == end.line {
+            let l = &self.lines[start.line];
+            return l.text[l.byte_index(start.col)..l.byte_index(end.col)].to_string();
+        }
+
+        let first = &self.lines[start.line];
+        let mut s = first.text[first.byte_index(start.col)..].to_string();
+        for l in &self.lines[start.line + 1..end.line] {
+            s.push('\n');
+            s.push_str(&l.text);
+        }
+        let last = &self.lines[end.line];
+        s.push('\n');
+        s.push_str(&last.text[..last.byte_index(end.col)]);
+        s
+    }
+
+    // the only place the text changes, returns the end of the inserted text
+    fn replace_range(&mut self, start: TextPos, end: TextPos, text: &str) -> TextPos {
+        let head = {
+            let l = &self.lines[start.line];
+            &l.text[..l.byte_index(start.col)]
+        };
+        let tail = {
+            let l = &self.lines[end.line];
+            &l.text[l.byte_index(end.col)..]
+        };
+        let joined = format!("{}{}{}", head, text, tail);
+
+        let new_lines = joined
+            .split('\n')
+            .map(|l| Line::new(l.to_string(), &self.font, self.wrap_width))
+            .collect::<Vec<_>>();
+        self.lines.splice(start.line..=end.line, new_lines);
+        self.rebuild_rows(start.line);
+        self.text_cache = OnceCell::new();
+
+        start.after(text)
+    }
+
+    // replaces the selection and records it for undo
+    fn edit(&mut self, text: &str, typing: bool) -> bool {
+        if self.read_only {
+            return false;
+        }
+        let sel = self.get_selection();
+        if sel.is_empty() && text.is_empty() {
+            return false;
+        }
+
+        let removed = self.text_range(sel.start, sel.end);
+        let before = (self.anchor, self.caret);
+        let caret = self.replace_range(sel.start, sel.end, text);
+
+        let merge = typing && self.merge_typing && sel.is_empty();
+        match self.undo.last_mut() {
+            Some(last) if merge && last.start.after(&last.inserted) == sel.start => {
+                last.inserted.push_str(text);
+            }
+            _ => {
+                self.undo.push(EditRecord {
+                    start: sel.start,
+                    removed,
+                    inserted: text.to_string(),
+                    before,
+                });
+                if self.undo.len() > MAX_UNDO {
+                    self.undo.remove(0);
+                }
+            }
+        }
+        self.redo.clear();
+        // a space ends the word, the next one is undone separately
+        self.merge_typing = typing && !text.chars().all(char::is_whitespace);
+
+        self.caret = caret;
+        self.anchor = caret;
+        self.preferred_x = None;
+        self.scroll_to_caret();
+        true
+    }
+
+    // deletes the selection or from the caret to `to`
+    fn delete_to(&mut self, to: TextPos) -> bool {
+        if self.read_only {
+            return false;
+        }
+        if self.anchor == self.caret {
+            self.anchor = to;
+        }
+        self.edit("", false)
+    }
+
+    fn rebuild_rows(&mut self, from: usize) {
+        self.row_starts.truncate(from + 1);
+        let mut row = self.row_starts[from];
+        for l in &self.lines[from..] {
+            row += l.rows.len();
+            self.row_starts.push(row);
+        }
+    }
+
+    fn relayout(&mut self) {
+        for l in self.lines.iter_mut() {
+            l.layout(&self.font, self.wrap_width);
+        }
+        self.rebuild_rows(0);
+        self.scroll_to_caret();
+    }
+
+    fn update_ctx(&mut self) {
+        self.font = self.ctrl.get_cur_style().font.make_font(&self.dpi_cache);
+        self.paint.set_anti_alias(true);
+
+        let (_, m) = self.font.metrics();
+        self.line_height = (m.descent - m.ascent + m.leading).ceil();
+    }
+
+    fn text_rc(&self) -> IRect {
+        let mut rc = self.ctrl.inner.real_rc;
+        if let Some(p) = self.ctrl.inner.padding {
+            let p = self.dpi_cache.scale(p);
+            rc = IRect::new(
+                rc.left + p.left,
+                rc.top + p.top,
+                rc.right - p.right,
+                rc.bottom - p.bottom,
+            );
+        }
+        rc
+    }
+
+    fn max_scroll_y(&self) -> f32 {
+        let content = self.get_row_count() as f32 * self.line_height;
+        (content - self.text_rc().height() as f32).max(0.)
+    }
+
+    // the visual row and its line
+    fn row_to_line(&self, row: usize) -> (usize, usize) {
+        let row = row.min(self.get_row_count() - 1);
+        let line = self.row_starts.partition_point(|s| *s <= row) - 1;
+        (line, row - self.row_starts[line])
+    }
+
+    fn row_of(&self, p: TextPos) -> usize {
+        self.row_starts[p.line] + self.lines[p.line].row_of(p.col)
+    }
+
+    // x of `p` from the start of its row
+    fn x_in_row(&self, p: TextPos) -> f32 {
+        let l = &self.lines[p.line];
+        let start = l.rows[l.row_of(p.col)];
+        l.offsets[p.col] - l.offsets[start]
+    }
+
+    // the col in row `k` of `line` closest to `x`, the wrap point at its end
+    // belongs to the next row so it is never picked
+    fn col_at_x(&self, line: usize, k: usize, x: f32) -> usize {
+        let l = &self.lines[line];
+        let range = l.row_range(k);
+        let base = l.offsets[range.start];
+        let last = if k + 1 < l.rows.len() {
+            range.end - 1
+        } else {
+            range.end
+        };
+
+        (range.start..=last.max(range.start))
+            .min_by(|a, b| {
+                let da = (l.offsets[*a] - base - x).abs();
+                let db = (l.offsets[*b] - base - x).abs();
+                da.total_cmp(&db)
+            })
+            .unwrap_or(range.start)
+    }
+
+    // `pos` is physical like `real_rc`
+    fn pos_at(&self, x: i32, y: i32) -> TextPos {
+        let rc = self.text_rc();
+        let y = (y - rc.top) as f32 + self.scroll_y;
+        let row = if y < 0. {
+            0
+        } else {
+            (y / self.line_height.max(1.)) as usize
+        };
+        let (line, k) = self.row_to_line(row);
+        let x = (x - rc.left) as f32 + self.scroll_x;
+        TextPos::new(line, self.col_at_x(line, k, x))
+    }
+
+    fn scroll_to_caret(&mut self) {
+        let rc = self.text_rc();
+        let top = self.row_of(self.caret) as f32 * self.line_height;
+        if top < self.scroll_y {
+            self.scroll_y = top;
+        }
+        if top + self.line_height > self.scroll_y + rc.height() as f32 {
+            self.scroll_y = top + self.line_height - rc.height() as f32;
+        }
+        self.scroll_y = self.scroll_y.clamp(0., self.max_scroll_y());
+
+        if self.wrap {
+            self.scroll_x = 0.;
+            return;
+        }
+        let x = self.x_in_row(self.caret);
+        let caret_w = self.dpi_cache.scale(1) as f32;
+        if x + caret_w - self.scroll_x > rc.width() as f32 {
+            self.scroll_x = x + caret_w - rc.width() as f32;
+        }
+        if x < self.scroll_x {
+            self.scroll_x = x;
+        }
+    }
+
+    fn move_caret(&mut self, p: TextPos, extend: bool) {
+        self.caret = p;
+        if !extend {
+            self.anchor = p;
+        }
+        self.merge_typing = false;
+        self.scroll_to_caret();
+    }
+
+    fn left_of(&self, p: TextPos, word: bool) -> TextPos {
+        if p.col == 0 {
+            return match p.line {
+                0 => p,
+                line => TextPos::new(line - 1, self.lines[line - 1].len()),
+            };
+        }
+        if word {
+            let chars = self.lines[p.line].text.chars().collect::<Vec<_>>();
+            return TextPos::new(p.line, word_left(&chars, p.col));
+        }
+        TextPos::new(p.line, p.col - 1)
+    }
+
+    fn right_of(&self, p: TextPos, word: bool) -> TextPos {
+        let len = self.lines[p.line].len();
+        if p.col >= len {
+            if p.line + 1 < self.lines.len() {
+                return TextPos::new(p.line + 1, 0);
+            }
+            return p;
+        }
+        if word {
+            let chars = self.lines[p.line].text.chars().collect::<Vec<_>>();
+            return TextPos::new(p.line, word_right(&chars, p.col));
+        }
+        TextPos::new(p.line, p.col + 1)
+    }
+
+    // `rows` up (negative) or down, keeping the x the move started at
+    fn vertical(&mut self, rows: isize) -> TextPos {
+        let x = match self.preferred_x {
+            Some(x) => x,
+            None => self.x_in_row(self.caret),
+        };
+        let row = self.row_of(self.caret) as isize + rows;
+        let p = if row < 0 {
+            TextPos::default()
+        } else if row as usize >= self.get_row_count() {
+            self.end_pos()
+        } else {
+            let (line, k) = self.row_to_line(row as usize);
+            TextPos::new(line, self.col_at_x(line, k, x))
+        };
+        self.preferred_x = Some(x);
+        p
+    }
+
+    fn copy(&self) {
+        let sel = self.get_selection();
+        if !sel.is_empty() {
+            clipboard::set_text(&self.get_selected_text());
+        }
+    }
+
+    fn restart_blink(&mut self, event: &mut UIEvent) {
+        self.caret_visible = true;
+        event.set_timer(CARET_TIMER, CARET_BLINK);
+        event.request_redraw();
+    }
+
+    // returns (the key was used, the text changed)
+    fn on_key(&mut self, input: &KeyInput) -> (bool, bool) {
+        let m = input.modifiers;
+        let shift = m.shift;
+        let (word, line) = if cfg!(target_os = "macos") {
+            (m.alt, m.logo)
+        } else {
+            (m.ctrl, false)
+        };
+        let sel = self.get_selection();
+        let page = (self.text_rc().height() as f32 / self.line_height.max(1.)) as isize;
+        let row = {
+            let l = &self.lines[self.caret.line];
+            l.row_range(l.row_of(self.caret.col))
+        };
+
+        // only up and down keep the x they started at
+        if !matches!(
+            input.key,
+            EKey::Up | EKey::Down | EKey::PageUp | EKey::PageDown
+        ) {
+            self.preferred_x = None;
+        }
+
+        let changed = match input.key {
+            EKey::Left => {
+                let p = if line {
+                    TextPos::new(self.caret.line, row.start)
+                } else if !shift && !word && !sel.is_empty() {
+                    sel.start
+                } else {
+                    self.left_of(self.caret, word)
+                };
+                self.move_caret(p, shift);
+                false
+            }
+            EKey::Right => {
+                let p = if line {
+                    TextPos::new(self.caret.line, row.end)
+                } else if !shift && !word && !sel.is_empty() {
+                    sel.end
+                } else {
+                    self.right_of(self.caret, word)
+                };
+                self.move_caret(p, shift);
+                false
+            }
+            EKey::Up | EKey::Down | EKey::PageUp | EKey::PageDown => {
+                let rows = match input.key {
+                    EKey::Up => -1,
+                    EKey::Down => 1,
+                    EKey::PageUp => -page.max(1),
+                    _ => page.max(1),
+                };
+                let p = if line && rows < 0 {
+                    TextPos::default()
+                } else if line {
+                    self.end_pos()
+                } else {
+                    self.vertical(rows)
+                };
+                self.move_caret(p, shift);
+                false
+            }
+            EKey::Home => {
+                let p = if m.command() {
+                    TextPos::default()
+                } else {
+                    TextPos::new(self.caret.line, row.start)
+                };
+                self.move_caret(p, shift);
+                false
+            }
+            EKey::End => {
+                let l = &self.lines[self.caret.line];
+                let p = if m.command() {
+                    self.end_pos()
+                } else if row.end < l.len() {
+                    // before the space the row wraps at
+                    TextPos::new(self.caret.line, (row.end - 1).max(row.start))
+                } else {
+                    TextPos::new(self.caret.line, row.end)
+                };
+                self.move_caret(p, shift);
+                false
+            }
+            EKey::Enter => self.edit("\n", false),
+            EKey::Backspace => {
+                let to = if line {
+                    TextPos::new(self.caret.line, row.start)
+                } else {
+                    self.left_of(self.caret, word)
+                };
+                self.delete_to(to)
+            }
+            EKey::Delete => {
+                let to = if line {
+                    TextPos::new(self.caret.line, row.end)
+                } else {
+                    self.right_of(self.caret, word)
+                };
+                self.delete_to(to)
+            }
+            EKey::Char('a') if m.command() => {
+                self.set_selection(TextPos::default(), self.end_pos());
+                false
+            }
+            EKey::Char('c') if m.command() => {
+                self.copy();
+                false
+            }
+            EKey::Char('x') if m.command() => {
+                self.copy();
+                self.edit("", false)
+            }
+            EKey::Char('v') if m.command() => match clipboard::get_text() {
+                Some(t) => self.edit(&t.replace('\r', ""), false),
+                None => false,
+            },
+            EKey::Char('z') if m.command() && shift => self.redo(),
+            EKey::Char('z') if m.command() => self.undo(),
+            EKey::Char('y') if m.command() => self.redo(),
+            _ => return (false, false),
+        };
+        (true, changed)
+    }
+}
+
+impl TCtrl for TextArea {
+    fn get_inner(&self) -> &TCtrlInner {
+        &self.ctrl.inner
+    }
+    fn get_mut_inner(&mut self) -> &mut TCtrlInner {
+        &mut self.ctrl.inner
+    }
+    fn get_style(&self, status: EUIStatus) -> Option<&StyleInner> {
+        self.ctrl.styles.get_inner_style(status)
+    }
+    fn get_mut_style(&mut self, status: EUIStatus) -> Option<&mut StyleInner> {
+        self.ctrl.styles.get_mut_inner_style(status)
+    }
+    fn type_name(&self) -> &str {
+        "TextArea"
+    }
+    fn type_(&self) -> ECtrlType {
+        ECtrlType::TextArea
+    }
+    fn get_status(&self) -> ECtrlStatus {
+        self.ctrl.status
+    }
+    fn set_status(&mut self, status: ECtrlStatus) {
+        self.ctrl.status = status;
+    }
+    fn get_text(&self) -> Option<&str> {
+        let text = self.text_cache.get_or_init(|| {
+            let lines = self.lines.iter().map(|l| l.text.as_str());
+            lines.collect::<Vec<_>>().join("\n")
+        });
+        Some(text)
+    }
+
+    fn update(&mut self, _dpi: &ScaleDpi) {
+        let width = self.wrap.then(|| self.text_rc().width() as f32);
+        if width != self.wrap_width {
+            self.wrap_width = width;
+            self.relayout();
+        }
+        self.scroll_y = self.scroll_y.clamp(0., self.max_scroll_y());
+    }
+
+    fn update_dpi(&mut self, dpi: &ScaleDpi) {
+        self.dpi_cache = dpi.clone();
+        self.update_ctx();
+        self.relayout();
+    }
+
+    fn on_event(&mut self, event: &mut UIEvent) {
+        if !event.is_target() || self.ctrl.status == ECtrlStatus::Disable {
+            return;
+        }
+
+        match event.kind.clone() {
+            EUIEvent::FocusIn => self.restart_blink(event),
+            EUIEvent::FocusOut => {
+                self.caret_visible = false;
+                self.selecting = false;
+                event.request_redraw();
+            }
+            EUIEvent::Timer(CARET_TIMER) => {
+                if self.ctrl.inner.is_focused() {
+                    self.caret_visible = !self.caret_visible;
+                    event.set_timer(CARET_TIMER, CARET_BLINK);
+                    event.request_redraw();
+                }
+            }
+            EUIEvent::MouseDown {
+                pos,
+                button: EMouseButton::Left,
+            } => {
+                let p = self.pos_at(pos.x, pos.y);
+                self.preferred_x = None;
+                self.move_caret(p, event.modifiers.shift);
+                self.selecting = true;
+                self.restart_blink(event);
+            }
+            EUIEvent::MouseMove { pos } if self.selecting => {
+                let p = self.pos_at(pos.x, pos.y);
+                if p != self.caret {
+                    self.move_caret(p, true);
+                    self.restart_blink(event);
+                }
+            }
+            EUIEvent::MouseUp {
+                button: EMouseButton::Left,
+                ..
+            } => self.selecting = false,
+            EUIEvent::Wheel { delta, .. } => {
+                // parents only scroll once the end is reached
+                let y = (self.scroll_y - delta.y).clamp(0., self.max_scroll_y());
+                if y != self.scroll_y {
+                    self.scroll_y = y;
+                    event.request_redraw();
+                    event.stop_propagation();
+                }
+            }
+            EUIEvent::KeyDown(input) => {
+                let (used, changed) = self.on_key(&input);
+                if used {
+                    if changed {
+                        event.post(EUIEvent::Change);
+                    }
+                    self.restart_blink(event);
+                    event.stop_propagation();
+                }
+            }
+            EUIEvent::Char(c) => {
+                let m = event.modifiers;
+                if m.logo || (m.ctrl && !m.alt) {
+                    return;
+                }
+                if self.edit(&c.to_string(), true) {
+                    event.post(EUIEvent::Change);
+                    self.restart_blink(event);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn render(&self, canvas: &mut skia_safe::Canvas, dpi: &ScaleDpi) {
+        let style = self.ctrl.get_cur_style();
+        self.ctrl.inner.render(canvas, dpi, style.get_inner());
+        if self.ctrl.inner.empty_paint() || self.line_height <= 0. {
+            return;
+        }
+
+        let rc = self.text_rc();
+        let (_, metrics) = self.font.metrics();
+        let left = rc.left as f32 - self.scroll_x;
+        let focused = self.ctrl.inner.is_focused();
+        let sel = self.get_selection();
+        // the line break of a selected line is shown as a bit of space
+        let eol_w = self.line_height / 3.;
+
+        canvas.save();
+        canvas.clip_rect(Rect::from(rc), None, None);
+        let mut paint = self.paint.clone();
+
+        let row_top = |row: usize| rc.top as f32 + row as f32 * self.line_height - self.scroll_y;
+        let baseline = |row: usize| row_top(row) - metrics.ascent + metrics.leading / 2.;
+
+        if self.get_row_count() == 1 && self.lines[0].text.is_empty() {
+            paint.set_color(style.placeholder_color);
+            font::draw_text(
+                canvas,
+                &self.placeholder,
+                Point::new(left, baseline(0)),
+                &self.font,
+                &paint,
+            );
+        }
+
+        let first = (self.scroll_y / self.line_height) as usize;
+        let last = ((self.scroll_y + rc.height() as f32) / self.line_height).ceil() as usize;
+        let last = last.min(self.get_row_count());
+
+        let (mut line, mut k) = self.row_to_line(first);
+        for row in first..last {
+            let l = &self.lines[line];
+            let range = l.row_range(k);
+            let x0 = left - l.offsets[range.start];
+            let last_row = k + 1 == l.rows.len();
+
+            // selected part of this row
+            if focused && !sel.is_empty() && sel.start.line <= line && line <= sel.end.line {
+                let s = if sel.start.line == line {
+                    sel.start.col.max(range.start)
+                } else {
+                    range.start
+                };
+                let (e, eol) = if sel.end.line == line {
+                    (sel.end.col.min(range.end), false)
+                } else {
+                    (range.end, last_row)
+                };
+                if s < e || (eol && s <= e) {
+                    let right = x0 + l.offsets[e] + if eol { eol_w } else { 0. };
+                    paint.set_color(style.selection_color);
+                    canvas.draw_rect(
+                        Rect::new(
+                            x0 + l.offsets[s],
+                            row_top(row),
+                            right,
+                            row_top(row) + self.line_height,
+                        ),
+                        &paint,
+                    );
+                }
+            }
+
+            let text = &l.text[l.byte_index(range.start)..l.byte_index(range.end)];
+            paint.set_color(style.font_color);
+            font::draw_text(
+                canvas,
+                text,
+                Point::new(left, baseline(row)),
+                &self.font,
+                &paint,
+            );
+
+            if last_row {
+                line += 1;
+                k = 0;
+            } else {
+                k += 1;
+            }
+        }
+
+        if focused && self.caret_visible {
+            let row = self.row_of(self.caret);
+            if (first..last).contains(&row) {
+                let x = left + self.x_in_row(self.caret);
+                paint.set_color(style.caret_color);
+                canvas.draw_rect(
+                    Rect::new(
+                        x,
+                        row_top(row),
+                        x + dpi.scale(1) as f32,
+                        row_top(row) + self.line_height,
+                    ),
+                    &paint,
+                );
+            }
+        }
+
+        canvas.restore();
+    }
+}
+
+#[test]
+fn test_edit_and_undo() {
+    let mut t = TextArea::new(12);
+    t.set_text("ab\ncd");
+
+    t.set_selection(TextPos::new(0, 1), TextPos::new(1, 1));
+    assert_eq!(t.get_selected_text(), "b\nc");
+    assert!(t.edit("X\nY\nZ", false));
+    assert_eq!(t.get_text(), Some("aX\nY\nZd"));
+    assert_eq!(t.get_caret(), TextPos::new(2, 1));
+    assert_eq!(t.get_line_count(), 3);
+
+    for c in ["q", "w", " ", "e"] {
+        t.edit(c, true);
+    }
+    assert_eq!(t.get_line(2), Some("Zqw ed"));
+
+    // "e", then "qw " typed together
+    assert!(t.undo());
+    assert_eq!(t.get_line(2), Some("Zqw d"));
+    assert!(t.undo());
+    assert_eq!(t.get_text(), Some("aX\nY\nZd"));
+    assert!(t.undo());
+    assert_eq!(t.get_text(), Some("ab\ncd"));
+    assert_eq!(t.get_selection(), TextPos::new(0, 1)..TextPos::new(1, 1));
+    assert!(!t.undo());
+
+    assert!(t.redo());
+    assert_eq!(t.get_text(), Some("aX\nY\nZd"));
+    t.edit("!", false);
+    assert!(!t.can_redo());
+}
+
+#[test]
+fn test_wrap() {
+    let font = Font::default();
+    let w = font.measure_str("aaaa ", None).0;
+
+    let l = Line::new("aaaa bbbb cccccccccc".to_string(), &font, Some(w * 1.5));
+    assert_eq!(l.rows[..3], [0, 5, 10]);
+    // the long word is cut inside
+    assert!(l.rows.len() > 3);
+    assert_eq!(l.row_of(5), 1);
+    assert_eq!(l.row_of(4), 0);
+
+    let l = Line::new("aaaa bbbb".to_string(), &font, None);
+    assert_eq!(l.rows, [0]);
+}
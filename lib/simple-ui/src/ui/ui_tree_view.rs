@@ -0,0 +1,652 @@
+use crate::{
+    input::{EKey, EMouseButton},
+    ui::{
+        styles::{EUIStatus, StyleInner},
+        ui_ctrl::{BaseCtrl, CtrlStyle, TCtrlInner},
+        Container, ECtrlStatus, ECtrlType, EEventPhase, EUIEvent, LayoutHorizontal, ListView,
+        TCtrl, TListSource, UIEvent,
+    },
+    utils::ScaleDpi,
+    Color, IPoint, IRect,
+};
+use as_any::Downcast;
+use skia_safe::{Canvas, Paint, Path, Point};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+    time::Duration,
+};
+
+const SEARCH_TIMER: u32 = 1;
+// typing after this long starts a new search
+const SEARCH_RESET: Duration = Duration::from_millis(1000);
+
+/// The nodes of a `TreeView`, named by ids the source picks. Children are
+/// asked for the first time their parent is expanded.
+pub trait TTreeSource {
+    /// children of `parent`, the top level nodes for `None`
+    fn get_children(&mut self, parent: Option<u64>) -> Vec<u64>;
+    /// whether to draw an arrow before the children are loaded
+    fn has_children(&self, node: u64) -> bool;
+    /// matched by type-ahead search
+    fn get_text(&self, node: u64) -> String;
+    /// logical pixels, the same for every row
+    fn get_row_height(&self) -> i32 {
+        24
+    }
+    /// an empty row, `update_row` fills it
+    fn create_row(&mut self) -> Box<dyn TCtrl>;
+    /// shows `node` in a new or recycled row
+    fn update_row(&mut self, row: &mut dyn TCtrl, node: u64);
+}
+
+#[derive(Clone, Copy)]
+struct TreeLine {
+    node: u64,
+    depth: usize,
+    expandable: bool,
+}
+
+struct TreeState {
+    source: Box<dyn TTreeSource>,
+    // loaded children, the top level under `None`
+    children: HashMap<Option<u64>, Vec<u64>>,
+    expanded: HashSet<u64>,
+    // the nodes shown, in order
+    lines: Vec<TreeLine>,
+
+    indent: i32,
+    guide_color: Option<Color>,
+    arrow_color: Color,
+}
+
+impl TreeState {
+    fn load(&mut self, parent: Option<u64>) -> Vec<u64> {
+        if !self.children.contains_key(&parent) {
+            let children = self.source.get_children(parent);
+            self.children.insert(parent, children);
+        }
+        self.children[&parent].clone()
+    }
+
+    fn is_expandable(&self, node: u64) -> bool {
+        match self.children.get(&Some(node)) {
+            Some(c) => !c.is_empty(),
+            None => self.source.has_children(node),
+        }
+    }
+
+    // the children of `parent` and their expanded descendants
+    fn collect(&mut self, parent: Option<u64>, depth: usize, out: &mut Vec<TreeLine>) {
+        for node in self.load(parent) {
+            out.push(TreeLine {
+                node,
+                depth,
+                expandable: self.is_expandable(node),
+            });
+            if self.expanded.contains(&node) {
+                self.collect(Some(node), depth + 1, out);
+            }
+        }
+    }
+
+    fn find(&self, node: u64) -> Option<usize> {
+        self.lines.iter().position(|l| l.node == node)
+    }
+}
+
+// the lines of the tree as the items of the `ListView`
+struct TreeItems(Rc<RefCell<TreeState>>);
+
+impl TListSource for TreeItems {
+    fn get_count(&self) -> usize {
+        self.0.borrow().lines.len()
+    }
+    fn get_row_height(&self) -> i32 {
+        self.0.borrow().source.get_row_height()
+    }
+    fn create_row(&mut self) -> Box<dyn TCtrl> {
+        let row = self.0.borrow_mut().source.create_row();
+        Box::new(TreeRow::new(row))
+    }
+    fn update_row(&mut self, row: &mut dyn TCtrl, index: usize) {
+        let state = &mut *self.0.borrow_mut();
+        let row = match row.downcast_mut::<TreeRow>() {
+            Some(r) => r,
+            None => return,
+        };
+        let line = state.lines[index];
+        row.depth = line.depth;
+        row.expandable = line.expandable;
+        row.expanded = state.expanded.contains(&line.node);
+        row.indent = state.indent;
+        row.guide_color = state.guide_color;
+        row.arrow_color = state.arrow_color;
+
+        if let Some(c) = row.cell.get_mut_children().first_mut() {
+            state.source.update_row(c.as_mut(), line.node);
+        }
+    }
+}
+
+// a row of the source moved right by the depth, with the guides and the arrow
+struct TreeRow {
+    ctrl: BaseCtrl<CtrlStyle>,
+    cell: Container,
+
+    depth: usize,
+    expandable: bool,
+    expanded: bool,
+    indent: i32,
+    guide_color: Option<Color>,
+    arrow_color: Color,
+}
+
+impl TreeRow {
+    fn new(row: Box<dyn TCtrl>) -> Self {
+        let mut cell = Container::new(LayoutHorizontal::new());
+        cell.append_child(row);
+        Self {
+            ctrl: BaseCtrl::default(),
+            cell,
+
+            depth: 0,
+            expandable: false,
+            expanded: false,
+            indent: 0,
+            guide_color: None,
+            arrow_color: Color::BLACK,
+        }
+    }
+}
+
+impl TCtrl for TreeRow {
+    fn get_inner(&self) -> &TCtrlInner {
+        &self.ctrl.inner
+    }
+    fn get_mut_inner(&mut self) -> &mut TCtrlInner {
+        &mut self.ctrl.inner
+    }
+    fn get_style(&self, status: EUIStatus) -> Option<&StyleInner> {
+        self.ctrl.styles.get_inner_style(status)
+    }
+    fn get_mut_style(&mut self, status: EUIStatus) -> Option<&mut StyleInner> {
+        self.ctrl.styles.get_mut_inner_style(status)
+    }
+    fn type_name(&self) -> &str {
+        "TreeRow"
+    }
+    fn type_(&self) -> ECtrlType {
+        ECtrlType::Unknow
+    }
+    fn as_container(&self) -> Option<&Container> {
+        Some(&self.cell)
+    }
+    fn as_mut_container(&mut self) -> Option<&mut Container> {
+        Some(&mut self.cell)
+    }
+    fn get_status(&self) -> ECtrlStatus {
+        self.ctrl.status
+    }
+    fn set_status(&mut self, status: ECtrlStatus) {
+        self.ctrl.status = status;
+    }
+
+    fn update(&mut self, dpi: &ScaleDpi) {
+        let rc = self.ctrl.inner.real_rc;
+        let left = rc.left + dpi.scale(self.indent) * (self.depth as i32 + 1);
+        self.cell.ctrl.inner.real_rc = match rc.is_empty() {
+            true => IRect::default(),
+            false => IRect::new(left.min(rc.right), rc.top, rc.right, rc.bottom),
+        };
+        self.cell.update(dpi);
+    }
+
+    fn update_dpi(&mut self, dpi: &ScaleDpi) {
+        self.cell.update_dpi(dpi);
+    }
+
+    fn render(&self, canvas: &mut Canvas, dpi: &ScaleDpi) {
+        if self.ctrl.inner.empty_paint() {
+            return;
+        }
+        let rc = self.ctrl.inner.real_rc;
+        let indent = dpi.scale(self.indent) as f32;
+
+        let mut paint = Paint::default();
+        if let Some(c) = self.guide_color {
+            paint.set_color(c);
+            paint.set_stroke_width(dpi.scale(1) as f32);
+            for level in 0..self.depth {
+                let x = (rc.left as f32 + indent * (level as f32 + 0.5)).floor() + 0.5;
+                canvas.draw_line((x, rc.top as f32), (x, rc.bottom as f32), &paint);
+            }
+        }
+
+        if self.expandable {
+            let cx = rc.left as f32 + indent * (self.depth as f32 + 0.5);
+            let cy = rc.top as f32 + rc.height() as f32 / 2.;
+            let r = indent / 4.;
+            let points = match self.expanded {
+                true => [
+                    (cx - r, cy - r / 2.),
+                    (cx + r, cy - r / 2.),
+                    (cx, cy + r / 2.),
+                ],
+                false => [
+                    (cx - r / 2., cy - r),
+                    (cx + r / 2., cy),
+                    (cx - r / 2., cy + r),
+                ],
+            };
+            let mut path = Path::new();
+            path.move_to(Point::from(points[0]));
+            path.line_to(Point::from(points[1]));
+            path.line_to(Point::from(points[2]));
+            path.close();
+
+            paint.set_color(self.arrow_color);
+            paint.set_anti_alias(true);
+            canvas.draw_path(&path, &paint);
+        }
+
+        self.cell.render(canvas, dpi);
+    }
+}
+
+/// A `ListView` of the expanded nodes of a `TTreeSource`, so large trees
+/// stay as fast as a list. The arrow or a double click expands and collapses
+/// a node; Right expands or goes to the first child, Left collapses or goes
+/// to the parent, typing jumps to the next node starting with the text.
+/// The events are those of the `ListView` and its indexes are rows, see
+/// `get_node_at_row`.
+pub struct TreeView {
+    /// the rows, its `ctrl` is the control of the tree
+    pub list: ListView,
+
+    /// logical pixels a level moves right, set before showing
+    pub indent: i32,
+    /// vertical lines under the parents, `None` hides them
+    pub guide_color: Option<Color>,
+    pub arrow_color: Color,
+
+    state: Rc<RefCell<TreeState>>,
+    search: String,
+    dpi_cache: ScaleDpi,
+}
+
+impl TreeView {
+    pub fn new(source: Box<dyn TTreeSource>) -> Self {
+        let mut state = TreeState {
+            source,
+            children: HashMap::new(),
+            expanded: HashSet::new(),
+            lines: Vec::new(),
+
+            indent: 16,
+            guide_color: Some(Color::from_rgb(0xdd, 0xdd, 0xdd)),
+            arrow_color: Color::from_rgb(0x60, 0x60, 0x60),
+        };
+        let mut lines = Vec::new();
+        state.collect(None, 0, &mut lines);
+        state.lines = lines;
+
+        let (indent, guide_color, arrow_color) =
+            (state.indent, state.guide_color, state.arrow_color);
+        let state = Rc::new(RefCell::new(state));
+        Self {
+            list: ListView::new(Box::new(TreeItems(state.clone()))),
+
+            indent,
+            guide_color,
+            arrow_color,
+
+            state,
+            search: String::new(),
+            dpi_cache: ScaleDpi::default(),
+        }
+    }
+
+    /// Call after the nodes changed, the children are asked for again. The
+    /// expanded nodes still there stay expanded and so does the selection.
+    pub fn reload(&mut self) {
+        let selected = self.get_selected_nodes();
+        let current = self.get_current_node();
+        {
+            let mut state = self.state.borrow_mut();
+            state.children.clear();
+            let mut lines = Vec::new();
+            state.collect(None, 0, &mut lines);
+            state.lines = lines;
+        }
+
+        // the current node goes last
+        let rows = selected
+            .into_iter()
+            .filter(|n| Some(*n) != current)
+            .chain(current)
+            .filter_map(|n| self.get_row_of(n))
+            .collect::<Vec<_>>();
+        self.list.reload();
+        self.list.set_selection(&rows);
+    }
+
+    /// rows shown, the expanded nodes count their visible descendants
+    pub fn get_row_count(&self) -> usize {
+        self.state.borrow().lines.len()
+    }
+    pub fn get_node_at_row(&self, row: usize) -> Option<u64> {
+        self.state.borrow().lines.get(row).map(|l| l.node)
+    }
+    /// `None` while a parent is collapsed
+    pub fn get_row_of(&self, node: u64) -> Option<usize> {
+        self.state.borrow().find(node)
+    }
+    /// how many parents are above `row`, 0 for the top level
+    pub fn get_depth(&self, row: usize) -> Option<usize> {
+        self.state.borrow().lines.get(row).map(|l| l.depth)
+    }
+
+    /// in row order
+    pub fn get_selected_nodes(&self) -> Vec<u64> {
+        let state = self.state.borrow();
+        self.list
+            .get_selection()
+            .into_iter()
+            .filter_map(|i| state.lines.get(i).map(|l| l.node))
+            .collect()
+    }
+    pub fn get_current_node(&self) -> Option<u64> {
+        self.get_node_at_row(self.list.get_current()?)
+    }
+    /// selects `node` alone and scrolls to it, `false` if it is not shown
+    pub fn select_node(&mut self, node: u64) -> bool {
+        match self.get_row_of(node) {
+            Some(row) => {
+                self.list.set_selection(&[row]);
+                self.list.scroll_to_item(row);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_expanded(&self, node: u64) -> bool {
+        self.state.borrow().expanded.contains(&node)
+    }
+
+    /// Shows the children of `node`, loading them the first time. A node
+    /// under a collapsed parent shows them once the parent is expanded.
+    /// Returns whether it was collapsed.
+    pub fn expand(&mut self, node: u64) -> bool {
+        let (row, count) = {
+            let mut state = self.state.borrow_mut();
+            if !state.expanded.insert(node) {
+                return false;
+            }
+            let row = match state.find(node) {
+                Some(r) => r,
+                None => return true,
+            };
+
+            let mut lines = Vec::new();
+            let depth = state.lines[row].depth;
+            state.collect(Some(node), depth + 1, &mut lines);
+            state.lines[row].expandable = state.is_expandable(node);
+            let count = lines.len();
+            state.lines.splice(row + 1..row + 1, lines);
+            (row, count)
+        };
+
+        self.list
+            .remap(|i| Some(if i > row { i + count } else { i }), None);
+        true
+    }
+
+    /// Hides the descendants of `node`, the selection among them is dropped
+    /// and the current row moves up to `node`. Returns whether it was expanded.
+    pub fn collapse(&mut self, node: u64) -> bool {
+        let (row, end) = {
+            let mut state = self.state.borrow_mut();
+            if !state.expanded.remove(&node) {
+                return false;
+            }
+            let row = match state.find(node) {
+                Some(r) => r,
+                None => return true,
+            };
+
+            let depth = state.lines[row].depth;
+            let end = state.lines[row + 1..]
+                .iter()
+                .position(|l| l.depth <= depth)
+                .map_or(state.lines.len(), |p| row + 1 + p);
+            state.lines.drain(row + 1..end);
+            (row, end)
+        };
+
+        let count = end - row - 1;
+        self.list.remap(
+            |i| match i {
+                i if i <= row => Some(i),
+                i if i < end => None,
+                i => Some(i - count),
+            },
+            Some(row),
+        );
+        true
+    }
+
+    pub fn toggle(&mut self, node: u64) -> bool {
+        match self.is_expanded(node) {
+            true => self.collapse(node),
+            false => self.expand(node),
+        }
+    }
+
+    // the arrow of the row under `pos`
+    fn get_arrow_at(&self, pos: IPoint) -> Option<u64> {
+        let row = self.list.get_item_at(pos)?;
+        let line = self.state.borrow().lines[row];
+        let indent = self.dpi_cache.scale(self.indent);
+        let left = self.list.get_item_rc(row).left + indent * line.depth as i32;
+        (line.expandable && pos.x >= left && pos.x < left + indent).then_some(line.node)
+    }
+
+    fn move_to(&mut self, row: usize, event: &mut UIEvent) {
+        if self.list.select(row, false, false, false) {
+            event.post(EUIEvent::SelectionChanged);
+        }
+    }
+
+    // Left and Right, returns whether the key was used
+    fn on_key(&mut self, key: EKey, event: &mut UIEvent) -> bool {
+        let row = match self.list.get_current() {
+            Some(r) => r,
+            None => return false,
+        };
+        let (line, next) = {
+            let state = self.state.borrow();
+            (state.lines[row], state.lines.get(row + 1).copied())
+        };
+        let expanded = self.is_expanded(line.node);
+
+        match key {
+            EKey::Right if line.expandable && !expanded => {
+                self.expand(line.node);
+            }
+            EKey::Right => match next {
+                Some(n) if expanded && n.depth > line.depth => self.move_to(row + 1, event),
+                _ => return false,
+            },
+            EKey::Left if expanded => {
+                self.collapse(line.node);
+            }
+            EKey::Left => {
+                let parent = self.state.borrow().lines[..row]
+                    .iter()
+                    .rposition(|l| l.depth < line.depth);
+                match parent {
+                    Some(p) => self.move_to(p, event),
+                    None => return false,
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    // the next row starting with the typed text, from the current one on
+    fn on_search(&mut self, c: char, event: &mut UIEvent) -> bool {
+        self.search.extend(c.to_lowercase());
+        event.set_timer(SEARCH_TIMER, SEARCH_RESET);
+
+        let count = self.get_row_count();
+        let cur = self.list.get_current().unwrap_or(0);
+        // a new search looks past the current row
+        let start = match self.search.chars().count() {
+            1 => cur + 1,
+            _ => cur,
+        };
+        let found = {
+            let state = self.state.borrow();
+            (0..count).map(|k| (start + k) % count).find(|i| {
+                let text = state.source.get_text(state.lines[*i].node);
+                text.to_lowercase().starts_with(&self.search)
+            })
+        };
+        match found {
+            Some(row) => {
+                self.move_to(row, event);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // the tree's own input, the rest goes to the list
+    fn on_tree_event(&mut self, event: &mut UIEvent) -> bool {
+        let m = event.modifiers;
+        match event.kind.clone() {
+            EUIEvent::MouseDown {
+                pos,
+                button: EMouseButton::Left,
+            } => match self.get_arrow_at(pos) {
+                Some(node) => {
+                    self.toggle(node);
+                    event.request_redraw();
+                    true
+                }
+                None => false,
+            },
+            // toggles and still activates
+            EUIEvent::DoubleClick { pos } if self.get_arrow_at(pos).is_none() => {
+                if let Some(node) = self
+                    .list
+                    .get_item_at(pos)
+                    .and_then(|r| self.get_node_at_row(r))
+                {
+                    self.toggle(node);
+                    event.request_redraw();
+                }
+                false
+            }
+            EUIEvent::KeyDown(input) if event.is_target() => {
+                if !self.on_key(input.key, event) {
+                    return false;
+                }
+                event.request_redraw();
+                event.stop_propagation();
+                true
+            }
+            EUIEvent::Char(c) if event.is_target() => {
+                if m.command() || m.alt || c.is_control() || self.get_row_count() == 0 {
+                    return false;
+                }
+                if c.is_whitespace() && self.search.is_empty() {
+                    return false;
+                }
+                if self.on_search(c, event) {
+                    event.request_redraw();
+                }
+                event.stop_propagation();
+                true
+            }
+            EUIEvent::Timer(SEARCH_TIMER) => {
+                self.search.clear();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl TCtrl for TreeView {
+    fn get_inner(&self) -> &TCtrlInner {
+        self.list.get_inner()
+    }
+    fn get_mut_inner(&mut self) -> &mut TCtrlInner {
+        self.list.get_mut_inner()
+    }
+    fn get_style(&self, status: EUIStatus) -> Option<&StyleInner> {
+        self.list.get_style(status)
+    }
+    fn get_mut_style(&mut self, status: EUIStatus) -> Option<&mut StyleInner> {
+        self.list.get_mut_style(status)
+    }
+    fn type_name(&self) -> &str {
+        "TreeView"
+    }
+    fn type_(&self) -> ECtrlType {
+        ECtrlType::TreeView
+    }
+    fn as_container(&self) -> Option<&Container> {
+        self.list.as_container()
+    }
+    fn as_mut_container(&mut self) -> Option<&mut Container> {
+        self.list.as_mut_container()
+    }
+    fn get_status(&self) -> ECtrlStatus {
+        self.list.get_status()
+    }
+    fn set_status(&mut self, status: ECtrlStatus) {
+        self.list.set_status(status);
+    }
+
+    fn update(&mut self, dpi: &ScaleDpi) {
+        self.dpi_cache = dpi.clone();
+        let changed = {
+            let mut state = self.state.borrow_mut();
+            let look = (self.indent, self.guide_color, self.arrow_color);
+            let changed = look != (state.indent, state.guide_color, state.arrow_color);
+            state.indent = self.indent;
+            state.guide_color = self.guide_color;
+            state.arrow_color = self.arrow_color;
+            changed
+        };
+        // rows on screen only take them when bound again
+        if changed {
+            self.list.rebind();
+        }
+        self.list.update(dpi);
+    }
+
+    fn update_dpi(&mut self, dpi: &ScaleDpi) {
+        self.dpi_cache = dpi.clone();
+        self.list.update_dpi(dpi);
+    }
+
+    fn on_event(&mut self, event: &mut UIEvent) {
+        let skip =
+            event.phase == EEventPhase::Capture || self.list.ctrl.status == ECtrlStatus::Disable;
+        if !skip && self.on_tree_event(event) {
+            return;
+        }
+        self.list.on_event(event);
+    }
+
+    fn render(&self, canvas: &mut Canvas, dpi: &ScaleDpi) {
+        self.list.render(canvas, dpi);
+    }
+}
@@ -1,4 +1,5 @@
-use crate::{IPoint, IRect, ISize};
+use crate::{IPoint, IRect, ISize, Point};
+use std::str::FromStr;
 
 ////////////////////////////////////////////////////////////
 /// DPI
@@ -16,6 +17,24 @@ impl TScaleDpi for i32 {
     }
 }
 
+impl TScaleDpi for f32 {
+    fn scale(self, dpi: f64) -> Self {
+        (self as f64 * dpi) as f32
+    }
+    fn rescale(self, dpi: f64) -> Self {
+        (self as f64 / dpi) as f32
+    }
+}
+
+impl TScaleDpi for Point {
+    fn scale(self, dpi: f64) -> Self {
+        Self::new(self.x.scale(dpi), self.y.scale(dpi))
+    }
+    fn rescale(self, dpi: f64) -> Self {
+        Self::new(self.x.rescale(dpi), self.y.rescale(dpi))
+    }
+}
+
 impl TScaleDpi for IRect {
     fn scale(self, dpi: f64) -> Self {
         Self::new(
@@ -35,6 +54,21 @@ impl TScaleDpi for IRect {
     }
 }
 
+impl TScaleDpi for ISize {
+    fn scale(self, dpi: f64) -> Self {
+        Self::new(
+            (self.width as f64 * dpi) as i32,
+            (self.height as f64 * dpi) as i32,
+        )
+    }
+    fn rescale(self, dpi: f64) -> Self {
+        Self::new(
+            (self.width as f64 / dpi) as i32,
+            (self.height as f64 / dpi) as i32,
+        )
+    }
+}
+
 impl TScaleDpi for IPoint {
     fn scale(self, dpi: f64) -> Self {
         Self::new((self.x as f64 * dpi) as i32, (self.y as f64 * dpi) as i32)
@@ -68,26 +102,76 @@ impl Default for ScaleDpi {
         Self { dpi: 1. }
     }
 }
+////////////////////////////////////////////////////////////
+/// Unit
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum EUnit {
+    /// physical pixels
+    Px(i32),
+    /// logical pixels, scaled with the dpi
+    Dp(i32),
+    /// percent of the parent's content size
+    Percent(f32),
+    /// weight in the space left by the siblings, `2*` in text; used by the
+    /// horizontal, vertical and flex layouts
+    Star(f32),
+    /// what `TCtrl::get_preferred_size` asks for
+    Auto,
+}
+
+impl EUnit {
+    /// Physical pixels inside a parent `parent` pixels long, `None` for
+    /// `Star` and `Auto` which the layout works out.
+    pub fn resolve(&self, parent: i32, dpi: &ScaleDpi) -> Option<i32> {
+        match *self {
+            Self::Px(v) => Some(v),
+            Self::Dp(v) => Some(dpi.scale(v)),
+            Self::Percent(v) => Some((parent as f32 * v / 100.) as i32),
+            Self::Star(_) | Self::Auto => None,
+        }
+    }
+}
+
+impl From<i32> for EUnit {
+    fn from(v: i32) -> Self {
+        Self::Dp(v)
+    }
+}
+
+/// `12`, `12dp`, `12px`, `20%`, `*`, `2*` or `auto`
+impl FromStr for EUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let err = || format!("invalid unit: {}", s);
+        let num = |v: &str| v.trim().parse::<f32>().map_err(|_| err());
+
+        if s.eq_ignore_ascii_case("auto") {
+            Ok(Self::Auto)
+        } else if s == "*" {
+            Ok(Self::Star(1.))
+        } else if let Some(v) = s.strip_suffix('*') {
+            Ok(Self::Star(num(v)?))
+        } else if let Some(v) = s.strip_suffix('%') {
+            Ok(Self::Percent(num(v)?))
+        } else if let Some(v) = s.strip_suffix("px") {
+            Ok(Self::Px(num(v)? as i32))
+        } else {
+            Ok(Self::Dp(num(s.strip_suffix("dp").unwrap_or(s))? as i32))
+        }
+    }
+}
+
 ////////////////////////////////////////////////////////////
 /// IRect Option
 
 #[derive(Default)]
 pub struct IRectOpt {
-    pub left: Option<i32>,
-    pub top: Option<i32>,
-    pub right: Option<i32>,
-    pub bottom: Option<i32>,
-}
-
-impl IRectOpt {
-    pub fn to_rc(&self, default: i32) -> IRect {
-        IRect::new(
-            self.left.unwrap_or(default),
-            self.top.unwrap_or(default),
-            self.right.unwrap_or(default),
-            self.bottom.unwrap_or(default),
-        )
-    }
+    pub left: Option<EUnit>,
+    pub top: Option<EUnit>,
+    pub right: Option<EUnit>,
+    pub bottom: Option<EUnit>,
 }
 
 ////////////////////////////////////////////////////////////
@@ -95,42 +179,32 @@ impl IRectOpt {
 
 #[derive(Default)]
 pub struct ISizeOpt {
-    pub width: Option<i32>,
-    pub height: Option<i32>,
+    pub width: Option<EUnit>,
+    pub height: Option<EUnit>,
 }
 
 impl ISizeOpt {
-    pub fn new(w: Option<i32>, h: Option<i32>) -> Self {
+    pub fn new(w: Option<EUnit>, h: Option<EUnit>) -> Self {
         Self {
             width: w,
             height: h,
         }
     }
-
-    pub fn to_size(&self, default: i32) -> ISize {
-        ISize::new(
-            self.width.unwrap_or(default),
-            self.height.unwrap_or(default),
-        )
-    }
 }
 
 ////////////////////////////////////////////////////////////
-/// ISize Option
+/// IPoint Option
 
 #[derive(Default)]
 pub struct IPointOpt {
-    pub x: Option<i32>,
-    pub y: Option<i32>,
+    pub x: Option<EUnit>,
+    pub y: Option<EUnit>,
 }
 
 impl IPointOpt {
-    pub fn new(x: Option<i32>, y: Option<i32>) -> Self {
+    pub fn new(x: Option<EUnit>, y: Option<EUnit>) -> Self {
         Self { x, y }
     }
-    pub fn _to_point(self, default: i32) -> IPoint {
-        IPoint::new(self.x.unwrap_or(default), self.y.unwrap_or(default))
-    }
 }
 
 ////////////////////////////////////////////////////////////
@@ -208,3 +282,21 @@ fn test_in_rc() {
     assert!(!in_rc(&IRect::new(10, 10, 20, 20), &IPoint::new(9, 21)));
     assert!(in_rc(&IRect::new(10, 10, 20, 20), &IPoint::new(11, 20)));
 }
+
+#[test]
+fn test_unit() {
+    assert_eq!("12".parse(), Ok(EUnit::Dp(12)));
+    assert_eq!("12dp".parse(), Ok(EUnit::Dp(12)));
+    assert_eq!("12px".parse(), Ok(EUnit::Px(12)));
+    assert_eq!(" 20% ".parse(), Ok(EUnit::Percent(20.)));
+    assert_eq!("*".parse(), Ok(EUnit::Star(1.)));
+    assert_eq!("2.5*".parse(), Ok(EUnit::Star(2.5)));
+    assert_eq!("Auto".parse(), Ok(EUnit::Auto));
+    assert!("wide".parse::<EUnit>().is_err());
+
+    let dpi = ScaleDpi::new(2.);
+    assert_eq!(EUnit::Px(12).resolve(100, &dpi), Some(12));
+    assert_eq!(EUnit::Dp(12).resolve(100, &dpi), Some(24));
+    assert_eq!(EUnit::Percent(20.).resolve(300, &dpi), Some(60));
+    assert_eq!(EUnit::Star(1.).resolve(300, &dpi), None);
+}
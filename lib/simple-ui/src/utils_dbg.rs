@@ -1,5 +1,4 @@
 use crate::ui::{styles::EUIStatus, Container, TCtrl};
-use as_any::Downcast;
 use skia_safe::Color;
 
 const COLOR_LIST: [Color; 10] = [
@@ -47,8 +46,7 @@ pub fn fill_bg_color(root: &mut Container) {
 
     for child in root.get_mut_children().iter_mut().rev() {
         let child = child.as_mut();
-        if child.is_container() {
-            let cc = child.downcast_mut::<Container>().unwrap();
+        if let Some(cc) = child.as_mut_container() {
             fill_bg_color(cc);
         }
     }
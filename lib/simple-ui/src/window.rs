@@ -1,31 +1,38 @@
-use cocoa::{appkit::NSView, base::id as cocoa_id};
-use core_graphics_types::geometry::CGSize;
-use foreign_types_shared::{ForeignType, ForeignTypeRef};
-use metal::{Device, MTLPixelFormat, MetalLayer};
-use objc::{rc::autoreleasepool, runtime::YES};
-use skia_safe::{
-    gpu::{self, mtl, BackendRenderTarget, DirectContext, SurfaceOrigin},
-    Canvas, ColorType, ISize,
-};
+use skia_safe::{Canvas, ISize, Image};
 use winit::{
-    dpi::{LogicalSize, PhysicalPosition, PhysicalSize},
-    event::{ElementState, Event, MouseButton, StartCause, WindowEvent},
+    dpi::{LogicalSize, PhysicalPosition},
+    event::{ElementState, Event, MouseScrollDelta, StartCause, WindowEvent},
     event_loop::{ControlFlow, EventLoop},
-    platform::macos::WindowExtMacOS,
 };
 
+use std::time::{Duration, Instant};
+
 use crate::{
-    ui::{Container, TCtrl},
+    backend::{self, HeadlessBackend, TBackend},
+    input::{EKey, EMouseButton, KeyInput, KeyModifiers},
+    ui::{Container, ECtrlStatus, EEventPhase, EUIEvent, EventCtx, TCtrl, UIEvent},
     utils::ScaleDpi,
-    IPoint,
+    IPoint, Point,
 };
 
+// logical pixels scrolled by one wheel line
+const WHEEL_LINE_SIZE: f32 = 20.;
+// keeps a timer re-armed from its own handler from firing in the same pass
+const MIN_TIMER_DELAY: Duration = Duration::from_millis(1);
+// a second click on the same control within both is a double click
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(500);
+const DOUBLE_CLICK_DIST: i32 = 4;
+
 pub type WinitWindow = winit::window::Window;
 
 pub trait TWindowDelegate {
     fn on_draw(&mut self, _window: &Window, _canvas: &mut Canvas) {}
     fn on_resize(&mut self, _size: ISize) {}
     fn on_mouse_moved(&mut self, _window: &mut Window, _pos: IPoint) {}
+    fn on_lbtn_down(&mut self, _window: &mut Window, _pos: IPoint) {}
+    fn on_lbtn_up(&mut self, _window: &mut Window, _pos: IPoint) {}
+    fn on_key(&mut self, _window: &mut Window, _key: &KeyInput) {}
+    fn on_char(&mut self, _window: &mut Window, _c: char) {}
 }
 
 struct DefWindowDelegate {}
@@ -33,28 +40,47 @@ struct DefWindowDelegate {}
 impl TWindowDelegate for DefWindowDelegate {}
 
 pub struct WindowBuilder {
-    // size: ISize,
+    size: ISize,
+    dpi: ScaleDpi,
     delegate: Option<Box<dyn TWindowDelegate>>,
 
-    window: winit::window::Window,
+    window: Option<winit::window::Window>,
     window_events_loop: Option<EventLoop<()>>,
 
     root_container: Option<Container>,
 }
 
+struct Timer {
+    at: Instant,
+    path: Vec<usize>,
+    id: u32,
+}
+
 pub struct Window {
     delegate: Option<Box<dyn TWindowDelegate>>,
 
-    window: winit::window::Window,
-    context: DirectContext,
-
-    metal_layer: MetalLayer,
-    command_queue: metal::CommandQueue,
+    // None for headless windows
+    window: Option<winit::window::Window>,
+    backend: Box<dyn TBackend>,
 
     root_container: Option<Container>,
 
     need_rerender: bool,
     dpi_cache: ScaleDpi,
+    modifiers: KeyModifiers,
+    cursor_pos: IPoint,
+    close_requested: bool,
+
+    // child index paths from the root container
+    hover_path: Option<Vec<usize>>,
+    capture_path: Option<Vec<usize>>,
+    focus_path: Option<Vec<usize>>,
+    // path, logical position and time of the last click
+    last_click: Option<(Vec<usize>, IPoint, Instant)>,
+
+    timers: Vec<Timer>,
+    // the time of headless windows only moves with `inject_elapse`
+    clock: Option<Instant>,
 }
 
 impl WindowBuilder {
@@ -68,15 +94,29 @@ impl WindowBuilder {
             .unwrap();
 
         Self {
-            // size,
+            size,
+            dpi: ScaleDpi::new(window.scale_factor()),
             delegate: None,
 
-            window,
+            window: Some(window),
             window_events_loop: Some(window_events_loop),
             root_container: None,
         }
     }
 
+    /// A window without a native window or event loop, see `build_headless`.
+    pub fn new_headless(size: ISize, dpi: ScaleDpi) -> Self {
+        Self {
+            size,
+            dpi,
+            delegate: None,
+
+            window: None,
+            window_events_loop: None,
+            root_container: None,
+        }
+    }
+
     pub fn set_root_container(&mut self, container: Container) {
         self.root_container = Some(container);
     }
@@ -86,58 +126,101 @@ impl WindowBuilder {
     }
 
     pub fn run(mut self) {
-        let window_events_loop = self.window_events_loop.take().unwrap();
+        let window_events_loop = self
+            .window_events_loop
+            .take()
+            .expect("headless window can't run, use build_headless");
+        let native_window = self.window.take().unwrap();
 
-        let device = Device::system_default().expect("no device found");
+        let backend = backend::create(&native_window);
+        let dpi_cache = ScaleDpi::new(native_window.scale_factor());
 
-        let metal_layer = {
-            let draw_size = self.window.inner_size();
-            let layer = MetalLayer::new();
-            layer.set_device(&device);
-            layer.set_pixel_format(MTLPixelFormat::BGRA8Unorm);
-            layer.set_presents_with_transaction(false);
+        let mut window = Window {
+            delegate: self.delegate,
+            window: Some(native_window),
+            backend,
 
-            unsafe {
-                let view = self.window.ns_view() as cocoa_id;
-                view.setWantsLayer(YES);
-                view.setLayer(layer.as_ref() as *const _ as _);
-            }
-            layer.set_drawable_size(CGSize::new(draw_size.width as f64, draw_size.height as f64));
-            layer
-        };
+            root_container: self.root_container,
 
-        let command_queue = device.new_command_queue();
+            need_rerender: false,
+            dpi_cache,
+            modifiers: KeyModifiers::default(),
+            cursor_pos: IPoint::default(),
+            close_requested: false,
+
+            hover_path: None,
+            capture_path: None,
+            focus_path: None,
+            last_click: None,
 
-        let backend = unsafe {
-            mtl::BackendContext::new(
-                device.as_ptr() as mtl::Handle,
-                command_queue.as_ptr() as mtl::Handle,
-                std::ptr::null(),
-            )
+            timers: Vec::new(),
+            clock: None,
         };
 
-        let context = DirectContext::new_metal(&backend, None).unwrap();
-        let dpi_cache = ScaleDpi::new(self.window.scale_factor());
+        window_events_loop.run(move |event, _, control_flow| {
+            backend::autoreleasepool(|| {
+                window.on_event(event, control_flow);
+            });
+        });
+    }
+
+    /// Builds a window that renders into an offscreen surface and is driven
+    /// by the `inject_*` functions instead of winit events.
+    pub fn build_headless(self) -> Window {
+        let pixel_size = self.dpi.scale(self.size);
 
         let mut window = Window {
             delegate: self.delegate,
-            window: self.window,
-            context,
-
-            metal_layer,
-            command_queue,
+            window: None,
+            backend: Box::new(HeadlessBackend::new(pixel_size)),
 
             root_container: self.root_container,
 
             need_rerender: false,
-            dpi_cache,
+            dpi_cache: self.dpi,
+            modifiers: KeyModifiers::default(),
+            cursor_pos: IPoint::default(),
+            close_requested: false,
+
+            hover_path: None,
+            capture_path: None,
+            focus_path: None,
+            last_click: None,
+
+            timers: Vec::new(),
+            clock: Some(Instant::now()),
         };
 
-        window_events_loop.run(move |event, _, control_flow| {
-            autoreleasepool(|| {
-                window.on_event(event, control_flow);
-            });
-        });
+        window.on_init();
+        window.on_resize(pixel_size);
+        window.draw_pending();
+        window
+    }
+}
+
+// delivers to the control at `target[..depth]`
+fn deliver_event(ctx: &mut EventCtx, event: &mut UIEvent, phase: EEventPhase, depth: usize) {
+    event.phase = phase;
+    event.current = event.target[..depth].to_vec();
+
+    let path = event.current.clone();
+    // handlers are taken out so they can borrow the whole tree through `ctx`
+    let mut handlers = match ctx.root.get_mut_ctrl_by_path(&path) {
+        Some(ctrl) => {
+            ctrl.on_event(event);
+            std::mem::take(&mut ctrl.get_mut_inner().handlers)
+        }
+        None => return,
+    };
+
+    for h in handlers.iter_mut() {
+        h.on_event(ctx, event);
+    }
+
+    if let Some(ctrl) = ctx.root.get_mut_ctrl_by_path(&path) {
+        let inner = ctrl.get_mut_inner();
+        let added = std::mem::replace(&mut inner.handlers, handlers);
+        inner.handlers.extend(added);
     }
 }
 
@@ -147,15 +230,23 @@ impl Window {
 
         match event {
             Event::NewEvents(start_cause) => self.on_event_new(start_cause),
-            Event::WindowEvent { event, .. } => self.on_event_window(event, control_flow),
+            Event::WindowEvent { event, .. } => self.on_event_window(event),
             Event::RedrawRequested(_) => self.on_draw(),
             _ => {}
         }
+
+        if let Some(at) = self.timers.iter().map(|t| t.at).min() {
+            *control_flow = ControlFlow::WaitUntil(at);
+        }
+        if self.close_requested {
+            *control_flow = ControlFlow::Exit;
+        }
     }
 
     fn on_event_new(&mut self, start_cause: StartCause) {
         match start_cause {
             StartCause::Init => self.on_init(),
+            StartCause::ResumeTimeReached { .. } => self.fire_timers(),
             _ => {}
         }
     }
@@ -170,25 +261,50 @@ impl Window {
         };
     }
 
-    fn on_event_window(&mut self, event: WindowEvent<'_>, control_flow: &mut ControlFlow) {
+    fn on_event_window(&mut self, event: WindowEvent<'_>) {
         use winit::event::WindowEvent::*;
 
         match event {
-            CloseRequested => *control_flow = ControlFlow::Exit,
-            Resized(size) => self.on_resize(size),
+            // armed timers must not keep the window open
+            CloseRequested => self.close(),
+            Resized(size) => self.on_resize(ISize::new(size.width as i32, size.height as i32)),
             MouseInput {
-                state: ElementState::Pressed,
-                button: MouseButton::Left,
+                state,
+                button,
                 position,
                 ..
-            } => self.on_lbtn_down(IPoint::new(position.x as i32, position.y as i32)),
-            MouseInput {
-                state: ElementState::Released,
-                button: MouseButton::Left,
-                position,
-                ..
-            } => self.on_lbtn_up(self.tran_point(position)),
+            } => {
+                let pos = self.tran_point(position);
+                match state {
+                    ElementState::Pressed => self.on_mouse_down(pos, button.into()),
+                    ElementState::Released => self.on_mouse_up(pos, button.into()),
+                }
+            }
             CursorMoved { position, .. } => self.on_mouse_moved(self.tran_point(position)),
+            MouseWheel { delta, .. } => {
+                let delta = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => {
+                        self.dpi_cache.scale(Point::new(x, y) * WHEEL_LINE_SIZE)
+                    }
+                    MouseScrollDelta::PixelDelta(p) => Point::new(p.x as f32, p.y as f32),
+                };
+                self.on_wheel(delta);
+            }
+            ModifiersChanged(state) => self.modifiers = state.into(),
+            KeyboardInput {
+                input:
+                    winit::event::KeyboardInput {
+                        state,
+                        virtual_keycode: Some(key),
+                        ..
+                    },
+                ..
+            } => self.on_key(key.into(), state == ElementState::Pressed),
+            ReceivedCharacter(c) => {
+                if !c.is_control() {
+                    self.on_char(c);
+                }
+            }
             _ => (),
         }
     }
@@ -197,30 +313,22 @@ impl Window {
         self.dpi_cache.rescale(IPoint::new(p.x as i32, p.y as i32))
     }
 
-    fn on_resize(&mut self, size: PhysicalSize<u32>) {
-        self.metal_layer
-            .set_drawable_size(CGSize::new(size.width as f64, size.height as f64));
+    fn on_resize(&mut self, size: ISize) {
+        self.backend.resize(size);
 
         match self.root_container.as_mut() {
             Some(c) => {
-                c.update_self(
-                    ISize::new(size.width as i32, size.height as i32),
-                    &self.dpi_cache,
-                );
+                c.update_self(size, &self.dpi_cache);
 
                 c.update(&self.dpi_cache);
-                self.need_rerender = true;
             }
             None => (),
         };
 
-        self.window.request_redraw();
+        self.redraw();
 
         if self.delegate.is_some() {
-            self.delegate
-                .as_mut()
-                .unwrap()
-                .on_resize(ISize::new(size.width as i32, size.height as i32));
+            self.delegate.as_mut().unwrap().on_resize(size);
         }
     }
 
@@ -230,36 +338,14 @@ impl Window {
         }
         self.need_rerender = false;
 
-        let drawable = self.metal_layer.next_drawable();
-        if drawable.is_none() {
-            return;
-        }
-        let drawable = drawable.unwrap();
-        let drawable_size = {
-            let size = self.metal_layer.drawable_size();
-            ISize::new(size.width as i32, size.height as i32)
+        let mut surface = match self.backend.begin_frame() {
+            Some(s) => s,
+            None => return,
         };
-
-        let mut surface = unsafe {
-            let texture_info = mtl::TextureInfo::new(drawable.texture().as_ptr() as mtl::Handle);
-
-            let backend_render_target = BackendRenderTarget::new_metal(
-                (drawable_size.width, drawable_size.height),
-                1,
-                &texture_info,
-            );
-
-            gpu::surfaces::wrap_backend_render_target(
-                &mut self.context,
-                &backend_render_target,
-                SurfaceOrigin::TopLeft,
-                ColorType::BGRA8888,
-                None,
-                None,
-            )
-            .unwrap()
+        let dpi = match self.window.as_ref() {
+            Some(w) => ScaleDpi::new(w.scale_factor()),
+            None => self.dpi_cache.clone(),
         };
-        let dpi = ScaleDpi::new(self.window.scale_factor());
 
         // Draw UI
         let canvas = surface.canvas();
@@ -275,21 +361,114 @@ impl Window {
         }
         self.delegate = dg;
 
-        self.context.flush_and_submit();
-        drop(surface);
-
-        let command_buffer = self.command_queue.new_command_buffer();
-        command_buffer.present_drawable(drawable);
-        command_buffer.commit();
+        self.backend.end_frame(surface);
         self.dpi_cache = dpi;
     }
 
-    fn on_lbtn_down(&self, pos: IPoint) {
-        let ctrl = self.get_ctrl_by_pos(&pos).unwrap();
-        println!("click {}", ctrl.get_inner().name);
+    fn on_mouse_down(&mut self, pos: IPoint, button: EMouseButton) {
+        if let Some(path) = self.get_ctrl_path_by_pos(&pos) {
+            let pos = self.dpi_cache.scale(pos);
+            self.dispatch_event(path, EUIEvent::MouseDown { pos, button });
+        }
+
+        if button == EMouseButton::Left {
+            self.on_lbtn_down(pos);
+        }
+    }
+    fn on_mouse_up(&mut self, pos: IPoint, button: EMouseButton) {
+        let target = match self.capture_path.clone() {
+            Some(path) => Some(path),
+            None => self.get_ctrl_path_by_pos(&pos),
+        };
+        if let Some(path) = target {
+            let pos = self.dpi_cache.scale(pos);
+            self.dispatch_event(path, EUIEvent::MouseUp { pos, button });
+        }
+
+        if button == EMouseButton::Left {
+            self.on_lbtn_up(pos);
+        }
+    }
+
+    fn on_lbtn_down(&mut self, pos: IPoint) {
+        if let Some(path) = self.get_ctrl_path_by_pos(&pos) {
+            // clicking outside of any focusable control clears the focus
+            self.set_focus(self.get_focusable_ancestor(&path));
+
+            if self.set_ctrl_status(&path, ECtrlStatus::Press) {
+                self.hover_path = Some(path.clone());
+                self.capture_path = Some(path);
+            }
+        }
+
+        let mut dg = self.delegate.take();
+        if dg.is_some() {
+            dg.as_mut().unwrap().on_lbtn_down(self, pos);
+
+            self.delegate = dg;
+        }
+    }
+    fn on_lbtn_up(&mut self, pos: IPoint) {
+        if let Some(capture) = self.capture_path.take() {
+            let path = self.get_ctrl_path_by_pos(&pos);
+
+            if path.as_ref() == Some(&capture) {
+                self.set_ctrl_status(&capture, ECtrlStatus::Hover);
+
+                let now = self.now();
+                let double = match &self.last_click {
+                    Some((p, at, t)) => {
+                        *p == capture
+                            && now.duration_since(*t) <= DOUBLE_CLICK_TIME
+                            && (pos.x - at.x).abs() <= DOUBLE_CLICK_DIST
+                            && (pos.y - at.y).abs() <= DOUBLE_CLICK_DIST
+                    }
+                    None => false,
+                };
+                // a third click starts over
+                self.last_click = (!double).then(|| (capture.clone(), pos, now));
+
+                let pos = self.dpi_cache.scale(pos);
+                self.dispatch_event(capture.clone(), EUIEvent::Click { pos });
+                if double {
+                    self.dispatch_event(capture, EUIEvent::DoubleClick { pos });
+                }
+            } else {
+                self.set_ctrl_status(&capture, ECtrlStatus::Default);
+                self.update_hover(path);
+            }
+        }
+
+        let mut dg = self.delegate.take();
+        if dg.is_some() {
+            dg.as_mut().unwrap().on_lbtn_up(self, pos);
+
+            self.delegate = dg;
+        }
     }
-    fn on_lbtn_up(&self, _pos: IPoint) {}
     fn on_mouse_moved(&mut self, pos: IPoint) {
+        self.cursor_pos = pos;
+
+        let path = self.get_ctrl_path_by_pos(&pos);
+        let target = self.capture_path.clone().or_else(|| path.clone());
+        if let Some(target) = target {
+            let pos = self.dpi_cache.scale(pos);
+            self.dispatch_event(target, EUIEvent::MouseMove { pos });
+        }
+
+        match self.capture_path.clone() {
+            // only the pressed control changes while the button is down
+            Some(capture) => {
+                let status = if path.as_ref() == Some(&capture) {
+                    ECtrlStatus::Press
+                } else {
+                    ECtrlStatus::Default
+                };
+                self.set_ctrl_status(&capture, status);
+            }
+            None => self.update_hover(path),
+        }
+
         let mut dg = self.delegate.take();
         if dg.is_some() {
             dg.as_mut().unwrap().on_mouse_moved(self, pos);
@@ -298,6 +477,354 @@ impl Window {
         }
     }
 
+    // `delta` is in physical pixels
+    fn on_wheel(&mut self, delta: Point) {
+        if let Some(path) = self.get_ctrl_path_by_pos(&self.cursor_pos) {
+            let pos = self.dpi_cache.scale(self.cursor_pos);
+            self.dispatch_event(path, EUIEvent::Wheel { pos, delta });
+        }
+    }
+
+    fn update_hover(&mut self, path: Option<Vec<usize>>) {
+        if path == self.hover_path {
+            return;
+        }
+
+        if let Some(old) = self.hover_path.take() {
+            self.set_ctrl_status(&old, ECtrlStatus::Default);
+            self.dispatch_event(old, EUIEvent::MouseLeave);
+        }
+        if let Some(new) = path {
+            self.set_ctrl_status(&new, ECtrlStatus::Hover);
+            self.dispatch_event(new.clone(), EUIEvent::MouseEnter);
+            self.hover_path = Some(new);
+        }
+    }
+
+    // returns false if the control is missing or disabled
+    fn set_ctrl_status(&mut self, path: &[usize], status: ECtrlStatus) -> bool {
+        let ctrl = match self.get_mut_ctrl_by_path(path) {
+            Some(c) => c,
+            None => return false,
+        };
+
+        let cur = ctrl.get_status();
+        if cur == ECtrlStatus::Disable {
+            return false;
+        }
+        if cur != status {
+            ctrl.set_status(status);
+            self.redraw();
+        }
+        true
+    }
+
+    fn on_key(&mut self, key: EKey, pressed: bool) {
+        let input = KeyInput {
+            key,
+            pressed,
+            modifiers: self.modifiers,
+        };
+
+        let kind = if pressed {
+            EUIEvent::KeyDown(input)
+        } else {
+            EUIEvent::KeyUp(input)
+        };
+        let target = self.focus_path.clone().unwrap_or_default();
+        let event = self.dispatch_event(target, kind);
+
+        // controls that use Tab themselves stop the event
+        let m = input.modifiers;
+        if pressed && key == EKey::Tab && !event.is_stopped() && !(m.ctrl || m.alt || m.logo) {
+            self.focus_next(m.shift);
+        }
+
+        let mut dg = self.delegate.take();
+        if dg.is_some() {
+            dg.as_mut().unwrap().on_key(self, &input);
+
+            self.delegate = dg;
+        }
+    }
+    fn on_char(&mut self, c: char) {
+        let target = self.focus_path.clone().unwrap_or_default();
+        self.dispatch_event(target, EUIEvent::Char(c));
+
+        let mut dg = self.delegate.take();
+        if dg.is_some() {
+            dg.as_mut().unwrap().on_char(self, c);
+
+            self.delegate = dg;
+        }
+    }
+
+    /// Routes `kind` from the root container down to `target` (capture),
+    /// delivers it to `target`, then back up to the root (bubble).
+    /// Every control on the way sees it in `TCtrl::on_event` and then in its handlers.
+    pub fn dispatch_event(&mut self, target: Vec<usize>, kind: EUIEvent) -> UIEvent {
+        self.route_event(target, kind, true)
+    }
+
+    // without `routed` only the target sees the event
+    fn route_event(&mut self, target: Vec<usize>, kind: EUIEvent, routed: bool) -> UIEvent {
+        let mut event = UIEvent::new(kind, target);
+        event.modifiers = self.modifiers;
+
+        let root = match self.root_container.as_mut() {
+            Some(r) => r,
+            None => return event,
+        };
+        let mut ctx = EventCtx::new(root, &self.dpi_cache);
+
+        let depth = event.target.len();
+        let ancestors = if routed { depth } else { 0 };
+        for i in 0..ancestors {
+            if event.is_stopped() {
+                break;
+            }
+            deliver_event(&mut ctx, &mut event, EEventPhase::Capture, i);
+        }
+        if !event.is_stopped() {
+            deliver_event(&mut ctx, &mut event, EEventPhase::Target, depth);
+        }
+        for i in (0..ancestors).rev() {
+            if event.is_stopped() {
+                break;
+            }
+            deliver_event(&mut ctx, &mut event, EEventPhase::Bubble, i);
+        }
+
+        let (redraw, close, focus) = (ctx.redraw, ctx.close, ctx.focus.take());
+        if redraw || event.need_redraw() {
+            self.redraw();
+        }
+        if close {
+            self.close();
+        }
+        if let Some(path) = focus {
+            self.set_focus(path);
+        }
+        for (path, id, delay) in event.take_timers() {
+            self.add_timer(path, id, delay);
+        }
+
+        for (path, kind) in event.take_posted() {
+            self.dispatch_event(path, kind);
+        }
+        event
+    }
+
+    // the nearest focusable control from `path` up to the root
+    fn get_focusable_ancestor(&self, path: &[usize]) -> Option<Vec<usize>> {
+        (0..=path.len()).rev().map(|i| &path[..i]).find_map(|p| {
+            let ctrl = self.get_ctrl_by_path(p)?;
+            let ok = ctrl.get_inner().focusable && ctrl.get_status() != ECtrlStatus::Disable;
+            ok.then(|| p.to_vec())
+        })
+    }
+
+    pub fn get_focus_path(&self) -> Option<&Vec<usize>> {
+        self.focus_path.as_ref()
+    }
+
+    pub fn get_focused_ctrl(&self) -> Option<&dyn TCtrl> {
+        self.get_ctrl_by_path(self.focus_path.as_ref()?)
+    }
+
+    /// Moves the keyboard focus, sending `FocusOut` to the old control and
+    /// `FocusIn` to the new one. `None` or a missing path clears the focus.
+    pub fn set_focus(&mut self, path: Option<Vec<usize>>) {
+        let path = path.filter(|p| self.get_ctrl_by_path(p).is_some());
+        if path == self.focus_path {
+            return;
+        }
+
+        if let Some(old) = self.focus_path.take() {
+            if let Some(c) = self.get_mut_ctrl_by_path(&old) {
+                c.get_mut_inner().focused = false;
+            }
+            self.dispatch_event(old, EUIEvent::FocusOut);
+        }
+        if let Some(new) = path {
+            if let Some(c) = self.get_mut_ctrl_by_path(&new) {
+                c.get_mut_inner().focused = true;
+            }
+            self.focus_path = Some(new.clone());
+            self.dispatch_event(new, EUIEvent::FocusIn);
+        }
+        self.redraw();
+    }
+
+    /// Tab / Shift+Tab, wraps around at both ends.
+    pub fn focus_next(&mut self, backward: bool) {
+        let order = match self.root_container.as_ref() {
+            Some(r) => r.get_tab_order(),
+            None => return,
+        };
+        if order.is_empty() {
+            return;
+        }
+
+        let cur = self
+            .focus_path
+            .as_ref()
+            .and_then(|f| order.iter().position(|p| p == f));
+        let next = match (cur, backward) {
+            (None, false) => 0,
+            (None, true) => order.len() - 1,
+            (Some(i), false) => (i + 1) % order.len(),
+            (Some(i), true) => (i + order.len() - 1) % order.len(),
+        };
+        self.set_focus(Some(order[next].clone()));
+    }
+
+    fn now(&self) -> Instant {
+        self.clock.unwrap_or_else(Instant::now)
+    }
+
+    fn add_timer(&mut self, path: Vec<usize>, id: u32, delay: Duration) {
+        self.timers.retain(|t| t.path != path || t.id != id);
+        self.timers.push(Timer {
+            at: self.now() + delay.max(MIN_TIMER_DELAY),
+            path,
+            id,
+        });
+    }
+
+    fn fire_timers(&mut self) {
+        let now = self.now();
+        let (mut due, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut self.timers)
+            .into_iter()
+            .partition(|t| t.at <= now);
+        self.timers = rest;
+
+        due.sort_by_key(|t| t.at);
+        for t in due {
+            self.route_event(t.path, EUIEvent::Timer(t.id), false);
+        }
+    }
+
+    /// Exits the event loop after the current event.
+    pub fn close(&mut self) {
+        self.close_requested = true;
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.close_requested
+    }
+
+    ////////////////////////////////////////////////////////////
+    /// Synthetic input, positions and sizes are logical like the winit path
+
+    pub fn inject_mouse_move(&mut self, pos: IPoint) {
+        self.on_mouse_moved(pos);
+    }
+
+    pub fn inject_mouse_down(&mut self, pos: IPoint, button: EMouseButton) {
+        self.on_mouse_down(pos, button);
+    }
+
+    pub fn inject_mouse_up(&mut self, pos: IPoint, button: EMouseButton) {
+        self.on_mouse_up(pos, button);
+    }
+
+    /// move + left down + left up at `pos`
+    pub fn inject_click(&mut self, pos: IPoint) {
+        self.on_mouse_moved(pos);
+        self.on_mouse_down(pos, EMouseButton::Left);
+        self.on_mouse_up(pos, EMouseButton::Left);
+    }
+
+    /// `delta` is logical, positive y scrolls up like winit
+    pub fn inject_wheel(&mut self, delta: Point) {
+        self.on_wheel(self.dpi_cache.scale(delta));
+    }
+
+    pub fn inject_modifiers(&mut self, modifiers: KeyModifiers) {
+        self.modifiers = modifiers;
+    }
+
+    pub fn inject_key_down(&mut self, key: EKey) {
+        self.on_key(key, true);
+    }
+
+    pub fn inject_key_up(&mut self, key: EKey) {
+        self.on_key(key, false);
+    }
+
+    /// key down + key up with the current modifiers
+    pub fn inject_key(&mut self, key: EKey) {
+        self.on_key(key, true);
+        self.on_key(key, false);
+    }
+
+    pub fn inject_char(&mut self, c: char) {
+        self.on_char(c);
+    }
+
+    pub fn inject_text(&mut self, text: &str) {
+        for c in text.chars() {
+            self.on_char(c);
+        }
+    }
+
+    /// Moves the clock of a headless window forward, firing the timers due on the way.
+    pub fn inject_elapse(&mut self, d: Duration) {
+        let end = self.now() + d;
+        while let Some(at) = self
+            .timers
+            .iter()
+            .map(|t| t.at)
+            .filter(|at| *at <= end)
+            .min()
+        {
+            self.clock = Some(at);
+            self.fire_timers();
+        }
+        self.clock = Some(end);
+    }
+
+    /// the close button of the title bar
+    pub fn inject_close_request(&mut self) {
+        self.on_event_window(WindowEvent::CloseRequested);
+    }
+
+    pub fn inject_resize(&mut self, size: ISize) {
+        self.on_resize(self.dpi_cache.scale(size));
+    }
+
+    /// Draws now if a redraw was requested, returns whether a frame was drawn.
+    /// A native window does this when winit sends `RedrawRequested`.
+    pub fn draw_pending(&mut self) -> bool {
+        if !self.need_rerender {
+            return false;
+        }
+        self.on_draw();
+        true
+    }
+
+    pub fn is_redraw_pending(&self) -> bool {
+        self.need_rerender
+    }
+
+    /// The last drawn frame, only available on headless windows.
+    pub fn snapshot(&mut self) -> Option<Image> {
+        self.backend.snapshot()
+    }
+
+    pub fn get_root_container(&self) -> Option<&Container> {
+        self.root_container.as_ref()
+    }
+
+    pub fn get_mut_root_container(&mut self) -> Option<&mut Container> {
+        self.root_container.as_mut()
+    }
+
+    pub fn get_dpi(&self) -> &ScaleDpi {
+        &self.dpi_cache
+    }
+
     pub fn get_ctrl_by_name(&self, name: &str) -> Option<&dyn TCtrl> {
         if self.root_container.is_none() {
             return None;
@@ -326,8 +853,25 @@ impl Window {
         Some(root.get_ctrl_by_pos(&self.dpi_cache.scale(pos.clone())))
     }
 
+    /// `pos` is logical
+    pub fn get_ctrl_path_by_pos(&self, pos: &IPoint) -> Option<Vec<usize>> {
+        let root = self.root_container.as_ref()?;
+
+        Some(root.get_ctrl_path_by_pos(&self.dpi_cache.scale(*pos)))
+    }
+
+    pub fn get_ctrl_by_path(&self, path: &[usize]) -> Option<&dyn TCtrl> {
+        self.root_container.as_ref()?.get_ctrl_by_path(path)
+    }
+
+    pub fn get_mut_ctrl_by_path(&mut self, path: &[usize]) -> Option<&mut dyn TCtrl> {
+        self.root_container.as_mut()?.get_mut_ctrl_by_path(path)
+    }
+
     pub fn redraw(&mut self) {
         self.need_rerender = true;
-        self.window.request_redraw();
+        if let Some(w) = self.window.as_ref() {
+            w.request_redraw();
+        }
     }
 }
@@ -0,0 +1,87 @@
+use simple_ui::{
+    input::EMouseButton, testing::UIDriver, ui::*, EUnit, IPoint, IRect, ISize, ScaleDpi,
+};
+use std::{cell::Cell, rc::Rc};
+
+struct ClickCounter {
+    count: Rc<Cell<u32>>,
+}
+
+impl TEventHandler for ClickCounter {
+    fn on_event(&mut self, _ctx: &mut EventCtx, event: &mut UIEvent) {
+        if let EUIEvent::Click { .. } = event.kind {
+            self.count.set(self.count.get() + 1);
+        }
+    }
+}
+
+fn create(count: Rc<Cell<u32>>) -> Container {
+    let mut root = Container::new(LayoutHorizontal::new());
+    root.ctrl.inner.name = "Root".to_string();
+    root.ctrl.inner.padding = Some(IRect::new(10, 10, 10, 10));
+    {
+        let mut btn = Button::default();
+        btn.ctrl.inner.name = "Ok".to_string();
+        btn.ctrl.inner.size.width = Some(EUnit::Dp(80));
+        btn.ctrl.inner.add_handler(Box::new(ClickCounter { count }));
+        root.append_child(Box::new(btn));
+    }
+    {
+        let mut btn = Button::default();
+        btn.ctrl.inner.name = "Disabled".to_string();
+        btn.ctrl.status = ECtrlStatus::Disable;
+        root.append_child(Box::new(btn));
+    }
+    root
+}
+
+#[test]
+fn test_hover() {
+    let count = Rc::new(Cell::new(0));
+    let mut driver = UIDriver::new(create(count), ISize::new(200, 50), ScaleDpi::new(1.));
+
+    driver.hover("Ok").assert_redraw();
+    driver.assert_status("Ok", ECtrlStatus::Hover);
+
+    // padding of the root container
+    driver.window().inject_mouse_move(IPoint::new(5, 5));
+    driver
+        .assert_status("Ok", ECtrlStatus::Default)
+        .assert_status("Root", ECtrlStatus::Hover);
+
+    driver.hover("Disabled");
+    driver
+        .assert_status("Root", ECtrlStatus::Default)
+        .assert_status("Disabled", ECtrlStatus::Disable);
+}
+
+#[test]
+fn test_click() {
+    let count = Rc::new(Cell::new(0));
+    let mut driver = UIDriver::new(
+        create(count.clone()),
+        ISize::new(200, 50),
+        ScaleDpi::new(1.),
+    );
+
+    driver.click("Ok");
+    assert_eq!(count.get(), 1);
+    driver.assert_status("Ok", ECtrlStatus::Hover);
+
+    // press on the button, release outside, no click
+    let pos = driver.center("Ok");
+    let outside = driver.center("Disabled");
+    driver.window().inject_mouse_down(pos, EMouseButton::Left);
+    driver.assert_status("Ok", ECtrlStatus::Press);
+    driver.window().inject_mouse_move(outside);
+    driver.assert_status("Ok", ECtrlStatus::Default);
+    driver.window().inject_mouse_move(pos);
+    driver.assert_status("Ok", ECtrlStatus::Press);
+    driver.window().inject_mouse_move(outside);
+    driver.window().inject_mouse_up(outside, EMouseButton::Left);
+    driver.assert_status("Ok", ECtrlStatus::Default);
+    assert_eq!(count.get(), 1);
+
+    driver.click("Disabled");
+    driver.assert_status("Disabled", ECtrlStatus::Disable);
+}
@@ -0,0 +1,104 @@
+use as_any::Downcast;
+use simple_ui::{
+    input::EKey,
+    testing::UIDriver,
+    ui::{styles::TStyle, ui_check_box::CheckStyle, *},
+    Color, EUnit, ISize, ScaleDpi,
+};
+use std::{cell::Cell, rc::Rc};
+
+fn count(c: &Rc<Cell<u32>>) -> impl FnMut(&mut EventCtx, &mut UIEvent) + 'static {
+    let c = c.clone();
+    move |_, _| c.set(c.get() + 1)
+}
+
+fn check_box(driver: &UIDriver) -> &CheckBox {
+    driver
+        .find("Check")
+        .unwrap()
+        .downcast_ref::<CheckBox>()
+        .unwrap()
+}
+
+#[test]
+fn test_check_box() {
+    let toggled = Rc::new(Cell::new(0));
+    let mut root = Container::new(LayoutVertical::new());
+    let mut check = CheckBox::new("Remember me");
+    check.ctrl.inner.name = "Check".to_string();
+    check.ctrl.inner.size.height = Some(EUnit::Dp(20));
+    check.tri_state = true;
+    let mut checked = CheckStyle::default();
+    checked.get_mut_inner().bg_color = Some(Color::BLUE);
+    check.ctrl.styles.checked = Some(checked);
+    check.ctrl.inner.on_toggled(count(&toggled));
+    root.append_child(Box::new(check));
+    let mut driver = UIDriver::new(root, ISize::new(200, 100), ScaleDpi::new(1.));
+
+    driver.assert_text("Check", "Remember me");
+    driver.click("Check");
+    assert_eq!(check_box(&driver).get_state(), ECheckState::Checked);
+    assert_eq!(
+        check_box(&driver).ctrl.get_cur_style().get_inner().bg_color,
+        Some(Color::BLUE)
+    );
+
+    // focused by the click, Space goes on to the third state
+    driver.press_key(EKey::Space);
+    assert_eq!(check_box(&driver).get_state(), ECheckState::Indeterminate);
+    assert_eq!(
+        check_box(&driver).ctrl.get_cur_style().get_inner().bg_color,
+        None
+    );
+    driver.press_key(EKey::Space);
+    assert_eq!(check_box(&driver).get_state(), ECheckState::Unchecked);
+    assert_eq!(toggled.get(), 3);
+}
+
+#[test]
+fn test_radio_group() {
+    let toggled = Rc::new(Cell::new(0));
+    let changes = Rc::new(Cell::new(0));
+    let mut root = Container::new(LayoutVertical::new());
+    let mut group = RadioGroup::new(LayoutVertical::new());
+    group.items.ctrl.inner.name = "Group".to_string();
+    group.items.ctrl.inner.on_change(count(&changes));
+    for name in ["A", "B", "C"] {
+        let mut radio = RadioButton::new(name);
+        radio.ctrl.inner.name = name.to_string();
+        radio.ctrl.inner.on_toggled(count(&toggled));
+        group.append_child(Box::new(radio));
+    }
+    group.set_checked(Some(0));
+    root.append_child(Box::new(group));
+    let mut driver = UIDriver::new(root, ISize::new(200, 90), ScaleDpi::new(1.));
+
+    let checked = |driver: &UIDriver| {
+        driver
+            .find("Group")
+            .unwrap()
+            .downcast_ref::<RadioGroup>()
+            .unwrap()
+            .get_checked()
+    };
+    assert_eq!(checked(&driver), Some(0));
+
+    driver.click("B");
+    assert_eq!(checked(&driver), Some(1));
+    let a = driver
+        .find("A")
+        .unwrap()
+        .downcast_ref::<RadioButton>()
+        .unwrap();
+    assert!(!a.is_checked());
+    assert_eq!((toggled.get(), changes.get()), (1, 1));
+
+    // a checked one stays checked
+    driver.click("B");
+    assert_eq!(checked(&driver), Some(1));
+    assert_eq!((toggled.get(), changes.get()), (1, 1));
+
+    driver.click("C").press_key(EKey::Space);
+    assert_eq!(checked(&driver), Some(2));
+    assert_eq!((toggled.get(), changes.get()), (2, 2));
+}
@@ -0,0 +1,101 @@
+use simple_ui::{testing::UIDriver, ui::*, EUnit, IPoint, IRect, ISize, ScaleDpi};
+
+fn new_ctrl(name: &str, width: Option<i32>, height: Option<i32>) -> Box<dyn TCtrl> {
+    let mut ctrl = Ctrl::default();
+    ctrl.inner.name = name.to_string();
+    ctrl.inner.size.width = width.map(EUnit::Dp);
+    ctrl.inner.size.height = height.map(EUnit::Dp);
+
+    Box::new(ctrl)
+}
+
+fn create() -> Container {
+    let mut root = Container::new(LayoutVertical::new());
+    root.ctrl.inner.name = "Root".to_string();
+    root.ctrl.inner.padding = Some(IRect::new(10, 10, 10, 10));
+    {
+        let mut bar = Container::new(LayoutHorizontal::new());
+        bar.ctrl.inner.name = "Bar".to_string();
+        bar.ctrl.inner.size.height = Some(EUnit::Dp(20));
+        bar.append_child(new_ctrl("Item", Some(30), None));
+        bar.append_child(new_ctrl("Rest", Some(150), None));
+        // pushed out of the bar by the first two children
+        bar.append_child(new_ctrl("Hidden", Some(30), None));
+        root.append_child(Box::new(bar));
+    }
+    root.append_child(new_ctrl("Item", None, None));
+    root
+}
+
+#[test]
+fn test_find() {
+    let driver = UIDriver::new(create(), ISize::new(200, 100), ScaleDpi::new(2.));
+
+    assert_eq!(driver.find("Bar").unwrap().type_(), ECtrlType::Container);
+    assert!(driver.find("Nothing").is_none());
+    assert!(driver.find("Root/Bar/Nothing").is_none());
+    assert_eq!(
+        driver.find(ECtrlType::BaseCtrl).unwrap().get_inner().name,
+        "Item"
+    );
+
+    driver
+        .assert_rect("Bar/Item", IRect::new(10, 10, 40, 30))
+        .assert_rect("Root/Item", IRect::new(10, 30, 190, 90))
+        .assert_rect("Rest", IRect::new(40, 10, 190, 30));
+}
+
+#[test]
+fn test_visible_and_status() {
+    let mut driver = UIDriver::new(create(), ISize::new(200, 100), ScaleDpi::new(1.));
+
+    driver
+        .assert_visible("Rest", true)
+        .assert_visible("Hidden", false)
+        .assert_status("Rest", ECtrlStatus::Default);
+
+    // left hovered by the click
+    driver
+        .click("Rest")
+        .assert_redraw()
+        .assert_status("Rest", ECtrlStatus::Hover);
+}
+
+#[test]
+fn test_clip() {
+    let mut root = Container::new(LayoutVertical::new());
+    root.ctrl.inner.name = "Root".to_string();
+    root.ctrl.inner.round = 20;
+    root.clip = EClip::Round;
+    {
+        let mut bx = Container::new(LayoutVertical::new());
+        bx.ctrl.inner.name = "Box".to_string();
+        bx.ctrl.inner.size.height = Some(EUnit::Dp(40));
+        bx.clip = EClip::None;
+        bx.append_child(new_ctrl("Out", None, Some(60)));
+        root.append_child(Box::new(bx));
+    }
+    let mut driver = UIDriver::new(root, ISize::new(200, 100), ScaleDpi::new(1.));
+
+    let name_at = |driver: &mut UIDriver, x, y| {
+        let w = driver.window();
+        w.get_ctrl_by_pos(&IPoint::new(x, y))
+            .unwrap()
+            .get_inner()
+            .name
+            .clone()
+    };
+    // the rounded corner of the root
+    assert_eq!(name_at(&mut driver, 1, 1), "Root");
+    assert_eq!(name_at(&mut driver, 100, 20), "Out");
+    // out of the box without a clip
+    assert_eq!(name_at(&mut driver, 100, 50), "Out");
+    driver
+        .assert_rect("Out", IRect::new(0, 0, 200, 60))
+        .assert_visible("Box/Out", true);
+
+    let w = driver.window();
+    let bx = w.get_mut_ctrl_by_name("Box").unwrap();
+    bx.as_mut_container().unwrap().clip = EClip::Rect;
+    assert_eq!(name_at(&mut driver, 100, 50), "Root");
+}
@@ -0,0 +1,179 @@
+use as_any::Downcast;
+use simple_ui::{
+    clipboard::{self, MemoryClipboard},
+    input::{EKey, EMouseButton, KeyModifiers},
+    testing::UIDriver,
+    ui::*,
+    EUnit, IPoint, ISize, ScaleDpi,
+};
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+#[derive(Default)]
+struct Record {
+    changes: u32,
+    submitted: Vec<String>,
+}
+
+fn create(record: &Rc<RefCell<Record>>, setup: impl FnOnce(&mut Edit)) -> UIDriver {
+    clipboard::set_clipboard(Box::<MemoryClipboard>::default());
+
+    let mut root = Container::new(LayoutVertical::new());
+    root.ctrl.inner.name = "Root".to_string();
+
+    let mut edit = Edit::new(14);
+    edit.ctrl.inner.name = "Name".to_string();
+    edit.ctrl.inner.size.height = Some(EUnit::Dp(30));
+    {
+        let record = record.clone();
+        edit.ctrl
+            .inner
+            .on_change(move |_, _| record.borrow_mut().changes += 1);
+    }
+    {
+        let record = record.clone();
+        edit.ctrl.inner.on_submit(move |ctx, _| {
+            let text = ctx.get_ctrl_by_name("Name").unwrap().get_text().unwrap();
+            record.borrow_mut().submitted.push(text.to_string());
+        });
+    }
+    setup(&mut edit);
+    root.append_child(Box::new(edit));
+
+    let mut ok = Button::default();
+    ok.ctrl.inner.name = "Ok".to_string();
+    root.append_child(Box::new(ok));
+
+    UIDriver::new(root, ISize::new(200, 60), ScaleDpi::new(1.))
+}
+
+fn edit(driver: &mut UIDriver) -> &mut Edit {
+    let w = driver.window();
+    w.get_mut_ctrl_by_name("Name")
+        .unwrap()
+        .downcast_mut::<Edit>()
+        .unwrap()
+}
+
+fn shift() -> KeyModifiers {
+    KeyModifiers {
+        shift: true,
+        ..Default::default()
+    }
+}
+
+fn command() -> KeyModifiers {
+    KeyModifiers {
+        ctrl: !cfg!(target_os = "macos"),
+        logo: cfg!(target_os = "macos"),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_type_and_submit() {
+    let record = Rc::new(RefCell::new(Record::default()));
+    let mut driver = create(&record, |_| {});
+
+    driver.type_text("Name", "helo");
+    driver.press_key(EKey::Left).press_key(EKey::Left);
+    driver.window().inject_text("l");
+    driver.press_key(EKey::End).press_key(EKey::Backspace);
+    driver.assert_text("Name", "hell").assert_focused("Name");
+    assert_eq!(record.borrow().changes, 6);
+
+    driver.press_key(EKey::Enter);
+    assert_eq!(record.borrow().submitted, vec!["hell"]);
+
+    // Tab still moves the focus away
+    driver.press_key(EKey::Tab).assert_focused("Ok");
+    driver.window().inject_text("x");
+    driver.assert_text("Name", "hell");
+}
+
+#[test]
+fn test_selection_and_clipboard() {
+    let record = Rc::new(RefCell::new(Record::default()));
+    let mut driver = create(&record, |e| e.set_text("hello world"));
+
+    driver.click("Name");
+    driver.press_key(EKey::End);
+    for _ in 0..5 {
+        driver.press_key_with(EKey::Left, shift());
+    }
+    assert_eq!(edit(&mut driver).get_selected_text(), "world");
+
+    driver.press_key_with(EKey::Char('x'), command());
+    driver.assert_text("Name", "hello ");
+
+    driver.press_key(EKey::Home);
+    driver.press_key_with(EKey::Char('v'), command());
+    driver.assert_text("Name", "worldhello ");
+    assert_eq!(edit(&mut driver).get_caret(), 5);
+
+    driver.press_key_with(EKey::Char('a'), command());
+    driver.window().inject_text("a");
+    driver.assert_text("Name", "a");
+    assert_eq!(clipboard::get_text().as_deref(), Some("world"));
+}
+
+#[test]
+fn test_password_and_max_length() {
+    let record = Rc::new(RefCell::new(Record::default()));
+    let mut driver = create(&record, |e| {
+        e.set_password(true);
+        e.set_max_length(Some(4));
+    });
+
+    driver.type_text("Name", "secret");
+    driver.assert_text("Name", "secr");
+    assert_eq!(record.borrow().changes, 4);
+
+    // nothing leaves a password field
+    driver
+        .press_key_with(EKey::Char('a'), command())
+        .press_key_with(EKey::Char('c'), command())
+        .press_key_with(EKey::Char('x'), command());
+    driver.assert_text("Name", "secr");
+    assert_eq!(clipboard::get_text(), None);
+}
+
+#[test]
+fn test_mouse_and_caret_blink() {
+    let record = Rc::new(RefCell::new(Record::default()));
+    let mut driver = create(&record, |e| e.set_text("hello"));
+
+    let rc = driver.rect("Name");
+    driver
+        .window()
+        .inject_click(IPoint::new(rc.left + 1, rc.top + 5));
+    assert_eq!(edit(&mut driver).get_caret(), 0);
+    assert!(edit(&mut driver).is_caret_visible());
+
+    driver.window().inject_elapse(Duration::from_millis(600));
+    assert!(!edit(&mut driver).is_caret_visible());
+    driver.window().inject_elapse(Duration::from_millis(600));
+    assert!(edit(&mut driver).is_caret_visible());
+
+    // dragging to the right end selects everything
+    let w = driver.window();
+    w.inject_mouse_down(IPoint::new(rc.left + 1, rc.top + 5), EMouseButton::Left);
+    w.inject_mouse_move(IPoint::new(rc.right - 1, rc.top + 5));
+    w.inject_mouse_up(IPoint::new(rc.right - 1, rc.top + 5), EMouseButton::Left);
+    assert_eq!(edit(&mut driver).get_selection(), 0..5);
+
+    // no blinking without the focus
+    driver.window().set_focus(None);
+    driver.window().inject_elapse(Duration::from_millis(600));
+    assert!(!edit(&mut driver).is_caret_visible());
+}
+
+#[test]
+fn test_close_while_focused() {
+    let record = Rc::new(RefCell::new(Record::default()));
+    let mut driver = create(&record, |_| ());
+
+    // the caret blink timer is armed while focused
+    driver.click("Name").assert_focused("Name");
+    driver.window().inject_close_request();
+    assert!(driver.window().is_closed());
+}
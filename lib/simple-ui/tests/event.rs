@@ -0,0 +1,162 @@
+use simple_ui::{input::EKey, testing::UIDriver, ui::*, IRect, ISize, Point, ScaleDpi};
+use std::{cell::RefCell, rc::Rc};
+
+type Log = Rc<RefCell<Vec<String>>>;
+
+struct Recorder {
+    name: &'static str,
+    log: Log,
+    stop_in: Option<EEventPhase>,
+}
+
+impl TEventHandler for Recorder {
+    fn on_event(&mut self, _ctx: &mut EventCtx, event: &mut UIEvent) {
+        let kind = match event.kind {
+            EUIEvent::MouseDown { .. } => "down",
+            EUIEvent::Click { .. } => "click",
+            EUIEvent::Wheel { .. } => "wheel",
+            EUIEvent::KeyDown(_) => "key",
+            _ => return,
+        };
+        self.log
+            .borrow_mut()
+            .push(format!("{}:{}:{:?}", kind, self.name, event.phase));
+
+        if self.stop_in == Some(event.phase) {
+            event.stop_propagation();
+        }
+    }
+}
+
+fn create(log: &Log, stop_in: Option<EEventPhase>) -> Container {
+    let recorder = |name, stop_in| {
+        Box::new(Recorder {
+            name,
+            log: log.clone(),
+            stop_in,
+        })
+    };
+
+    let mut root = Container::new(LayoutHorizontal::new());
+    root.ctrl.inner.name = "Root".to_string();
+    root.ctrl.inner.add_handler(recorder("Root", None));
+
+    let mut panel = Container::new(LayoutHorizontal::new());
+    panel.ctrl.inner.name = "Panel".to_string();
+    panel.ctrl.inner.padding = Some(IRect::new(10, 10, 10, 10));
+    panel.ctrl.inner.add_handler(recorder("Panel", stop_in));
+
+    let mut btn = Button::default();
+    btn.ctrl.inner.name = "Btn".to_string();
+    btn.ctrl.inner.add_handler(recorder("Btn", None));
+
+    panel.append_child(Box::new(btn));
+    root.append_child(Box::new(panel));
+    root
+}
+
+#[test]
+fn test_route() {
+    let log = Log::default();
+    let mut driver = UIDriver::new(create(&log, None), ISize::new(100, 50), ScaleDpi::new(1.));
+
+    driver.click("Btn");
+    assert_eq!(
+        *log.borrow(),
+        vec![
+            "down:Root:Capture",
+            "down:Panel:Capture",
+            "down:Btn:Target",
+            "down:Panel:Bubble",
+            "down:Root:Bubble",
+            "click:Root:Capture",
+            "click:Panel:Capture",
+            "click:Btn:Target",
+            "click:Panel:Bubble",
+            "click:Root:Bubble",
+        ]
+    );
+
+    log.borrow_mut().clear();
+    driver.window().inject_wheel(Point::new(0., -1.));
+    driver.press_key(EKey::Enter);
+    assert_eq!(
+        *log.borrow(),
+        vec![
+            "wheel:Root:Capture",
+            "wheel:Panel:Capture",
+            "wheel:Btn:Target",
+            "wheel:Panel:Bubble",
+            "wheel:Root:Bubble",
+            // the clicked button has the focus, Enter clicks it again
+            "key:Root:Capture",
+            "key:Panel:Capture",
+            "key:Btn:Target",
+            "click:Root:Capture",
+            "click:Panel:Capture",
+            "click:Btn:Target",
+            "click:Panel:Bubble",
+            "click:Root:Bubble",
+        ]
+    );
+}
+
+#[test]
+fn test_stop_propagation() {
+    let log = Log::default();
+    let mut driver = UIDriver::new(
+        create(&log, Some(EEventPhase::Capture)),
+        ISize::new(100, 50),
+        ScaleDpi::new(1.),
+    );
+
+    driver.click("Btn");
+    assert_eq!(
+        *log.borrow(),
+        vec![
+            "down:Root:Capture",
+            "down:Panel:Capture",
+            "click:Root:Capture",
+            "click:Panel:Capture",
+        ]
+    );
+}
+
+#[test]
+fn test_closure_handlers() {
+    let log = Log::default();
+    let mut root = create(&log, None);
+    {
+        let mut status = Ctrl::default();
+        status.inner.name = "Status".to_string();
+        root.append_child(Box::new(status));
+    }
+
+    let changes = Rc::new(RefCell::new(0));
+    {
+        let btn = root.get_mut_ctrl_by_name("Btn").unwrap().get_mut_inner();
+        btn.on_click(|ctx, event| {
+            let status = ctx.get_mut_ctrl_as::<Ctrl>("Status").unwrap();
+            status.styles.default.bg_color = Some(simple_ui::Color::RED);
+            ctx.redraw();
+            event.post(EUIEvent::Change);
+        });
+
+        let changes = changes.clone();
+        btn.on_change(move |ctx, _| {
+            *changes.borrow_mut() += 1;
+            ctx.close_window();
+        });
+    }
+
+    let mut driver = UIDriver::new(root, ISize::new(100, 50), ScaleDpi::new(1.));
+    driver.wait_redraw();
+    driver.click("Btn");
+
+    let status = driver.find("Status").unwrap();
+    let style = status.get_style(styles::EUIStatus::Default).unwrap();
+    assert_eq!(style.bg_color, Some(simple_ui::Color::RED));
+    assert_eq!(*changes.borrow(), 1);
+    assert!(driver.window().is_closed());
+    assert!(driver.wait_redraw());
+}
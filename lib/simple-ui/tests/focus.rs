@@ -0,0 +1,169 @@
+use as_any::Downcast;
+use simple_ui::{
+    input::{EKey, KeyModifiers},
+    testing::UIDriver,
+    ui::{styles::TStyle, *},
+    Color, ISize, ScaleDpi,
+};
+use std::{cell::RefCell, rc::Rc};
+
+type Log = Rc<RefCell<Vec<String>>>;
+
+fn button(name: &str, tab_index: Option<i32>, log: &Log) -> Box<Button> {
+    let mut btn = Button::default();
+    btn.ctrl.inner.name = name.to_string();
+    btn.ctrl.inner.tab_index = tab_index;
+
+    let (log, name) = (log.clone(), name.to_string());
+    btn.ctrl
+        .inner
+        .add_handler(Box::new(move |_: &mut EventCtx, event: &mut UIEvent| {
+            if !event.is_target() {
+                return;
+            }
+            let kind = match event.kind {
+                EUIEvent::FocusIn => "in".to_string(),
+                EUIEvent::FocusOut => "out".to_string(),
+                EUIEvent::Char(c) => format!("char {}", c),
+                _ => return,
+            };
+            log.borrow_mut().push(format!("{}:{}", kind, name));
+        }));
+    Box::new(btn)
+}
+
+// A B | C D, D is disabled
+fn create(log: &Log, tab_indexes: [Option<i32>; 3]) -> Container {
+    let mut root = Container::new(LayoutVertical::new());
+    root.ctrl.inner.name = "Root".to_string();
+
+    let mut top = Container::new(LayoutHorizontal::new());
+    top.append_child(button("A", tab_indexes[0], log));
+    top.append_child(button("B", tab_indexes[1], log));
+    root.append_child(Box::new(top));
+
+    let mut bottom = Container::new(LayoutHorizontal::new());
+    bottom.append_child(button("C", tab_indexes[2], log));
+    let mut d = button("D", None, log);
+    d.ctrl.status = ECtrlStatus::Disable;
+    bottom.append_child(d);
+    root.append_child(Box::new(bottom));
+
+    root
+}
+
+fn focused(driver: &mut UIDriver) -> String {
+    match driver.window().get_focused_ctrl() {
+        Some(c) => c.get_inner().name.clone(),
+        None => String::new(),
+    }
+}
+
+#[test]
+fn test_click_to_focus() {
+    let log = Log::default();
+    let mut driver = UIDriver::new(
+        create(&log, [None; 3]),
+        ISize::new(100, 100),
+        ScaleDpi::new(1.),
+    );
+
+    driver.click("B").assert_focused("B");
+    driver.click("C").assert_focused("C");
+    assert_eq!(*log.borrow(), vec!["in:B", "out:B", "in:C"]);
+
+    // neither a disabled control nor the root container takes it
+    driver.click("D");
+    assert_eq!(focused(&mut driver), "");
+    driver.click("A");
+    driver.window().set_focus(None);
+    assert_eq!(focused(&mut driver), "");
+}
+
+#[test]
+fn test_tab_order() {
+    let log = Log::default();
+    let mut driver = UIDriver::new(
+        create(&log, [None; 3]),
+        ISize::new(100, 100),
+        ScaleDpi::new(1.),
+    );
+
+    let mut order = Vec::new();
+    for _ in 0..4 {
+        driver.press_key(EKey::Tab);
+        order.push(focused(&mut driver));
+    }
+    assert_eq!(order, vec!["A", "B", "C", "A"]);
+
+    let shift = KeyModifiers {
+        shift: true,
+        ..Default::default()
+    };
+    driver.press_key_with(EKey::Tab, shift).assert_focused("C");
+}
+
+#[test]
+fn test_tab_index() {
+    let log = Log::default();
+    let mut driver = UIDriver::new(
+        create(&log, [Some(-1), None, Some(1)]),
+        ISize::new(100, 100),
+        ScaleDpi::new(1.),
+    );
+
+    let mut order = Vec::new();
+    for _ in 0..3 {
+        driver.press_key(EKey::Tab);
+        order.push(focused(&mut driver));
+    }
+    assert_eq!(order, vec!["C", "B", "C"]);
+
+    // a negative index is still focusable by click
+    driver.click("A").assert_focused("A");
+}
+
+#[test]
+fn test_keys_go_to_focused() {
+    let log = Log::default();
+    let mut driver = UIDriver::new(
+        create(&log, [None; 3]),
+        ISize::new(100, 100),
+        ScaleDpi::new(1.),
+    );
+
+    driver.type_text("B", "x");
+    driver.press_key(EKey::Tab);
+    driver.window().inject_char('y');
+    assert_eq!(
+        *log.borrow(),
+        vec!["in:B", "char x:B", "out:B", "in:C", "char y:C"]
+    );
+}
+
+#[test]
+fn test_focused_style() {
+    let log = Log::default();
+    let mut root = create(&log, [None; 3]);
+    {
+        let a = root.get_mut_ctrl_by_name("A").unwrap();
+        let a = a.downcast_mut::<Button>().unwrap();
+        let mut focused = ui_button::ButtonStyle::default();
+        focused.get_mut_inner().bg_color = Some(Color::BLUE);
+        a.ctrl.styles.focused = Some(focused);
+    }
+    let mut driver = UIDriver::new(root, ISize::new(100, 100), ScaleDpi::new(1.));
+
+    let bg_color = |driver: &UIDriver| {
+        let a = driver.find("A").unwrap().downcast_ref::<Button>().unwrap();
+        a.ctrl.get_cur_style().get_inner().bg_color
+    };
+
+    assert_eq!(bg_color(&driver), None);
+    driver.press_key(EKey::Tab).assert_redraw();
+    assert_eq!(bg_color(&driver), Some(Color::BLUE));
+
+    // hover still wins over focus
+    driver.hover("A");
+    assert_eq!(bg_color(&driver), None);
+}
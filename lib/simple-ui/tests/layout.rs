@@ -0,0 +1,346 @@
+use simple_ui::{testing::UIDriver, ui::*, EUnit, IRect, ISize, ScaleDpi};
+
+fn new_ctrl(name: &str, width: Option<i32>, height: Option<i32>) -> Box<dyn TCtrl> {
+    let mut ctrl = Ctrl::default();
+    ctrl.inner.name = name.to_string();
+    ctrl.inner.size.width = width.map(EUnit::Dp);
+    ctrl.inner.size.height = height.map(EUnit::Dp);
+    Box::new(ctrl)
+}
+
+fn new_text(name: &str, text: &str) -> Box<dyn TCtrl> {
+    let mut t = Text::new(text, 14);
+    t.ctrl.inner.name = name.to_string();
+    t.ctrl.inner.auto_size = true;
+    Box::new(t)
+}
+
+#[test]
+fn test_auto_size() {
+    let mut root = Container::new(LayoutHorizontal::new());
+    root.ctrl.inner.name = "Root".to_string();
+    root.append_child(new_text("Short", "ab"));
+    root.append_child(new_text("Long", "abcdefgh"));
+    let mut ok = Button::new("OK");
+    ok.ctrl.inner.name = "OK".to_string();
+    ok.ctrl.inner.auto_size = true;
+    ok.ctrl.inner.padding = Some(IRect::new(4, 2, 4, 2));
+    root.append_child(Box::new(ok));
+    root.append_child(new_ctrl("Rest", None, None));
+
+    let mut driver = UIDriver::new(root, ISize::new(300, 40), ScaleDpi::new(2.));
+    let short = driver.rect("Short");
+    let long = driver.rect("Long");
+    let ok = driver.rect("OK");
+    let rest = driver.rect("Rest");
+
+    assert!(short.width() > 0 && short.width() < long.width());
+    assert!(short.height() < 40, "as tall as the text");
+    assert_eq!(long.left, short.right);
+    // the label with the padding around it
+    assert_eq!(ok.left, long.right);
+    assert!(ok.width() > 16 && ok.height() > 8 && ok.height() < 40);
+    // what the others leave is shared as before
+    assert_eq!(rest.left, ok.right);
+    assert_eq!(rest.right, 300);
+}
+
+#[test]
+fn test_auto_size_nested() {
+    let mut root = Container::new(LayoutVertical::new());
+    root.ctrl.inner.name = "Root".to_string();
+    {
+        let mut bar = Container::new(LayoutHorizontal::new());
+        bar.ctrl.inner.name = "Bar".to_string();
+        bar.ctrl.inner.auto_size = true;
+        bar.ctrl.inner.padding = Some(IRect::new(2, 3, 2, 3));
+        bar.append_child(new_ctrl("Icon", Some(16), Some(16)));
+        bar.append_child(new_text("Title", "title"));
+        root.append_child(Box::new(bar));
+    }
+    root.append_child(new_ctrl("Body", None, None));
+
+    let mut driver = UIDriver::new(root, ISize::new(200, 100), ScaleDpi::new(1.));
+    let bar = driver.rect("Bar");
+    assert!(bar.height() >= 16 + 6);
+    assert_eq!(driver.rect("Body").top, bar.bottom);
+    assert_eq!(driver.rect("Body").bottom, 100);
+}
+
+#[test]
+fn test_min_max_size() {
+    let mut root = Container::new(LayoutHorizontal::new());
+    root.ctrl.inner.name = "Root".to_string();
+    {
+        let mut side = Ctrl::default();
+        side.inner.name = "Side".to_string();
+        side.inner.min_size.width = Some(EUnit::Dp(80));
+        side.inner.max_size.height = Some(EUnit::Dp(30));
+        root.append_child(Box::new(side));
+    }
+    root.append_child(new_ctrl("Main", None, None));
+    {
+        let mut tail = Ctrl::default();
+        tail.inner.name = "Tail".to_string();
+        tail.inner.max_size.width = Some(EUnit::Dp(20));
+        root.append_child(Box::new(tail));
+    }
+
+    // a third each would be 40, the side keeps 80 and the main gets what is left
+    let mut driver = UIDriver::new(root, ISize::new(120, 40), ScaleDpi::new(1.));
+    assert_eq!(driver.rect("Side"), IRect::new(0, 0, 80, 30));
+    assert_eq!(driver.rect("Main"), IRect::new(80, 0, 100, 40));
+    assert_eq!(driver.rect("Tail"), IRect::new(100, 0, 120, 40));
+
+    let mut root = Container::new(Layout::new());
+    root.ctrl.inner.name = "Root".to_string();
+    {
+        let mut ctrl = Ctrl::default();
+        ctrl.inner.name = "Abs".to_string();
+        ctrl.inner.pos.left = Some(EUnit::Dp(10));
+        ctrl.inner.size.width = Some(EUnit::Dp(10));
+        ctrl.inner.size.height = Some(EUnit::Dp(90));
+        ctrl.inner.min_size.width = Some(EUnit::Dp(30));
+        ctrl.inner.max_size.height = Some(EUnit::Dp(20));
+        root.append_child(Box::new(ctrl));
+    }
+    let mut driver = UIDriver::new(root, ISize::new(100, 100), ScaleDpi::new(1.));
+    assert_eq!(driver.rect("Abs"), IRect::new(10, 0, 40, 20));
+}
+
+fn unit_ctrl(name: &str, width: EUnit) -> Box<dyn TCtrl> {
+    let mut ctrl = Ctrl::default();
+    ctrl.inner.name = name.to_string();
+    ctrl.inner.size.width = Some(width);
+    Box::new(ctrl)
+}
+
+#[test]
+fn test_units() {
+    let mut root = Container::new(LayoutHorizontal::new());
+    root.ctrl.inner.name = "Root".to_string();
+    root.append_child(unit_ctrl("Side", EUnit::Percent(20.)));
+    root.append_child(unit_ctrl("Line", EUnit::Px(60)));
+    root.append_child(unit_ctrl("One", EUnit::Star(1.)));
+    root.append_child(unit_ctrl("Two", EUnit::Star(2.)));
+
+    // 600 physical pixels: 120 for 20%, 60 raw, and 420 split 1:2
+    let mut driver = UIDriver::new(root, ISize::new(300, 40), ScaleDpi::new(2.));
+    assert_eq!(driver.rect("Side"), IRect::new(0, 0, 60, 40));
+    assert_eq!(driver.rect("Line"), IRect::new(60, 0, 90, 40));
+    assert_eq!(driver.rect("One"), IRect::new(90, 0, 160, 40));
+    assert_eq!(driver.rect("Two"), IRect::new(160, 0, 300, 40));
+
+    let mut root = Container::new(Layout::new());
+    root.ctrl.inner.name = "Root".to_string();
+    {
+        let mut ctrl = Ctrl::default();
+        ctrl.inner.name = "Abs".to_string();
+        ctrl.inner.pos.left = Some(EUnit::Percent(50.));
+        ctrl.inner.size.width = Some(EUnit::Percent(25.));
+        ctrl.inner.size.height = Some(EUnit::Dp(10));
+        root.append_child(Box::new(ctrl));
+    }
+    let mut driver = UIDriver::new(root, ISize::new(300, 100), ScaleDpi::new(1.));
+    assert_eq!(driver.rect("Abs"), IRect::new(150, 0, 225, 10));
+}
+
+fn flex_item(name: &str, width: Option<i32>, grow: f32) -> Box<dyn TCtrl> {
+    let mut ctrl = Ctrl::default();
+    ctrl.inner.name = name.to_string();
+    ctrl.inner.size.width = width.map(EUnit::Dp);
+    ctrl.inner.size.height = Some(EUnit::Dp(10));
+    ctrl.inner.flex.grow = grow;
+    Box::new(ctrl)
+}
+
+#[test]
+fn test_flex_grow_and_justify() {
+    let mut root = Container::new(Box::new(LayoutFlex {
+        gap: 10,
+        align_items: EAlignItems::Center,
+        ..Default::default()
+    }));
+    root.ctrl.inner.name = "Root".to_string();
+    root.append_child(flex_item("A", Some(50), 0.));
+    root.append_child(flex_item("B", Some(20), 1.));
+    root.append_child(flex_item("C", Some(20), 2.));
+
+    let mut driver = UIDriver::new(root, ISize::new(200, 40), ScaleDpi::new(1.));
+    // 200 - 90 - 20 of gaps leaves 90, B and C take 1:2 of it
+    assert_eq!(driver.rect("A"), IRect::new(0, 15, 50, 25));
+    assert_eq!(driver.rect("B"), IRect::new(60, 15, 110, 25));
+    assert_eq!(driver.rect("C"), IRect::new(120, 15, 200, 25));
+
+    let mut root = Container::new(Box::new(LayoutFlex {
+        direction: EFlexDirection::RowReverse,
+        justify: EJustify::SpaceBetween,
+        align_items: EAlignItems::Start,
+        ..Default::default()
+    }));
+    root.ctrl.inner.name = "Root".to_string();
+    root.append_child(flex_item("A", Some(50), 0.));
+    root.append_child(flex_item("B", Some(50), 0.));
+
+    let mut driver = UIDriver::new(root, ISize::new(200, 40), ScaleDpi::new(1.));
+    assert_eq!(driver.rect("A"), IRect::new(150, 0, 200, 10));
+    assert_eq!(driver.rect("B"), IRect::new(0, 0, 50, 10));
+}
+
+#[test]
+fn test_flex_wrap_and_shrink() {
+    let mut root = Container::new(Box::new(LayoutFlex {
+        wrap: EFlexWrap::Wrap,
+        ..Default::default()
+    }));
+    root.ctrl.inner.name = "Root".to_string();
+    for name in ["A", "B", "C"] {
+        root.append_child(flex_item(name, Some(80), 0.));
+    }
+
+    let mut driver = UIDriver::new(root, ISize::new(200, 100), ScaleDpi::new(1.));
+    assert_eq!(driver.rect("B").top, 0);
+    assert_eq!(driver.rect("C").left, 0);
+    assert!(driver.rect("C").top >= 10);
+
+    let mut root = Container::new(LayoutFlex::new(EFlexDirection::Row));
+    root.ctrl.inner.name = "Root".to_string();
+    root.append_child(flex_item("A", Some(150), 0.));
+    root.append_child(flex_item("B", Some(150), 0.));
+    {
+        let mut fixed = Ctrl::default();
+        fixed.inner.name = "Fixed".to_string();
+        fixed.inner.size.width = Some(EUnit::Dp(100));
+        fixed.inner.flex.shrink = 0.;
+        root.append_child(Box::new(fixed));
+    }
+
+    let mut driver = UIDriver::new(root, ISize::new(300, 40), ScaleDpi::new(1.));
+    assert_eq!(driver.rect("A").width(), 100);
+    assert_eq!(driver.rect("B").width(), 100);
+    // no height and stretched
+    assert_eq!(driver.rect("Fixed"), IRect::new(200, 0, 300, 40));
+}
+
+#[test]
+fn test_flex_stretch_nested() {
+    let mut root = Container::new(Box::new(LayoutFlex::default()));
+    root.ctrl.inner.name = "Root".to_string();
+    {
+        // has a content size, still stretched as it sets no height
+        let mut panel = Container::new(LayoutVertical::new());
+        panel.ctrl.inner.name = "Panel".to_string();
+        panel.ctrl.inner.size.width = Some(EUnit::Dp(100));
+        panel.append_child(new_ctrl("Inner", None, None));
+        root.append_child(Box::new(panel));
+    }
+
+    let mut driver = UIDriver::new(root, ISize::new(200, 40), ScaleDpi::new(1.));
+    assert_eq!(driver.rect("Panel"), IRect::new(0, 0, 100, 40));
+    assert_eq!(driver.rect("Inner"), IRect::new(0, 0, 100, 40));
+}
+
+#[test]
+fn test_grid() {
+    let mut root = Container::new(Box::new(LayoutGrid {
+        rows: vec![ETrack::Fixed(20), ETrack::Fraction(1.)],
+        cols: vec![
+            ETrack::Fixed(60),
+            ETrack::Fraction(1.),
+            ETrack::Fraction(2.),
+        ],
+        row_gap: 4,
+        col_gap: 10,
+        ..Default::default()
+    }));
+    root.ctrl.inner.name = "Root".to_string();
+    root.append_child(new_ctrl("A", None, None));
+    {
+        let mut wide = Ctrl::default();
+        wide.inner.name = "Wide".to_string();
+        wide.inner.grid.col_span = 2;
+        root.append_child(Box::new(wide));
+    }
+    {
+        let mut small = Ctrl::default();
+        small.inner.name = "Small".to_string();
+        small.inner.size.width = Some(EUnit::Dp(20));
+        small.inner.size.height = Some(EUnit::Dp(10));
+        small.inner.grid.row = Some(1);
+        small.inner.grid.col = Some(2);
+        small.inner.grid.align_x = Some(EAlignItems::Center);
+        small.inner.grid.align_y = Some(EAlignItems::End);
+        root.append_child(Box::new(small));
+    }
+    // auto placed after the wide one, on the next row
+    root.append_child(new_ctrl("Next", None, None));
+
+    let mut driver = UIDriver::new(root, ISize::new(250, 100), ScaleDpi::new(2.));
+    // 250 - 60 - 20 of gaps leaves 170, 1:2 of it
+    assert_eq!(driver.rect("A"), IRect::new(0, 0, 60, 20));
+    assert_eq!(driver.rect("Wide"), IRect::new(70, 0, 250, 20));
+    assert_eq!(driver.rect("Small"), IRect::new(183, 90, 203, 100));
+    assert_eq!(driver.rect("Next"), IRect::new(0, 24, 60, 100));
+}
+
+#[test]
+fn test_table() {
+    let mut root = Container::new(layout::LayoutTable::new(2, 2));
+    root.ctrl.inner.name = "Root".to_string();
+    root.ctrl.inner.padding = Some(IRect::new(10, 10, 10, 10));
+    for name in ["A", "B", "C", "D", "E"] {
+        root.append_child(new_ctrl(name, None, None));
+    }
+
+    let mut driver = UIDriver::new(root, ISize::new(120, 120), ScaleDpi::new(2.));
+    assert_eq!(driver.rect("A"), IRect::new(10, 10, 60, 60));
+    assert_eq!(driver.rect("D"), IRect::new(60, 60, 110, 110));
+    assert_eq!(driver.rect("E"), IRect::default(), "past the last row");
+}
+
+#[test]
+fn test_table_stretch() {
+    let mut root = Container::new(layout::LayoutTable::new(1, 2));
+    root.ctrl.inner.name = "Root".to_string();
+    let mut text = Text::new("Hi", 14);
+    text.ctrl.inner.name = "Text".to_string();
+    root.append_child(Box::new(text));
+    let mut panel = Container::new(LayoutVertical::new());
+    panel.ctrl.inner.name = "Panel".to_string();
+    panel.append_child(new_ctrl("Inner", None, None));
+    root.append_child(Box::new(panel));
+
+    // equal cells whatever the children prefer
+    let mut driver = UIDriver::new(root, ISize::new(200, 40), ScaleDpi::new(1.));
+    driver
+        .assert_rect("Text", IRect::new(0, 0, 100, 40))
+        .assert_rect("Panel", IRect::new(100, 0, 200, 40))
+        .assert_rect("Inner", IRect::new(100, 0, 200, 40));
+}
+
+#[test]
+fn test_flex_min_max_size() {
+    let mut root = Container::new(Box::new(LayoutFlex::default()));
+    root.ctrl.inner.name = "Root".to_string();
+    let mut a = flex_item("A", None, 1.);
+    a.get_mut_inner().max_size.width = Some(EUnit::Dp(50));
+    root.append_child(a);
+    root.append_child(flex_item("B", None, 1.));
+
+    // A stops growing at 50 and B takes the rest
+    let mut driver = UIDriver::new(root, ISize::new(200, 20), ScaleDpi::new(1.));
+    assert_eq!(driver.rect("A").width(), 50);
+    assert_eq!(driver.rect("B"), IRect::new(50, 0, 200, 10));
+
+    let mut root = Container::new(Box::new(LayoutFlex::default()));
+    root.ctrl.inner.name = "Root".to_string();
+    let mut a = flex_item("A", Some(100), 0.);
+    a.get_mut_inner().min_size.width = Some(EUnit::Dp(80));
+    root.append_child(a);
+    root.append_child(flex_item("B", Some(100), 0.));
+
+    // both would shrink to 60, A stops at 80
+    let mut driver = UIDriver::new(root, ISize::new(120, 20), ScaleDpi::new(1.));
+    assert_eq!(driver.rect("A").width(), 80);
+    assert_eq!(driver.rect("B"), IRect::new(80, 0, 120, 10));
+}
@@ -0,0 +1,154 @@
+use as_any::Downcast;
+use simple_ui::{
+    input::{EKey, KeyModifiers},
+    testing::UIDriver,
+    ui::*,
+    IRect, ISize, Point, ScaleDpi,
+};
+use std::{cell::Cell, rc::Rc};
+
+struct Source {
+    count: usize,
+    created: Rc<Cell<u32>>,
+}
+
+impl TListSource for Source {
+    fn get_count(&self) -> usize {
+        self.count
+    }
+    fn get_row_height(&self) -> i32 {
+        20
+    }
+    fn create_row(&mut self) -> Box<dyn TCtrl> {
+        self.created.set(self.created.get() + 1);
+        Box::new(Ctrl::default())
+    }
+    fn update_row(&mut self, row: &mut dyn TCtrl, index: usize) {
+        row.get_mut_inner().name = format!("Item{}", index);
+    }
+}
+
+#[derive(Default)]
+struct Record {
+    created: Rc<Cell<u32>>,
+    changes: Rc<Cell<u32>>,
+    activated: Rc<Cell<Option<usize>>>,
+}
+
+fn create(count: usize, multi_select: bool, record: &Record) -> UIDriver {
+    let mut root = Container::new(LayoutVertical::new());
+    root.ctrl.inner.name = "Root".to_string();
+
+    let mut list = ListView::new(Box::new(Source {
+        count,
+        created: record.created.clone(),
+    }));
+    list.ctrl.inner.name = "List".to_string();
+    list.multi_select = multi_select;
+    {
+        let changes = record.changes.clone();
+        list.ctrl
+            .inner
+            .on_selection_changed(move |_, _| changes.set(changes.get() + 1));
+        let activated = record.activated.clone();
+        list.ctrl.inner.on_item_activate(move |_, e| {
+            if let EUIEvent::ItemActivate(i) = e.kind {
+                activated.set(Some(i));
+            }
+        });
+    }
+    root.append_child(Box::new(list));
+
+    UIDriver::new(root, ISize::new(200, 100), ScaleDpi::new(1.))
+}
+
+fn list(driver: &mut UIDriver) -> &mut ListView {
+    let w = driver.window();
+    w.get_mut_ctrl_by_name("List")
+        .unwrap()
+        .downcast_mut::<ListView>()
+        .unwrap()
+}
+
+fn command() -> KeyModifiers {
+    KeyModifiers {
+        ctrl: !cfg!(target_os = "macos"),
+        logo: cfg!(target_os = "macos"),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_virtual_rows() {
+    let record = Record::default();
+    let mut driver = create(1_000_000, false, &record);
+
+    // 5 rows fit, 2 more for the partly shown ones while scrolling
+    driver
+        .assert_rect("Item0", IRect::new(0, 0, 190, 20))
+        .assert_visible("Item4", true);
+    assert!(driver.find("Item5").is_none());
+    assert_eq!(record.created.get(), 7);
+
+    driver.hover("Item0");
+    driver.window().inject_wheel(Point::new(0., -30.));
+    driver
+        .assert_rect("Item1", IRect::new(0, -10, 190, 10))
+        .assert_rect("Item5", IRect::new(0, 70, 190, 90));
+
+    assert!(list(&mut driver).scroll_to_item(999_999));
+    assert_eq!(list(&mut driver).get_offset(), 19_999_900);
+    assert_eq!(list(&mut driver).get_visible_range(), 999_995..1_000_000);
+    driver.assert_rect("Item999999", IRect::new(0, 80, 190, 100));
+    assert_eq!(record.created.get(), 7);
+}
+
+#[test]
+fn test_select() {
+    let record = Record::default();
+    let mut driver = create(100, true, &record);
+
+    driver.click("Item1");
+    assert_eq!(list(&mut driver).get_selection(), vec![1]);
+    driver.assert_focused("List");
+
+    driver.window().inject_modifiers(command());
+    driver.click("Item3");
+    assert_eq!(list(&mut driver).get_selection(), vec![1, 3]);
+
+    // extends from the last Ctrl click
+    driver.window().inject_modifiers(KeyModifiers {
+        shift: true,
+        ..Default::default()
+    });
+    driver.click("Item0");
+    driver.window().inject_modifiers(KeyModifiers::default());
+    assert_eq!(list(&mut driver).get_selection(), vec![0, 1, 2, 3]);
+    assert_eq!(list(&mut driver).get_current(), Some(0));
+    assert_eq!(record.changes.get(), 3);
+
+    driver.press_key(EKey::Down);
+    assert_eq!(list(&mut driver).get_selection(), vec![1]);
+    driver.press_key(EKey::End);
+    assert_eq!(list(&mut driver).get_selection(), vec![99]);
+    assert_eq!(list(&mut driver).get_offset(), 1900);
+    driver.press_key_with(EKey::Char('a'), command());
+    assert_eq!(list(&mut driver).get_selection().len(), 100);
+    assert_eq!(record.changes.get(), 6);
+
+    driver.press_key(EKey::Enter);
+    assert_eq!(record.activated.get(), Some(99));
+}
+
+#[test]
+fn test_double_click() {
+    let record = Record::default();
+    let mut driver = create(100, false, &record);
+
+    driver.click("Item2");
+    assert_eq!(record.activated.get(), None);
+    driver.click("Item2");
+    assert_eq!(record.activated.get(), Some(2));
+    assert_eq!(list(&mut driver).get_selection(), vec![2]);
+    assert_eq!(record.changes.get(), 1);
+}
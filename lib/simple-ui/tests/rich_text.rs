@@ -0,0 +1,64 @@
+use as_any::Downcast;
+use simple_ui::{testing::UIDriver, ui::*, Color, IPoint, ISize, ScaleDpi};
+use std::{cell::RefCell, rc::Rc};
+
+fn create(links: &Rc<RefCell<Vec<String>>>) -> UIDriver {
+    let mut root = Container::new(LayoutVertical::new());
+    root.ctrl.inner.name = "Root".to_string();
+
+    let mut rich = RichText::new(14);
+    rich.ctrl.inner.name = "Help".to_string();
+    rich.set_spans(vec![
+        TextSpan::new("docs").link("https://example.com/docs"),
+        TextSpan::new(" and "),
+        TextSpan::new("bold").bold().color(Color::RED),
+        TextSpan::new(" text")
+            .italic()
+            .underline()
+            .background(Color::YELLOW),
+    ]);
+    {
+        let links = links.clone();
+        rich.ctrl.inner.on_link(move |_, e| {
+            if let EUIEvent::Link(url) = &e.kind {
+                links.borrow_mut().push(url.clone());
+            }
+        });
+    }
+    root.append_child(Box::new(rich));
+
+    UIDriver::new(root, ISize::new(300, 100), ScaleDpi::new(1.))
+}
+
+fn rich(driver: &mut UIDriver) -> &mut RichText {
+    let w = driver.window();
+    w.get_mut_ctrl_by_name("Help")
+        .unwrap()
+        .downcast_mut::<RichText>()
+        .unwrap()
+}
+
+#[test]
+fn test_spans_and_links() {
+    let links = Rc::new(RefCell::new(Vec::new()));
+    let mut driver = create(&links);
+    driver.assert_text("Help", "docs and bold text");
+
+    let rc = driver.rect("Help");
+    driver
+        .window()
+        .inject_click(IPoint::new(rc.left + 2, rc.top + 8));
+    assert_eq!(*links.borrow(), vec!["https://example.com/docs"]);
+
+    // only the link is clickable
+    driver
+        .window()
+        .inject_click(IPoint::new(rc.right - 2, rc.bottom - 2));
+    assert_eq!(links.borrow().len(), 1);
+
+    rich(&mut driver).append(TextSpan::new(" more"));
+    driver.assert_text("Help", "docs and bold text more");
+    let one = rich(&mut driver).measure(None);
+    let wrapped = rich(&mut driver).measure(Some(one.width / 2));
+    assert!(wrapped.height > one.height);
+}
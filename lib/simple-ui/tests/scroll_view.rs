@@ -0,0 +1,98 @@
+use as_any::Downcast;
+use simple_ui::{
+    input::EMouseButton, testing::UIDriver, ui::*, EUnit, IPoint, IRect, ISize, Point, ScaleDpi,
+};
+use std::{cell::Cell, rc::Rc};
+
+fn create(changes: &Rc<Cell<u32>>) -> UIDriver {
+    let mut root = Container::new(LayoutVertical::new());
+    root.ctrl.inner.name = "Root".to_string();
+
+    let mut view = ScrollView::new(LayoutVertical::new());
+    view.ctrl.inner.name = "View".to_string();
+    for i in 0..10 {
+        let mut item = Ctrl::default();
+        item.inner.name = format!("Item{}", i);
+        item.inner.size.height = Some(EUnit::Dp(30));
+        view.append_child(Box::new(item));
+    }
+    {
+        let changes = changes.clone();
+        view.ctrl
+            .inner
+            .on_change(move |_, _| changes.set(changes.get() + 1));
+    }
+    root.append_child(Box::new(view));
+
+    UIDriver::new(root, ISize::new(200, 100), ScaleDpi::new(1.))
+}
+
+fn view(driver: &mut UIDriver) -> &mut ScrollView {
+    let w = driver.window();
+    w.get_mut_ctrl_by_name("View")
+        .unwrap()
+        .downcast_mut::<ScrollView>()
+        .unwrap()
+}
+
+#[test]
+fn test_scroll_view() {
+    let changes = Rc::new(Cell::new(0));
+    let mut driver = create(&changes);
+
+    // the vertical bar takes 10 pixels on the right
+    driver
+        .assert_rect("View/Item0", IRect::new(0, 0, 190, 30))
+        .assert_visible("Item3", true)
+        .assert_visible("Item4", false);
+    assert_eq!(view(&mut driver).get_content_size(), ISize::new(190, 300));
+    assert_eq!(view(&mut driver).get_max_offset(), IPoint::new(0, 200));
+
+    driver.hover("Item0");
+    driver.window().inject_wheel(Point::new(0., -45.));
+    driver.assert_rect("Item0", IRect::new(0, -45, 190, -15));
+    assert_eq!(changes.get(), 1);
+
+    assert!(view(&mut driver).scroll_into_view("Item9"));
+    assert_eq!(view(&mut driver).get_offset(), IPoint::new(0, 200));
+    driver.assert_visible("Item9", true);
+    assert!(!view(&mut driver).scroll_into_view("Item9"));
+
+    // the end is reached, the wheel does nothing
+    driver.window().inject_wheel(Point::new(0., -45.));
+    assert_eq!(changes.get(), 1);
+}
+
+#[test]
+fn test_scroll_bar_input() {
+    let changes = Rc::new(Cell::new(0));
+    let mut driver = create(&changes);
+
+    // over the bar the view itself is hit
+    let w = driver.window();
+    assert_eq!(
+        w.get_ctrl_by_pos(&IPoint::new(195, 50))
+            .unwrap()
+            .get_inner()
+            .name,
+        "View"
+    );
+    assert_eq!(
+        view(&mut driver).get_vbar().get_thumb_rc(),
+        IRect::new(190, 0, 200, 33)
+    );
+
+    // dragged past the end of the track
+    let w = driver.window();
+    w.inject_mouse_down(IPoint::new(195, 10), EMouseButton::Left);
+    w.inject_mouse_move(IPoint::new(195, 40));
+    w.inject_mouse_move(IPoint::new(195, 150));
+    w.inject_mouse_up(IPoint::new(195, 150), EMouseButton::Left);
+    assert_eq!(view(&mut driver).get_offset(), IPoint::new(0, 200));
+    assert_eq!(changes.get(), 2);
+
+    // above the thumb is a page up
+    driver.window().inject_click(IPoint::new(195, 10));
+    assert_eq!(view(&mut driver).get_offset(), IPoint::new(0, 100));
+    driver.assert_rect("Item0", IRect::new(0, -100, 190, -70));
+}
@@ -0,0 +1,79 @@
+use simple_ui::{testing::Snapshot, ui::*, Color, EUnit, IRect, ISize, ScaleDpi};
+
+fn snapshot() -> Snapshot {
+    Snapshot::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots")).tolerance(2)
+}
+
+fn new_ctrl(name: &str, width: Option<i32>, height: Option<i32>, c: Color) -> Box<dyn TCtrl> {
+    let mut ctrl = Ctrl::default();
+    ctrl.inner.name = name.to_string();
+    ctrl.inner.size.width = width.map(EUnit::Dp);
+    ctrl.inner.size.height = height.map(EUnit::Dp);
+    ctrl.styles.default.bg_color = Some(c);
+
+    Box::new(ctrl)
+}
+
+fn new_root(layout: Box<dyn TLayout>) -> Container {
+    let mut root = Container::new(layout);
+    root.ctrl.inner.name = "Root".to_string();
+    root.ctrl.inner.margin = Some(IRect::new(4, 4, 4, 4));
+    root.ctrl.inner.padding = Some(IRect::new(6, 6, 6, 6));
+    // square corners, no anti-aliased edges in the reference images
+    root.ctrl.inner.border_width = 2;
+    root.ctrl.styles.default.border_color = Some(Color::DARK_GRAY);
+    root.ctrl.styles.default.bg_color = Some(Color::WHITE);
+
+    root
+}
+
+#[test]
+fn test_layout_horizontal() {
+    let mut root = new_root(LayoutHorizontal::new());
+    root.append_child(new_ctrl("A", Some(40), None, Color::RED));
+    root.append_child(new_ctrl("B", None, Some(30), Color::GREEN));
+    root.append_child(new_ctrl("C", None, None, Color::BLUE));
+
+    snapshot().assert_container(
+        "layout_horizontal",
+        &mut root,
+        ISize::new(200, 100),
+        ScaleDpi::new(2.),
+    );
+}
+
+#[test]
+fn test_layout_vertical() {
+    let mut root = new_root(LayoutVertical::new());
+    root.append_child(new_ctrl("A", None, Some(20), Color::RED));
+    root.append_child(new_ctrl("B", Some(50), None, Color::GREEN));
+    root.append_child(new_ctrl("C", None, None, Color::BLUE));
+
+    snapshot().assert_container(
+        "layout_vertical",
+        &mut root,
+        ISize::new(100, 200),
+        ScaleDpi::new(2.),
+    );
+}
+
+#[test]
+fn test_layout_nested() {
+    let mut root = new_root(LayoutVertical::new());
+    {
+        let mut bar = Container::new(LayoutHorizontal::new());
+        bar.ctrl.inner.size.height = Some(EUnit::Dp(20));
+        bar.ctrl.styles.default.bg_color = Some(Color::LIGHT_GRAY);
+        bar.append_child(new_ctrl("Bar_A", Some(30), None, Color::YELLOW));
+        bar.append_child(new_ctrl("Bar_B", None, None, Color::CYAN));
+        root.append_child(Box::new(bar));
+    }
+    root.append_child(new_ctrl("Ctx", None, None, Color::MAGENTA));
+
+    snapshot().assert_container(
+        "layout_nested",
+        &mut root,
+        ISize::new(160, 120),
+        ScaleDpi::new(1.),
+    );
+}
@@ -0,0 +1,83 @@
+use as_any::Downcast;
+use simple_ui::{testing::UIDriver, ui::*, ISize, ScaleDpi};
+
+fn create(text: &str, setup: impl FnOnce(&mut Text)) -> UIDriver {
+    let mut root = Container::new(LayoutVertical::new());
+    root.ctrl.inner.name = "Root".to_string();
+
+    let mut t = Text::new(text, 14);
+    t.ctrl.inner.name = "Label".to_string();
+    setup(&mut t);
+    root.append_child(Box::new(t));
+
+    UIDriver::new(root, ISize::new(100, 200), ScaleDpi::new(1.))
+}
+
+fn text(driver: &mut UIDriver) -> &mut Text {
+    let w = driver.window();
+    w.get_mut_ctrl_by_name("Label")
+        .unwrap()
+        .downcast_mut::<Text>()
+        .unwrap()
+}
+
+#[test]
+fn test_wrap_and_newlines() {
+    let long = "some words ".repeat(10);
+    let mut driver = create(&long, |_| {});
+    assert_eq!(text(&mut driver).get_line_count(), 1);
+
+    text(&mut driver).set_wrap(EWrapMode::Word);
+    let rows = text(&mut driver).get_line_count();
+    assert!(rows > 1, "a long line wraps");
+    text(&mut driver).set_wrap(EWrapMode::Char);
+    assert!(text(&mut driver).get_line_count() <= rows);
+
+    text(&mut driver).set_max_lines(Some(2));
+    assert_eq!(text(&mut driver).get_line_count(), 2);
+
+    // explicit newlines count without wrapping too
+    text(&mut driver).set_wrap(EWrapMode::None);
+    text(&mut driver).set_max_lines(None);
+    text(&mut driver).set_text("a\r\nb\nc".to_string());
+    assert_eq!(text(&mut driver).get_line_count(), 3);
+}
+
+#[test]
+fn test_measure() {
+    let mut driver = create(&"word ".repeat(20), |t| t.set_wrap(EWrapMode::Word));
+
+    let one = text(&mut driver).measure(None);
+    let wrapped = text(&mut driver).measure(Some(100));
+    assert!(wrapped.width <= 100);
+    assert!(wrapped.height > one.height);
+
+    text(&mut driver).set_line_spacing(2.);
+    let spaced = text(&mut driver).measure(Some(100));
+    assert!(spaced.height >= wrapped.height * 2 - 1);
+}
+
+#[test]
+fn test_ellipsis() {
+    let long = "0123456789".repeat(10);
+    let mut driver = create(&long, |t| {
+        t.ctrl.styles.default.overflow = EOverflow::Ellipsis;
+    });
+
+    let shown = text(&mut driver).get_shown_line(0).unwrap().to_string();
+    assert!(shown.starts_with("0123") && shown.ends_with('…'));
+    assert!(shown.chars().count() < long.len());
+
+    text(&mut driver).ctrl.styles.default.overflow = EOverflow::EllipsisMiddle;
+    text(&mut driver).set_max_lines(None);
+    let shown = text(&mut driver).get_shown_line(0).unwrap().to_string();
+    assert!(shown.starts_with("0123") && shown.ends_with("789"));
+    assert!(shown.contains('…'));
+
+    // short rows stay as they are, hidden rows mark the last one shown
+    text(&mut driver).ctrl.styles.default.overflow = EOverflow::Ellipsis;
+    text(&mut driver).set_text("a\nb\nc".to_string());
+    assert_eq!(text(&mut driver).get_shown_line(0), Some("a"));
+    text(&mut driver).set_max_lines(Some(2));
+    assert_eq!(text(&mut driver).get_shown_line(1), Some("b…"));
+}
@@ -0,0 +1,138 @@
+use as_any::Downcast;
+use simple_ui::{
+    clipboard::{self, MemoryClipboard},
+    input::{EKey, KeyModifiers},
+    testing::UIDriver,
+    ui::{ui_text_area::TextPos, *},
+    ISize, Point, ScaleDpi,
+};
+use std::{cell::Cell, rc::Rc};
+
+fn create(text: &str, changes: &Rc<Cell<u32>>) -> UIDriver {
+    clipboard::set_clipboard(Box::<MemoryClipboard>::default());
+
+    let mut root = Container::new(LayoutVertical::new());
+    root.ctrl.inner.name = "Root".to_string();
+
+    let mut area = TextArea::new(14);
+    area.ctrl.inner.name = "Area".to_string();
+    area.set_text(text);
+    {
+        let changes = changes.clone();
+        area.ctrl
+            .inner
+            .on_change(move |_, _| changes.set(changes.get() + 1));
+    }
+    root.append_child(Box::new(area));
+
+    UIDriver::new(root, ISize::new(200, 100), ScaleDpi::new(1.))
+}
+
+fn area(driver: &mut UIDriver) -> &mut TextArea {
+    let w = driver.window();
+    w.get_mut_ctrl_by_name("Area")
+        .unwrap()
+        .downcast_mut::<TextArea>()
+        .unwrap()
+}
+
+fn command() -> KeyModifiers {
+    KeyModifiers {
+        ctrl: !cfg!(target_os = "macos"),
+        logo: cfg!(target_os = "macos"),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_type_lines_and_undo() {
+    let changes = Rc::new(Cell::new(0));
+    let mut driver = create("", &changes);
+
+    driver.type_text("Area", "one");
+    driver.press_key(EKey::Enter);
+    driver.window().inject_text("two");
+    driver.assert_text("Area", "one\ntwo");
+    assert_eq!(area(&mut driver).get_caret(), TextPos::new(1, 3));
+    assert_eq!(changes.get(), 7);
+
+    // up keeps the column, left at the line start goes to the line above
+    driver.press_key(EKey::Up);
+    assert_eq!(area(&mut driver).get_caret(), TextPos::new(0, 3));
+    driver.press_key(EKey::Down).press_key(EKey::Home);
+    driver.press_key(EKey::Left);
+    assert_eq!(area(&mut driver).get_caret(), TextPos::new(0, 3));
+    driver.press_key(EKey::Right).press_key(EKey::Backspace);
+    driver.assert_text("Area", "onetwo");
+
+    driver.press_key_with(EKey::Char('z'), command());
+    driver.assert_text("Area", "one\ntwo");
+    driver.press_key_with(EKey::Char('z'), command());
+    driver.assert_text("Area", "one\n");
+    driver.press_key_with(EKey::Char('y'), command());
+    driver.assert_text("Area", "one\ntwo");
+}
+
+#[test]
+fn test_select_copy_paste() {
+    let changes = Rc::new(Cell::new(0));
+    let mut driver = create("alpha\nbeta\ngamma", &changes);
+
+    driver.click("Area");
+    area(&mut driver).set_selection(TextPos::new(0, 2), TextPos::new(1, 2));
+    driver.press_key_with(EKey::Char('c'), command());
+    assert_eq!(clipboard::get_text().as_deref(), Some("pha\nbe"));
+
+    let shift = KeyModifiers {
+        shift: true,
+        ..Default::default()
+    };
+    area(&mut driver).set_selection(TextPos::new(2, 0), TextPos::new(2, 0));
+    driver.press_key_with(EKey::End, shift);
+    driver.press_key_with(EKey::Char('v'), command());
+    driver.assert_text("Area", "alpha\nbeta\npha\nbe");
+    assert_eq!(area(&mut driver).get_caret(), TextPos::new(3, 2));
+
+    area(&mut driver).set_read_only(true);
+    driver.window().inject_text("x");
+    driver.press_key(EKey::Backspace);
+    driver.assert_text("Area", "alpha\nbeta\npha\nbe");
+    // nothing selected by the refused delete
+    assert!(area(&mut driver).get_selection().is_empty());
+}
+
+#[test]
+fn test_wrap_and_scroll() {
+    let changes = Rc::new(Cell::new(0));
+    let long = "word ".repeat(40);
+    let mut driver = create(&long, &changes);
+
+    let rows = area(&mut driver).get_row_count();
+    assert!(rows > 1, "a long line wraps");
+    area(&mut driver).set_wrap(false);
+    assert_eq!(area(&mut driver).get_row_count(), 1);
+    area(&mut driver).set_wrap(true);
+    assert_eq!(area(&mut driver).get_row_count(), rows);
+
+    // tens of thousands of lines, only the visible rows are drawn
+    let text = (0..30000)
+        .map(|i| format!("line {}", i))
+        .collect::<Vec<_>>()
+        .join("\n");
+    area(&mut driver).set_text(&text);
+    assert_eq!(area(&mut driver).get_line_count(), 30000);
+
+    driver.hover("Area");
+    driver.window().inject_wheel(Point::new(0., -3.));
+    assert!(area(&mut driver).get_scroll_y() > 0.);
+
+    driver.click("Area");
+    driver.press_key_with(EKey::End, command());
+    assert_eq!(area(&mut driver).get_caret(), TextPos::new(29999, 10));
+    driver.window().inject_text("!");
+    assert_eq!(area(&mut driver).get_line(29999), Some("line 29999!"));
+
+    let scroll_y = area(&mut driver).get_scroll_y();
+    driver.window().inject_wheel(Point::new(0., -3.));
+    assert_eq!(area(&mut driver).get_scroll_y(), scroll_y, "at the bottom");
+}
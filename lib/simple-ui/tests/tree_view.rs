@@ -0,0 +1,161 @@
+use as_any::Downcast;
+use simple_ui::{input::EKey, testing::UIDriver, ui::*, IPoint, IRect, ISize, ScaleDpi};
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+// `roots` top level nodes, nodes below 100 have the children `n1`, `n2` and `n3`
+struct Source {
+    roots: u64,
+    loads: Rc<Cell<u32>>,
+    created: Rc<Cell<u32>>,
+}
+
+impl TTreeSource for Source {
+    fn get_children(&mut self, parent: Option<u64>) -> Vec<u64> {
+        self.loads.set(self.loads.get() + 1);
+        match parent {
+            None => (1..=self.roots).collect(),
+            Some(n) => (1..=3).map(|i| n * 10 + i).collect(),
+        }
+    }
+    fn has_children(&self, node: u64) -> bool {
+        node < 100
+    }
+    fn get_text(&self, node: u64) -> String {
+        format!("Node{}", node)
+    }
+    fn get_row_height(&self) -> i32 {
+        20
+    }
+    fn create_row(&mut self) -> Box<dyn TCtrl> {
+        self.created.set(self.created.get() + 1);
+        Box::new(Ctrl::default())
+    }
+    fn update_row(&mut self, row: &mut dyn TCtrl, node: u64) {
+        row.get_mut_inner().name = format!("Node{}", node);
+    }
+}
+
+#[derive(Default)]
+struct Record {
+    loads: Rc<Cell<u32>>,
+    created: Rc<Cell<u32>>,
+}
+
+fn create(roots: u64, record: &Record) -> UIDriver {
+    let mut root = Container::new(LayoutVertical::new());
+    root.ctrl.inner.name = "Root".to_string();
+
+    let mut tree = TreeView::new(Box::new(Source {
+        roots,
+        loads: record.loads.clone(),
+        created: record.created.clone(),
+    }));
+    tree.list.ctrl.inner.name = "Tree".to_string();
+    root.append_child(Box::new(tree));
+
+    UIDriver::new(root, ISize::new(200, 100), ScaleDpi::new(1.))
+}
+
+fn tree(driver: &mut UIDriver) -> &mut TreeView {
+    let w = driver.window();
+    w.get_mut_ctrl_by_name("Tree")
+        .unwrap()
+        .downcast_mut::<TreeView>()
+        .unwrap()
+}
+
+#[test]
+fn test_expand() {
+    let record = Record::default();
+    let mut driver = create(3, &record);
+
+    // the rows start after the arrow
+    driver.assert_rect("Node1", IRect::new(16, 0, 200, 20));
+    assert_eq!(record.loads.get(), 1);
+
+    driver.window().inject_click(IPoint::new(8, 10));
+    assert!(tree(&mut driver).is_expanded(1));
+    assert_eq!(record.loads.get(), 2);
+    assert_eq!(tree(&mut driver).get_row_count(), 6);
+    // the bar takes 10 pixels once the rows overflow
+    driver
+        .assert_rect("Node11", IRect::new(32, 20, 190, 40))
+        .assert_rect("Node2", IRect::new(16, 80, 190, 100));
+    assert!(tree(&mut driver).get_selected_nodes().is_empty());
+
+    // the current row moves up to the collapsed parent
+    driver.click("Node12");
+    assert_eq!(tree(&mut driver).get_selected_nodes(), vec![12]);
+    assert!(tree(&mut driver).collapse(1));
+    assert!(tree(&mut driver).get_selected_nodes().is_empty());
+    assert_eq!(tree(&mut driver).get_current_node(), Some(1));
+    assert!(driver.find("Node11").is_none());
+
+    // loaded once, the children are kept
+    assert!(tree(&mut driver).expand(1));
+    assert_eq!(record.loads.get(), 2);
+}
+
+#[test]
+fn test_indent() {
+    let record = Record::default();
+    let mut driver = create(3, &record);
+
+    // rows already on screen take the new indent
+    tree(&mut driver).indent = 24;
+    tree(&mut driver).update(&ScaleDpi::new(1.));
+    driver
+        .assert_rect("Node1", IRect::new(24, 0, 200, 20))
+        .assert_rect("Node3", IRect::new(24, 40, 200, 60));
+}
+
+#[test]
+fn test_keys() {
+    let record = Record::default();
+    let mut driver = create(3, &record);
+
+    driver.click("Node2");
+    driver.press_key(EKey::Right);
+    assert!(tree(&mut driver).is_expanded(2));
+    assert_eq!(tree(&mut driver).get_current_node(), Some(2));
+
+    driver.press_key(EKey::Right);
+    assert_eq!(tree(&mut driver).get_current_node(), Some(21));
+    driver.press_key(EKey::Down);
+    assert_eq!(tree(&mut driver).get_selected_nodes(), vec![22]);
+
+    driver.press_key(EKey::Left);
+    assert_eq!(tree(&mut driver).get_current_node(), Some(2));
+    driver.press_key(EKey::Left);
+    assert!(!tree(&mut driver).is_expanded(2));
+    assert_eq!(tree(&mut driver).get_row_count(), 3);
+}
+
+#[test]
+fn test_type_ahead() {
+    let record = Record::default();
+    let mut driver = create(3, &record);
+
+    driver.click("Node1");
+    driver.window().inject_text("node3");
+    assert_eq!(tree(&mut driver).get_current_node(), Some(3));
+
+    // a pause starts over
+    driver.window().inject_elapse(Duration::from_secs(2));
+    driver.window().inject_text("node2");
+    assert_eq!(tree(&mut driver).get_selected_nodes(), vec![2]);
+}
+
+#[test]
+fn test_virtual_rows() {
+    let record = Record::default();
+    let mut driver = create(50_000, &record);
+
+    assert_eq!(record.created.get(), 7);
+    driver.click("Node1");
+    driver.press_key(EKey::End);
+    assert_eq!(tree(&mut driver).get_current_node(), Some(50_000));
+    driver.assert_visible("Node50000", true);
+    assert_eq!(record.created.get(), 7);
+    assert_eq!(record.loads.get(), 1);
+}
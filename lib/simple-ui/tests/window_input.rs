@@ -0,0 +1,115 @@
+use simple_ui::{
+    input::{EKey, KeyInput},
+    ui::*,
+    window::TWindowDelegate,
+    EUnit, IPoint, ISize, ScaleDpi, Window, WindowBuilder,
+};
+use std::{cell::RefCell, rc::Rc};
+
+#[derive(Default)]
+struct Record {
+    moved: Vec<IPoint>,
+    down: Vec<(IPoint, String)>,
+    up: Vec<IPoint>,
+    keys: Vec<KeyInput>,
+    text: String,
+    resized: Vec<ISize>,
+}
+
+struct RecordDelegate {
+    record: Rc<RefCell<Record>>,
+}
+
+impl TWindowDelegate for RecordDelegate {
+    fn on_resize(&mut self, size: ISize) {
+        self.record.borrow_mut().resized.push(size);
+    }
+    fn on_mouse_moved(&mut self, _window: &mut Window, pos: IPoint) {
+        self.record.borrow_mut().moved.push(pos);
+    }
+    fn on_lbtn_down(&mut self, window: &mut Window, pos: IPoint) {
+        let name = window
+            .get_ctrl_by_pos(&pos)
+            .unwrap()
+            .get_inner()
+            .name
+            .clone();
+        self.record.borrow_mut().down.push((pos, name));
+    }
+    fn on_lbtn_up(&mut self, _window: &mut Window, pos: IPoint) {
+        self.record.borrow_mut().up.push(pos);
+    }
+    fn on_key(&mut self, _window: &mut Window, key: &KeyInput) {
+        self.record.borrow_mut().keys.push(*key);
+    }
+    fn on_char(&mut self, _window: &mut Window, c: char) {
+        self.record.borrow_mut().text.push(c);
+    }
+}
+
+fn create_window(record: Rc<RefCell<Record>>) -> Window {
+    let mut root = Container::new(LayoutHorizontal::new());
+    root.ctrl.inner.name = "Root".to_string();
+    {
+        let mut left = Ctrl::default();
+        left.inner.name = "Left".to_string();
+        left.inner.size.width = Some(EUnit::Dp(50));
+        root.append_child(Box::new(left));
+    }
+    {
+        let mut right = Ctrl::default();
+        right.inner.name = "Right".to_string();
+        root.append_child(Box::new(right));
+    }
+
+    let mut builder = WindowBuilder::new_headless(ISize::new(200, 100), ScaleDpi::new(2.));
+    builder.set_root_container(root);
+    builder.set_delegate(Box::new(RecordDelegate { record }));
+    builder.build_headless()
+}
+
+#[test]
+fn test_inject_mouse() {
+    let record = Rc::new(RefCell::new(Record::default()));
+    let mut window = create_window(record.clone());
+
+    window.inject_click(IPoint::new(20, 20));
+    window.inject_click(IPoint::new(120, 20));
+
+    let record = record.borrow();
+    assert_eq!(
+        record.moved,
+        vec![IPoint::new(20, 20), IPoint::new(120, 20)]
+    );
+    assert_eq!(
+        record.down,
+        vec![
+            (IPoint::new(20, 20), "Left".to_string()),
+            (IPoint::new(120, 20), "Right".to_string())
+        ]
+    );
+    assert_eq!(record.up.len(), 2);
+}
+
+#[test]
+fn test_inject_key_and_resize() {
+    let record = Rc::new(RefCell::new(Record::default()));
+    let mut window = create_window(record.clone());
+
+    window.inject_key(EKey::Tab);
+    window.inject_text("ab");
+    window.inject_resize(ISize::new(300, 100));
+
+    assert!(window.is_redraw_pending());
+    assert!(window.draw_pending());
+    assert_eq!(window.snapshot().unwrap().width(), 600);
+
+    let record = record.borrow();
+    assert_eq!(record.keys.len(), 2);
+    assert!(record.keys[0].pressed && !record.keys[1].pressed);
+    assert_eq!(record.text, "ab");
+    assert_eq!(
+        record.resized,
+        vec![ISize::new(400, 200), ISize::new(600, 200)]
+    );
+}